@@ -0,0 +1,193 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::NormalizedArguments;
+
+/// Placeholder conocido: nombre, descripción y si su valor es un secreto.
+pub struct PlaceholderInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Credenciales: el launcher las sustituye localmente y nunca las envía.
+    pub secret: bool,
+}
+
+const fn info(name: &'static str, description: &'static str, secret: bool) -> PlaceholderInfo {
+    PlaceholderInfo { name, description, secret }
+}
+
+/// Placeholders que usan los JSON de Mojang (desde las alpha hasta hoy).
+pub const CATALOG: &[PlaceholderInfo] = &[
+    info("auth_player_name", "Nombre del jugador", false),
+    info("auth_uuid", "UUID de la cuenta, sin guiones", false),
+    info("auth_access_token", "Token de acceso de la sesión", true),
+    info("auth_session", "Sesión antigua (`token:<access_token>:<uuid>`), versiones legacy", true),
+    info("auth_xuid", "XUID de la cuenta Xbox, para telemetría de Microsoft", true),
+    info("clientid", "Id de cliente del launcher, para telemetría de Microsoft", false),
+    info("user_type", "Tipo de cuenta: `msa`, `mojang` o `legacy`", false),
+    info("user_properties", "Propiedades de Twitch en JSON; `{}` si no hay", false),
+    info("version_name", "Id de la versión lanzada", false),
+    info("version_type", "Tipo de la versión (`release`, `snapshot`...) o la marca del launcher", false),
+    info("game_directory", "Directorio de juego (saves, mods, opciones)", false),
+    info("assets_root", "Raíz de los assets (`<.minecraft>/assets`)", false),
+    info("assets_index_name", "Id del asset index", false),
+    info("game_assets", "Assets legacy: `assets/virtual/<index>` o `resources`", false),
+    info("natives_directory", "Directorio con los natives extraídos", false),
+    info("library_directory", "Raíz de las librerías (`<.minecraft>/libraries`)", false),
+    info("classpath", "Librerías y client jar unidos con el separador del SO", false),
+    info("classpath_separator", "`;` en Windows, `:` en el resto", false),
+    info("launcher_name", "Nombre del launcher", false),
+    info("launcher_version", "Versión del launcher", false),
+    info("primary_jar_name", "Nombre del client jar", false),
+    info("resolution_width", "Ancho de la ventana", false),
+    info("resolution_height", "Alto de la ventana", false),
+    info("quickPlayPath", "Archivo de registro de Quick Play", false),
+    info("quickPlaySingleplayer", "Mundo que abrir al iniciar", false),
+    info("quickPlayMultiplayer", "Servidor al que conectar al iniciar", false),
+    info("quickPlayRealms", "Realm al que conectar al iniciar", false),
+    info("path", "Ruta del archivo de configuración de log4j", false),
+];
+
+pub fn lookup(name: &str) -> Option<&'static PlaceholderInfo> {
+    CATALOG.iter().find(|p| p.name == name)
+}
+
+/// Trozo de un argumento: texto literal o un placeholder `${...}`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Token {
+    Literal { value: String },
+    Placeholder { name: String },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TemplateArgument {
+    pub raw: String,
+    pub tokens: Vec<Token>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlaceholderDoc {
+    /// `null` si el placeholder no está en el catálogo.
+    pub description: Option<String>,
+    pub secret: bool,
+}
+
+/// Argumentos de una versión separados en literales y placeholders.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArgumentTemplate {
+    pub game: Vec<TemplateArgument>,
+    pub jvm: Vec<TemplateArgument>,
+    /// Cada placeholder que aparece, con su documentación.
+    pub placeholders: BTreeMap<String, PlaceholderDoc>,
+}
+
+/// Argumentos con los valores del cliente ya sustituidos.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExpandedArguments {
+    pub game: Vec<String>,
+    pub jvm: Vec<String>,
+    /// Argumentos de la JVM, clase principal y argumentos de juego, en el
+    /// orden en que se pasan a `java`.
+    pub command_line: Vec<String>,
+    /// Placeholders que siguen en los argumentos: los secretos y los que no
+    /// se enviaron.
+    pub unresolved: Vec<String>,
+}
+
+pub fn tokenize(arg: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut rest = arg;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start + 2..].find('}') else {
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Literal { value: rest[..start].to_string() });
+        }
+        tokens.push(Token::Placeholder { name: rest[start + 2..start + 2 + len].to_string() });
+        rest = &rest[start + 3 + len..];
+    }
+    if !rest.is_empty() {
+        tokens.push(Token::Literal { value: rest.to_string() });
+    }
+    tokens
+}
+
+/// Nombres de los placeholders `${...}` de un argumento.
+pub fn placeholders_in(arg: &str) -> Vec<String> {
+    tokenize(arg)
+        .into_iter()
+        .filter_map(|token| match token {
+            Token::Placeholder { name } => Some(name),
+            Token::Literal { .. } => None,
+        })
+        .collect()
+}
+
+pub fn template(arguments: &NormalizedArguments) -> ArgumentTemplate {
+    let split = |args: &[String]| -> Vec<TemplateArgument> {
+        args.iter().map(|raw| TemplateArgument { raw: raw.clone(), tokens: tokenize(raw) }).collect()
+    };
+    let placeholders = arguments
+        .game
+        .iter()
+        .chain(&arguments.jvm)
+        .flat_map(|arg| placeholders_in(arg))
+        .map(|name| {
+            let known = lookup(&name);
+            let doc = PlaceholderDoc {
+                description: known.map(|p| p.description.to_string()),
+                secret: known.is_some_and(|p| p.secret),
+            };
+            (name, doc)
+        })
+        .collect();
+
+    ArgumentTemplate {
+        game: split(&arguments.game),
+        jvm: split(&arguments.jvm),
+        placeholders,
+    }
+}
+
+/// Sustituye los placeholders con `values`. Los secretos del catálogo nunca
+/// se sustituyen: devuelve su nombre como error si vienen en `values`.
+pub fn expand(
+    arguments: &NormalizedArguments,
+    main_class: Option<&str>,
+    values: &HashMap<String, String>,
+) -> Result<ExpandedArguments, String> {
+    if let Some(secret) = values.keys().find(|name| lookup(name).is_some_and(|p| p.secret)) {
+        return Err(secret.clone());
+    }
+
+    let mut unresolved = vec![];
+    let mut apply = |args: &[String]| -> Vec<String> {
+        args.iter()
+            .map(|arg| {
+                tokenize(arg)
+                    .into_iter()
+                    .map(|token| match token {
+                        Token::Literal { value } => value,
+                        Token::Placeholder { name } => match values.get(&name) {
+                            Some(value) => value.clone(),
+                            None => {
+                                let raw = format!("${{{}}}", name);
+                                unresolved.push(name);
+                                raw
+                            }
+                        },
+                    })
+                    .collect()
+            })
+            .collect()
+    };
+
+    let jvm = apply(&arguments.jvm);
+    let game = apply(&arguments.game);
+    unresolved.sort();
+    unresolved.dedup();
+    let command_line = jvm.iter().cloned().chain(main_class.map(String::from)).chain(game.iter().cloned()).collect();
+    Ok(ExpandedArguments { game, jvm, command_line, unresolved })
+}
@@ -0,0 +1,96 @@
+use std::collections::HashSet;
+
+use serde_json::{Map, Value};
+
+/// Combina un JSON de versión personalizado (`inheritsFrom`) con su padre,
+/// siguiendo las reglas del launcher de Mojang:
+///
+/// - los campos escalares del hijo reemplazan a los del padre;
+/// - las librerías del hijo van primero y tapan a las del padre con el mismo
+///   `grupo:artefacto[:clasificador]`;
+/// - `arguments.game` y `arguments.jvm` se concatenan (padre y luego hijo);
+/// - `downloads` se combina por clave.
+///
+/// El resultado conserva el `inheritsFrom` del padre, si lo tiene, para poder
+/// resolver cadenas de herencia.
+pub fn merge_inherited(parent: &Value, child: &Value) -> Value {
+    let mut merged = parent.as_object().cloned().unwrap_or_default();
+    merged.remove("inheritsFrom");
+
+    let Some(child) = child.as_object() else {
+        return Value::Object(merged);
+    };
+
+    for (key, value) in child {
+        match key.as_str() {
+            "inheritsFrom" => {}
+            "libraries" => {
+                let libraries = merge_libraries(value, merged.get("libraries"));
+                merged.insert(key.clone(), libraries);
+            }
+            "arguments" => {
+                let arguments = merge_arguments(merged.get("arguments"), value);
+                merged.insert(key.clone(), arguments);
+            }
+            "downloads" => {
+                let mut downloads = merged.get("downloads").and_then(Value::as_object).cloned().unwrap_or_default();
+                if let Some(extra) = value.as_object() {
+                    downloads.extend(extra.clone());
+                }
+                merged.insert(key.clone(), Value::Object(downloads));
+            }
+            _ => {
+                merged.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    if let Some(inherits) = parent.get("inheritsFrom") {
+        merged.insert("inheritsFrom".to_string(), inherits.clone());
+    }
+
+    Value::Object(merged)
+}
+
+fn merge_libraries(child: &Value, parent: Option<&Value>) -> Value {
+    let mut seen = HashSet::new();
+    let mut result = vec![];
+
+    let child = child.as_array().into_iter().flatten();
+    let parent = parent.and_then(Value::as_array).into_iter().flatten();
+    for lib in child.chain(parent) {
+        let key = lib.get("name").and_then(Value::as_str).map(library_key);
+        if key.is_none_or(|k| seen.insert(k)) {
+            result.push(lib.clone());
+        }
+    }
+
+    Value::Array(result)
+}
+
+// `grupo:artefacto:versión[:clasificador]` sin la versión.
+fn library_key(name: &str) -> String {
+    let parts: Vec<&str> = name.split(':').collect();
+    match parts.as_slice() {
+        [group, artifact, _version, classifier, ..] => format!("{}:{}:{}", group, artifact, classifier),
+        [group, artifact, ..] => format!("{}:{}", group, artifact),
+        _ => name.to_string(),
+    }
+}
+
+fn merge_arguments(parent: Option<&Value>, child: &Value) -> Value {
+    let mut merged = Map::new();
+
+    for kind in ["game", "jvm"] {
+        let parent_args = parent.and_then(|p| p.get(kind)).and_then(Value::as_array);
+        let child_args = child.get(kind).and_then(Value::as_array);
+        if parent_args.is_none() && child_args.is_none() {
+            continue;
+        }
+
+        let combined = parent_args.into_iter().chain(child_args).flatten().cloned().collect();
+        merged.insert(kind.to_string(), Value::Array(combined));
+    }
+
+    Value::Object(merged)
+}
@@ -0,0 +1,14 @@
+pub mod arguments;
+pub mod inherit;
+pub mod maven;
+pub mod normalize;
+pub mod plan;
+pub mod platform;
+pub mod types;
+pub mod upstream;
+
+pub use inherit::merge_inherited;
+pub use normalize::{parse_version_json, rewrite_urls};
+pub use plan::download_plan;
+pub use platform::{bundle_version_json, Platform};
+pub use upstream::{fetch_version_json, fetch_version_manifest};
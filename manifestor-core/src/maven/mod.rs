@@ -0,0 +1,55 @@
+/// Coordenada Maven `grupo:artefacto:versión[:clasificador][@extensión]`, como
+/// el campo `name` de las librerías.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coordinate {
+    pub group: String,
+    pub artifact: String,
+    pub version: String,
+    pub classifier: Option<String>,
+    pub extension: String,
+}
+
+impl Coordinate {
+    pub fn parse(name: &str) -> Result<Self, &'static str> {
+        let (coords, extension) = match name.trim().split_once('@') {
+            Some((coords, ext)) if !ext.is_empty() => (coords, ext),
+            Some(_) => return Err("Extensión vacía tras '@'"),
+            None => (name.trim(), "jar"),
+        };
+
+        let parts: Vec<&str> = coords.split(':').collect();
+        let (group, artifact, version, classifier) = match parts.as_slice() {
+            [group, artifact, version] => (group, artifact, version, None),
+            [group, artifact, version, classifier] => (group, artifact, version, Some(classifier)),
+            _ => return Err("Se esperaba grupo:artefacto:versión[:clasificador]"),
+        };
+
+        let valid = |part: &str| !part.is_empty() && !part.contains(['/', '\\']) && part != "..";
+        if ![*group, *artifact, *version].into_iter().chain(classifier.copied()).all(valid) || !valid(extension) {
+            return Err("Coordenada Maven inválida");
+        }
+
+        Ok(Self {
+            group: group.to_string(),
+            artifact: artifact.to_string(),
+            version: version.to_string(),
+            classifier: classifier.map(|c| c.to_string()),
+            extension: extension.to_string(),
+        })
+    }
+
+    /// Ruta dentro del repositorio, p. ej.
+    /// `org/ow2/asm/asm/9.3/asm-9.3.jar` para `org.ow2.asm:asm:9.3`.
+    pub fn path(&self) -> String {
+        let file = match &self.classifier {
+            Some(classifier) => format!("{}-{}-{}.{}", self.artifact, self.version, classifier, self.extension),
+            None => format!("{}-{}.{}", self.artifact, self.version, self.extension),
+        };
+        format!("{}/{}/{}/{}", self.group.replace('.', "/"), self.artifact, self.version, file)
+    }
+
+    /// URL del artefacto en el repositorio `base` (con o sin `/` final).
+    pub fn url(&self, base: &str) -> String {
+        format!("{}/{}", base.trim_end_matches('/'), self.path())
+    }
+}
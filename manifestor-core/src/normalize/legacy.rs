@@ -0,0 +1,61 @@
+use crate::types::mojang::VersionJson;
+use crate::types::{AssetIndex, LegacyAssets, NormalizedArguments};
+
+pub(super) const JAVA_VERSION: u8 = 8;
+
+// Argumentos de juego de las primeras alpha, que no traen `minecraftArguments`.
+const DEFAULT_GAME_ARGUMENTS: &[&str] = &[
+    "${auth_player_name}",
+    "${auth_session}",
+    "--gameDir",
+    "${game_directory}",
+    "--assetsDir",
+    "${game_assets}",
+];
+
+// Lo que las versiones con `arguments.jvm` declaran y las legacy dan por supuesto.
+const DEFAULT_JVM_ARGUMENTS: &[&str] = &[
+    "-Djava.library.path=${natives_directory}",
+    "-Dminecraft.launcher.brand=${launcher_name}",
+    "-Dminecraft.launcher.version=${launcher_version}",
+    "-cp",
+    "${classpath}",
+];
+
+/// Id del asset index: `assetIndex.id` o, en JSONs muy antiguos, `assets`.
+fn asset_index_id<'a>(version_json: &'a VersionJson, asset_index: Option<&'a AssetIndex>) -> Option<&'a str> {
+    asset_index
+        .map(|index| index.id.as_str())
+        .filter(|id| !id.is_empty())
+        .or(version_json.assets.as_deref())
+}
+
+pub(super) fn is_legacy(version_json: &VersionJson, version_type: Option<&str>, asset_index: Option<&AssetIndex>) -> bool {
+    matches!(version_type, Some("old_alpha" | "old_beta"))
+        || matches!(asset_index_id(version_json, asset_index), Some("legacy" | "pre-1.6"))
+}
+
+/// `pre-1.6` va a `resources/` del juego; `legacy` a `assets/virtual/legacy`.
+pub(super) fn assets(version_json: &VersionJson, asset_index: Option<&AssetIndex>) -> Option<LegacyAssets> {
+    let index = asset_index_id(version_json, asset_index)?;
+    let map_to_resources = index == "pre-1.6";
+    Some(LegacyAssets {
+        index: index.to_string(),
+        map_to_resources,
+        is_virtual: !map_to_resources,
+        directory: if map_to_resources {
+            "resources".to_string()
+        } else {
+            format!("assets/virtual/{}", index)
+        },
+    })
+}
+
+/// Rellena los argumentos que las versiones legacy no declaran.
+pub(super) fn complete_arguments(arguments: NormalizedArguments) -> NormalizedArguments {
+    let to_vec = |args: &[&str]| args.iter().map(|a| a.to_string()).collect();
+    NormalizedArguments {
+        game: if arguments.game.is_empty() { to_vec(DEFAULT_GAME_ARGUMENTS) } else { arguments.game },
+        jvm: if arguments.jvm.is_empty() { to_vec(DEFAULT_JVM_ARGUMENTS) } else { arguments.jvm },
+    }
+}
@@ -0,0 +1,224 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+mod legacy;
+
+use crate::maven::Coordinate;
+use crate::types::mojang::{ArgumentJson, DownloadJson, LibraryJson, LoggingClientJson, VersionJson};
+use crate::types::{
+    AssetIndex, Checksums, Downloadable, ExtractionHint, HashAlgorithm, Library, LoggingConfig, LoggingFile, NativeLibrary,
+    NormalizedArguments, NormalizedVersion,
+};
+
+// Repositorio que usa el launcher oficial para librerías sin `url`.
+const DEFAULT_LIBRARY_REPOSITORY: &str = "https://libraries.minecraft.net/";
+
+/// Convierte el JSON de una versión de Mojang en una `NormalizedVersion`.
+pub fn parse_version_json(version_json: &Value) -> Result<NormalizedVersion, String> {
+    let version = VersionJson::deserialize(version_json).map_err(|e| format!("JSON de versión inválido: {}", e))?;
+    Ok(normalize(&version))
+}
+
+/// Convierte una versión ya deserializada en una `NormalizedVersion`.
+pub fn normalize(version: &VersionJson) -> NormalizedVersion {
+    let java_version = version
+        .java_version
+        .as_ref()
+        .and_then(|j| j.major_version)
+        .map(|v| v as u8);
+
+    let downloads = version.downloads.as_ref();
+    let client_jar = downloads.and_then(|d| d.client.as_ref()).and_then(downloadable);
+    let server_jar = downloads.and_then(|d| d.server.as_ref()).and_then(downloadable);
+
+    let asset_index = version.asset_index.as_ref().map(|a| AssetIndex {
+        id: a.id.clone().unwrap_or_default(),
+        url: a.url.clone().unwrap_or_default(),
+        sha1: a.sha1.clone().unwrap_or_default(),
+        size: a.size.unwrap_or(0),
+    });
+
+    let mut libraries = vec![];
+    let mut natives = vec![];
+    let mut requires_extraction = vec![];
+    for lib in &version.libraries {
+        add_library(lib, &mut libraries, &mut natives, &mut requires_extraction);
+    }
+
+    let arguments = if let Some(args) = &version.arguments {
+        NormalizedArguments {
+            game: flatten_arguments(&args.game),
+            jvm: flatten_arguments(&args.jvm),
+        }
+    } else if let Some(args) = &version.minecraft_arguments {
+        let game = args.split_whitespace().map(String::from).collect();
+        NormalizedArguments { game, jvm: vec![] }
+    } else {
+        NormalizedArguments { game: vec![], jvm: vec![] }
+    };
+
+    let legacy = legacy::is_legacy(version, version.version_type.as_deref(), asset_index.as_ref());
+    let legacy_assets = legacy::assets(version, asset_index.as_ref()).filter(|_| legacy);
+    let arguments = if legacy { legacy::complete_arguments(arguments) } else { arguments };
+    // Sin `javaVersion`, el launcher oficial usa Java 8 (jre-legacy).
+    let java_version = java_version.or(legacy.then_some(legacy::JAVA_VERSION));
+
+    let logging = version
+        .logging
+        .as_ref()
+        .and_then(|l| l.client.as_ref())
+        .and_then(logging_config);
+
+    NormalizedVersion {
+        id: version.id.clone(),
+        release_time: version.release_time.clone(),
+        version_type: version.version_type.clone(),
+        main_class: version.main_class.clone(),
+        compliance_level: version.compliance_level.map(|v| v as u8),
+        minimum_launcher_version: version.minimum_launcher_version.map(|v| v as u32),
+        java_version,
+        client_jar,
+        server_jar,
+        asset_index,
+        libraries,
+        natives,
+        arguments,
+        requires_extraction,
+        logging,
+        legacy,
+        legacy_assets,
+    }
+}
+
+fn downloadable(download: &DownloadJson) -> Option<Downloadable> {
+    Some(Downloadable {
+        url: download.url.clone()?,
+        sha1: download.sha1.clone()?,
+        size: download.size?,
+        checksums: checksums(&download.sha256, &download.sha512),
+    })
+}
+
+// Los checksums que no son sha1; los que no son hexadecimal válido se ignoran.
+fn checksums(sha256: &Option<String>, sha512: &Option<String>) -> Checksums {
+    [(HashAlgorithm::Sha256, sha256), (HashAlgorithm::Sha512, sha512)]
+        .into_iter()
+        .filter_map(|(alg, hash)| {
+            let hash = hash.as_deref()?.trim().to_ascii_lowercase();
+            (HashAlgorithm::for_hex(&hash) == Some(alg)).then_some((alg, hash))
+        })
+        .collect()
+}
+
+fn add_library(
+    lib: &LibraryJson,
+    libraries: &mut Vec<Library>,
+    natives: &mut Vec<NativeLibrary>,
+    requires_extraction: &mut Vec<ExtractionHint>,
+) {
+    let name = lib.name.clone();
+    let downloads = lib.downloads.as_ref();
+
+    if let Some(natives_map) = &lib.natives {
+        let classifiers = downloads.and_then(|d| d.classifiers.as_ref());
+        for classifier in natives_map.values() {
+            if let Some(native) = classifiers.and_then(|c| c.get(classifier))
+                && let (Some(url), Some(sha1), Some(size), Some(path)) = (&native.url, &native.sha1, native.size, &native.path)
+            {
+                natives.push(NativeLibrary {
+                    name: name.clone(),
+                    classifier: classifier.clone(),
+                    url: url.clone(),
+                    sha1: sha1.clone(),
+                    size,
+                    path: path.clone(),
+                    checksums: checksums(&native.sha256, &native.sha512),
+                });
+                requires_extraction.push(ExtractionHint {
+                    path: path.clone(),
+                    requires_extraction: lib.extract.as_ref().is_some_and(|e| e.exclude.is_some()),
+                });
+            }
+        }
+    } else if let Some(artifact) = downloads.and_then(|d| d.artifact.as_ref()) {
+        let path = artifact
+            .path
+            .clone()
+            .or_else(|| Coordinate::parse(&name).ok().map(|c| c.path()));
+        libraries.push(Library {
+            url: artifact.url.clone(),
+            sha1: artifact.sha1.clone(),
+            size: artifact.size,
+            path,
+            checksums: checksums(&artifact.sha256, &artifact.sha512),
+            name,
+        });
+    } else if let Ok(coordinate) = Coordinate::parse(&name) {
+        // Perfiles de loaders (Fabric, Quilt...): solo `name` y, a veces,
+        // la `url` base del repositorio Maven y el `sha1`.
+        let repository = lib.url.as_deref().unwrap_or(DEFAULT_LIBRARY_REPOSITORY);
+        libraries.push(Library {
+            url: Some(coordinate.url(repository)),
+            sha1: lib.sha1.clone(),
+            size: lib.size,
+            path: Some(coordinate.path()),
+            checksums: checksums(&lib.sha256, &lib.sha512),
+            name,
+        });
+    }
+}
+
+// Todos los valores, también los condicionados por reglas; el filtrado por
+// plataforma está en `platform`.
+fn flatten_arguments(args: &[ArgumentJson]) -> Vec<String> {
+    args.iter()
+        .flat_map(|arg| match arg {
+            ArgumentJson::Plain(value) => std::slice::from_ref(value),
+            ArgumentJson::Conditional { value, .. } => value.values(),
+            ArgumentJson::Other(_) => &[],
+        })
+        .cloned()
+        .collect()
+}
+
+fn logging_config(client: &LoggingClientJson) -> Option<LoggingConfig> {
+    let file = client.file.as_ref()?;
+    Some(LoggingConfig {
+        argument: client.argument.clone()?,
+        log_type: client.log_type.clone().unwrap_or_default(),
+        file: LoggingFile {
+            id: file.id.clone()?,
+            url: file.url.clone()?,
+            sha1: file.sha1.clone()?,
+            size: file.size.unwrap_or(0),
+        },
+    })
+}
+
+/// Reescribe todas las URLs de descarga de `version` con `rewrite`; las URLs
+/// para las que devuelve `None` se dejan igual.
+pub fn rewrite_urls<F: Fn(&str) -> Option<String>>(version: &mut NormalizedVersion, rewrite: F) {
+    let apply = |url: &mut String| {
+        if let Some(new) = rewrite(url) {
+            *url = new;
+        }
+    };
+
+    for jar in [&mut version.client_jar, &mut version.server_jar].into_iter().flatten() {
+        apply(&mut jar.url);
+    }
+    if let Some(index) = &mut version.asset_index {
+        apply(&mut index.url);
+    }
+    for lib in &mut version.libraries {
+        if let Some(url) = &mut lib.url {
+            apply(url);
+        }
+    }
+    for native in &mut version.natives {
+        apply(&mut native.url);
+    }
+    if let Some(logging) = &mut version.logging {
+        apply(&mut logging.file.url);
+    }
+}
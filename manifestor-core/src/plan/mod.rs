@@ -0,0 +1,50 @@
+use crate::types::{Checksums, DownloadItem, DownloadPlan, PlatformBundle};
+
+/// Lista de descargas de un bundle, con las rutas que usa el launcher
+/// oficial dentro de `.minecraft`. Las librerías sin URL no se incluyen.
+pub fn download_plan(bundle: &PlatformBundle) -> DownloadPlan {
+    let version = &bundle.version;
+    let mut items = vec![];
+    let none = Checksums::new();
+    let mut push = |kind: &str, path: String, url: &str, sha1: Option<&str>, size: Option<u64>, checksums: &Checksums| {
+        items.push(DownloadItem {
+            kind: kind.to_string(),
+            path,
+            url: url.to_string(),
+            sha1: sha1.map(String::from),
+            size,
+            checksums: checksums.clone(),
+        });
+    };
+
+    if let Some(jar) = &version.client_jar {
+        let path = format!("versions/{0}/{0}.jar", version.id);
+        push("client", path, &jar.url, Some(&jar.sha1), Some(jar.size), &jar.checksums);
+    }
+    for lib in &version.libraries {
+        if let (Some(url), Some(path)) = (&lib.url, &lib.path) {
+            push("library", format!("libraries/{}", path), url, lib.sha1.as_deref(), lib.size, &lib.checksums);
+        }
+    }
+    for native in &version.natives {
+        let path = format!("libraries/{}", native.path);
+        push("native", path, &native.url, Some(&native.sha1), Some(native.size), &native.checksums);
+    }
+    if let Some(index) = &version.asset_index {
+        let path = format!("assets/indexes/{}.json", index.id);
+        push("asset_index", path, &index.url, Some(&index.sha1), Some(index.size), &none);
+    }
+    if let Some(logging) = &version.logging {
+        let file = &logging.file;
+        let path = format!("assets/log_configs/{}", file.id);
+        push("logging", path, &file.url, Some(&file.sha1), Some(file.size), &none);
+    }
+
+    let total_size = items.iter().filter_map(|item| item.size).sum();
+    DownloadPlan {
+        version_id: version.id.clone(),
+        platform: bundle.platform.clone(),
+        items,
+        total_size,
+    }
+}
@@ -0,0 +1,228 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::arguments::placeholders_in;
+use crate::maven::Coordinate;
+use crate::normalize::normalize;
+use crate::types::mojang::{ArgumentJson, LibraryJson, Rule, VersionJson};
+use crate::types::{JavaRuntime, PlatformBundle};
+
+/// Sistema operativo con los nombres que usan las reglas de Mojang.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Os {
+    Windows,
+    Linux,
+    Osx,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arch {
+    X64,
+    X86,
+    Arm64,
+}
+
+/// Plataforma de destino de un bundle, p. ej. `windows-x64` o `osx-arm64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Platform {
+    pub os: Os,
+    pub arch: Arch,
+}
+
+impl Os {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Os::Windows => "windows",
+            Os::Linux => "linux",
+            Os::Osx => "osx",
+        }
+    }
+}
+
+impl Arch {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Arch::X64 => "x64",
+            Arch::X86 => "x86",
+            Arch::Arm64 => "arm64",
+        }
+    }
+}
+
+impl Platform {
+    /// Acepta `<os>-<arch>`; `macos` y `amd64`/`x86_64`/`aarch64` como alias.
+    pub fn parse(raw: &str) -> Result<Self, &'static str> {
+        let (os, arch) = raw.split_once('-').ok_or("Plataforma inválida: se esperaba <os>-<arch>")?;
+        let os = match os.to_ascii_lowercase().as_str() {
+            "windows" => Os::Windows,
+            "linux" => Os::Linux,
+            "osx" | "macos" => Os::Osx,
+            _ => return Err("Sistema operativo desconocido: windows, linux u osx"),
+        };
+        let arch = match arch.to_ascii_lowercase().as_str() {
+            "x64" | "amd64" | "x86_64" => Arch::X64,
+            "x86" | "i386" => Arch::X86,
+            "arm64" | "aarch64" => Arch::Arm64,
+            _ => return Err("Arquitectura desconocida: x64, x86 o arm64"),
+        };
+        Ok(Self { os, arch })
+    }
+
+    /// Todas las combinaciones de SO y arquitectura.
+    pub fn all() -> impl Iterator<Item = Platform> {
+        [Os::Windows, Os::Linux, Os::Osx]
+            .into_iter()
+            .flat_map(|os| [Arch::X64, Arch::X86, Arch::Arm64].into_iter().map(move |arch| Platform { os, arch }))
+    }
+
+    pub fn id(&self) -> String {
+        format!("{}-{}", self.os.as_str(), self.arch.as_str())
+    }
+
+    /// Clave de la plataforma en el manifest de runtimes de Java de Mojang.
+    pub fn java_runtime_key(&self) -> &'static str {
+        match (self.os, self.arch) {
+            (Os::Windows, Arch::X64) => "windows-x64",
+            (Os::Windows, Arch::X86) => "windows-x86",
+            (Os::Windows, Arch::Arm64) => "windows-arm64",
+            (Os::Linux, Arch::X86) => "linux-i386",
+            (Os::Linux, _) => "linux",
+            (Os::Osx, Arch::Arm64) => "mac-os-arm64",
+            (Os::Osx, _) => "mac-os",
+        }
+    }
+
+    // Valor de `${arch}` en los clasificadores de natives antiguos.
+    fn bits(&self) -> &'static str {
+        if self.arch == Arch::X86 { "32" } else { "64" }
+    }
+}
+
+/// Evalúa una lista de `rules` como el launcher oficial: sin reglas se
+/// permite; con reglas, gana la última que coincide y, si ninguna, se deniega.
+/// Las reglas con `features` (demo, resolución personalizada, quick play...)
+/// nunca coinciden: un bundle describe el lanzamiento por defecto.
+pub fn rules_allow(rules: Option<&[Rule]>, platform: &Platform) -> bool {
+    let Some(rules) = rules else {
+        return true;
+    };
+
+    let mut allowed = false;
+    for rule in rules {
+        if rule_matches(rule, platform) {
+            allowed = rule.action == "allow";
+        }
+    }
+    allowed
+}
+
+fn rule_matches(rule: &Rule, platform: &Platform) -> bool {
+    if rule.features.as_ref().is_some_and(|f| !f.is_empty()) {
+        return false;
+    }
+    let Some(os) = &rule.os else {
+        return true;
+    };
+    if let Some(name) = &os.name
+        && name != platform.os.as_str()
+    {
+        return false;
+    }
+    // Mojang solo usa `x86` para distinguir la JVM de 32 bits. `os.version`
+    // (una regex sobre la versión del SO) no se puede conocer desde aquí y se
+    // da por buena: solo aparece para Windows 10 y posteriores.
+    if let Some(arch) = &os.arch
+        && arch != platform.arch.as_str()
+    {
+        return false;
+    }
+    true
+}
+
+/// Normaliza el JSON de una versión para una única plataforma: aplica las
+/// reglas de librerías y argumentos, elige los natives que corresponden y
+/// adjunta el runtime de Java.
+pub fn bundle_version_json(version_json: &Value, platform: &Platform) -> Result<PlatformBundle, String> {
+    let mut parsed = VersionJson::deserialize(version_json).map_err(|e| format!("JSON de versión inválido: {}", e))?;
+    filter_for_platform(&mut parsed, platform);
+    let version = normalize(&parsed);
+
+    let component = parsed
+        .java_version
+        .as_ref()
+        .and_then(|j| j.component.clone())
+        .unwrap_or_else(|| {
+            let fallback = if version.java_version.unwrap_or(8) <= 8 { "jre-legacy" } else { "java-runtime-alpha" };
+            fallback.to_string()
+        });
+    let java_runtime = JavaRuntime {
+        component,
+        major_version: version.java_version.unwrap_or(8),
+        platform: platform.java_runtime_key().to_string(),
+    };
+
+    let mut placeholders: Vec<String> = version
+        .arguments
+        .game
+        .iter()
+        .chain(&version.arguments.jvm)
+        .flat_map(|arg| placeholders_in(arg))
+        .collect();
+    placeholders.sort();
+    placeholders.dedup();
+
+    Ok(PlatformBundle {
+        platform: platform.id(),
+        java_runtime,
+        placeholders,
+        version,
+    })
+}
+
+fn filter_for_platform(version: &mut VersionJson, platform: &Platform) {
+    version
+        .libraries
+        .retain(|lib| rules_allow(lib.rules.as_deref(), platform) && native_artifact_matches(lib, platform));
+    for lib in &mut version.libraries {
+        pick_native(lib, platform);
+    }
+
+    if let Some(arguments) = &mut version.arguments {
+        for entries in [&mut arguments.game, &mut arguments.jvm] {
+            entries.retain(|entry| match entry {
+                ArgumentJson::Conditional { rules, .. } => rules_allow(Some(rules), platform),
+                _ => true,
+            });
+        }
+    }
+}
+
+// Desde LWJGL 3.3 los natives son librerías normales con clasificador
+// `natives-<os>[-<arch>]`, todas permitidas para el SO; se deja solo la de
+// la arquitectura pedida.
+fn native_artifact_matches(lib: &LibraryJson, platform: &Platform) -> bool {
+    let Some(classifier) = Coordinate::parse(&lib.name).ok().and_then(|c| c.classifier) else {
+        return true;
+    };
+    if !classifier.starts_with("natives-") {
+        return true;
+    }
+    match classifier.rsplit('-').next() {
+        Some("arm64") | Some("aarch_64") => platform.arch == Arch::Arm64,
+        Some("x86") => platform.arch == Arch::X86,
+        _ => platform.arch == Arch::X64,
+    }
+}
+
+// Deja en `natives` solo el SO pedido, con `${arch}` ya sustituido.
+fn pick_native(lib: &mut LibraryJson, platform: &Platform) {
+    let Some(natives) = &mut lib.natives else {
+        return;
+    };
+    let os = platform.os.as_str();
+    let chosen = natives.remove(os).map(|classifier| classifier.replace("${arch}", platform.bits()));
+    natives.clear();
+    if let Some(classifier) = chosen {
+        natives.insert(os.to_string(), classifier);
+    }
+}
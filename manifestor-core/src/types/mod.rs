@@ -0,0 +1,230 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+pub mod mojang;
+
+pub const MOJANG_URL: &str = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MinecraftVersion {
+    pub id: String,
+    #[serde(rename="sha1")]
+    pub hash: String,
+    pub release_time: String,
+    pub url: String,
+    #[serde(rename="type")]
+    pub version_type: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VersionManifest {
+    pub latest_release: String,
+    pub latest_snapshot: String,
+    pub versions: Vec<MinecraftVersion>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NormalizedVersion {
+    pub id: String,
+    pub release_time: Option<String>,
+    #[serde(rename="type")]
+    pub version_type: Option<String>,
+    pub main_class: Option<String>,
+    pub compliance_level: Option<u8>,
+    pub minimum_launcher_version: Option<u32>,
+    pub java_version: Option<u8>,
+    pub client_jar: Option<Downloadable>,
+    pub server_jar: Option<Downloadable>,
+    pub asset_index: Option<AssetIndex>,
+    pub libraries: Vec<Library>,
+    pub natives: Vec<NativeLibrary>,
+    pub arguments: NormalizedArguments,
+    pub requires_extraction: Vec<ExtractionHint>,
+    pub logging: Option<LoggingConfig>,
+    /// Versión anterior a 1.6 (old_alpha, old_beta o asset index `legacy`/`pre-1.6`).
+    #[serde(default)]
+    pub legacy: bool,
+    /// Cómo colocar los assets en una versión legacy.
+    pub legacy_assets: Option<LegacyAssets>,
+}
+
+/// Algoritmos de checksum que pueden traer los artefactos, del más débil al
+/// más fuerte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    pub const ALL: [HashAlgorithm; 3] = [HashAlgorithm::Sha1, HashAlgorithm::Sha256, HashAlgorithm::Sha512];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha1 => "sha1",
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Sha512 => "sha512",
+        }
+    }
+
+    /// Longitud del hash en hexadecimal.
+    pub fn hex_len(self) -> usize {
+        match self {
+            HashAlgorithm::Sha1 => 40,
+            HashAlgorithm::Sha256 => 64,
+            HashAlgorithm::Sha512 => 128,
+        }
+    }
+
+    /// El algoritmo de un hash hexadecimal, deducido de su longitud.
+    pub fn for_hex(hash: &str) -> Option<Self> {
+        if !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        Self::ALL.into_iter().find(|alg| alg.hex_len() == hash.len())
+    }
+}
+
+/// Checksums además del `sha1`, en hexadecimal y minúsculas (p. ej. el
+/// `sha256` que publican algunos mavens).
+pub type Checksums = BTreeMap<HashAlgorithm, String>;
+
+/// El checksum más fuerte disponible entre `sha1` y `checksums`.
+pub fn strongest_checksum<'a>(sha1: Option<&'a str>, checksums: &'a Checksums) -> Option<(HashAlgorithm, &'a str)> {
+    let others = checksums.iter().map(|(alg, hash)| (*alg, hash.as_str()));
+    sha1.map(|hash| (HashAlgorithm::Sha1, hash)).into_iter().chain(others).max_by_key(|(alg, _)| *alg)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Downloadable {
+    pub url: String,
+    pub sha1: String,
+    pub size: u64,
+    #[serde(default, skip_serializing_if = "Checksums::is_empty")]
+    pub checksums: Checksums,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AssetIndex {
+    pub id: String,
+    pub url: String,
+    pub sha1: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Library {
+    pub name: String,
+    pub url: Option<String>,
+    pub sha1: Option<String>,
+    pub size: Option<u64>,
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Checksums::is_empty")]
+    pub checksums: Checksums,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NativeLibrary {
+    pub name: String,
+    pub classifier: String,
+    pub url: String,
+    pub sha1: String,
+    pub size: u64,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Checksums::is_empty")]
+    pub checksums: Checksums,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExtractionHint {
+    pub path: String,
+    pub requires_extraction: bool,
+}
+
+/// Configuración de log4j para el cliente (`logging.client`).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LoggingConfig {
+    /// Argumento JVM con el placeholder `${path}`, p. ej. `-Dlog4j.configurationFile=${path}`.
+    pub argument: String,
+    #[serde(rename="type")]
+    pub log_type: String,
+    pub file: LoggingFile,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LoggingFile {
+    pub id: String,
+    pub url: String,
+    pub sha1: String,
+    pub size: u64,
+}
+
+/// Disposición de los assets que esperan las versiones anteriores a 1.6, que
+/// no leen `assets/objects` sino una copia con los nombres originales.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LegacyAssets {
+    /// Id del asset index (`legacy` o `pre-1.6`).
+    pub index: String,
+    /// Copiar cada asset a `resources/<nombre>` dentro del directorio de juego.
+    pub map_to_resources: bool,
+    /// Copiar cada asset a `assets/virtual/<index>/<nombre>`.
+    #[serde(rename="virtual")]
+    pub is_virtual: bool,
+    /// Directorio al que debe apuntar `${game_assets}`: relativo al de juego
+    /// si `map_to_resources`, si no relativo a la raíz de `.minecraft`.
+    pub directory: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NormalizedArguments {
+    pub game: Vec<String>,
+    pub jvm: Vec<String>,
+}
+
+/// Versión resuelta para una sola plataforma (`/version/{id}/bundle/{platform}`).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlatformBundle {
+    /// `<os>-<arch>`, p. ej. `windows-x64`.
+    pub platform: String,
+    #[serde(flatten)]
+    pub version: NormalizedVersion,
+    pub java_runtime: JavaRuntime,
+    /// Placeholders `${...}` que aparecen en los argumentos y el launcher debe sustituir.
+    pub placeholders: Vec<String>,
+}
+
+/// Runtime de Java que usa el launcher oficial para la versión.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JavaRuntime {
+    /// Componente del manifest de runtimes, p. ej. `java-runtime-gamma` o `jre-legacy`.
+    pub component: String,
+    pub major_version: u8,
+    /// Clave de la plataforma en ese manifest (`windows-x64`, `mac-os-arm64`...).
+    pub platform: String,
+}
+
+/// Archivos a descargar para lanzar una versión en una plataforma.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DownloadPlan {
+    pub version_id: String,
+    pub platform: String,
+    pub items: Vec<DownloadItem>,
+    /// Suma de los tamaños conocidos.
+    pub total_size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DownloadItem {
+    /// `client`, `library`, `native`, `asset_index` o `logging`.
+    pub kind: String,
+    /// Ruta relativa al directorio `.minecraft`.
+    pub path: String,
+    pub url: String,
+    pub sha1: Option<String>,
+    pub size: Option<u64>,
+    #[serde(default, skip_serializing_if = "Checksums::is_empty")]
+    pub checksums: Checksums,
+}
@@ -0,0 +1,173 @@
+//! Esquema del JSON de versión de Mojang, tal como llega. Los campos
+//! opcionales o con formas alternativas se aceptan sin fallar; la conversión a
+//! los tipos normalizados está en `normalize`.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Deserialize;
+use serde::de::IgnoredAny;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VersionJson {
+    pub id: String,
+    pub release_time: Option<String>,
+    #[serde(rename = "type")]
+    pub version_type: Option<String>,
+    pub main_class: Option<String>,
+    pub compliance_level: Option<u64>,
+    pub minimum_launcher_version: Option<u64>,
+    pub java_version: Option<JavaVersionJson>,
+    pub downloads: Option<Downloads>,
+    pub asset_index: Option<AssetIndexJson>,
+    /// Id del asset index en JSONs anteriores a `assetIndex`.
+    pub assets: Option<String>,
+    pub libraries: Vec<LibraryJson>,
+    pub arguments: Option<ArgumentsJson>,
+    /// Argumentos de juego en una sola cadena, antes de 1.13.
+    pub minecraft_arguments: Option<String>,
+    pub logging: Option<LoggingJson>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct JavaVersionJson {
+    pub component: Option<String>,
+    pub major_version: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Downloads {
+    pub client: Option<DownloadJson>,
+    pub server: Option<DownloadJson>,
+}
+
+/// Archivo descargable (`downloads.*`, artefactos y clasificadores).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DownloadJson {
+    pub path: Option<String>,
+    pub url: Option<String>,
+    pub sha1: Option<String>,
+    pub sha256: Option<String>,
+    pub sha512: Option<String>,
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AssetIndexJson {
+    pub id: Option<String>,
+    pub url: Option<String>,
+    pub sha1: Option<String>,
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LibraryJson {
+    pub name: String,
+    pub downloads: Option<LibraryDownloads>,
+    /// SO -> clasificador, que puede llevar `${arch}`.
+    pub natives: Option<BTreeMap<String, String>>,
+    pub extract: Option<ExtractJson>,
+    pub rules: Option<Vec<Rule>>,
+    /// Repositorio Maven base, en perfiles de loaders sin `downloads`.
+    pub url: Option<String>,
+    pub sha1: Option<String>,
+    pub sha256: Option<String>,
+    pub sha512: Option<String>,
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LibraryDownloads {
+    pub artifact: Option<DownloadJson>,
+    pub classifiers: Option<HashMap<String, DownloadJson>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ExtractJson {
+    pub exclude: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Rule {
+    /// `allow` o `disallow`.
+    pub action: String,
+    pub os: Option<OsRule>,
+    pub features: Option<HashMap<String, bool>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct OsRule {
+    pub name: Option<String>,
+    pub arch: Option<String>,
+    /// Regex sobre la versión del SO.
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ArgumentsJson {
+    pub game: Vec<ArgumentJson>,
+    pub jvm: Vec<ArgumentJson>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ArgumentJson {
+    Plain(String),
+    Conditional {
+        #[serde(default)]
+        rules: Vec<Rule>,
+        value: ArgumentValue,
+    },
+    /// Cualquier otra forma; se descarta.
+    Other(IgnoredAny),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ArgumentValue {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl ArgumentValue {
+    pub fn values(&self) -> &[String] {
+        match self {
+            ArgumentValue::One(value) => std::slice::from_ref(value),
+            ArgumentValue::Many(values) => values,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LoggingJson {
+    pub client: Option<LoggingClientJson>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LoggingClientJson {
+    pub argument: Option<String>,
+    #[serde(rename = "type")]
+    pub log_type: Option<String>,
+    pub file: Option<LoggingFileJson>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LoggingFileJson {
+    pub id: Option<String>,
+    pub url: Option<String>,
+    pub sha1: Option<String>,
+    pub size: Option<u64>,
+}
@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::types::{MinecraftVersion, VersionManifest};
+
+#[derive(Debug, Deserialize)]
+struct MojangVersion {
+    id: String,
+    url: String,
+    #[serde(rename = "sha1")]
+    hash: String,
+    #[serde(rename = "releaseTime")]
+    release_time: String,
+    #[serde(rename = "type")]
+    version_type: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MojangManifest {
+    latest: HashMap<String, String>,
+    versions: Vec<MojangVersion>,
+}
+
+/// Descarga el manifest de versiones desde `url` (normalmente `types::MOJANG_URL`).
+pub async fn fetch_version_manifest(client: &Client, url: &str) -> Result<VersionManifest, reqwest::Error> {
+    let resp = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<MojangManifest>()
+        .await?;
+
+    Ok(VersionManifest {
+        latest_release: resp.latest.get("release").cloned().unwrap_or_default(),
+        latest_snapshot: resp.latest.get("snapshot").cloned().unwrap_or_default(),
+        versions: resp
+            .versions
+            .into_iter()
+            .map(|v| MinecraftVersion {
+                id: v.id,
+                hash: v.hash,
+                url: v.url,
+                release_time: v.release_time,
+                version_type: v.version_type,
+            })
+            .collect(),
+    })
+}
+
+/// Descarga el JSON crudo de una versión a partir de su URL en el manifest.
+pub async fn fetch_version_json(client: &Client, url: &str) -> Result<Value, reqwest::Error> {
+    client.get(url).send().await?.error_for_status()?.json::<Value>().await
+}
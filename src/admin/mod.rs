@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, State},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use reqwest::StatusCode;
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::auth::require_admin;
+use crate::cache::{self, stats::cache_stats, StoreStats};
+use crate::config::Settings;
+use crate::proxy::store::artifact_stats;
+use crate::refresher;
+use crate::state::AppState;
+
+/// Rutas de administración, todas tras `require_admin`.
+pub fn router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/admin/stats", get(stats))
+        .route("/admin/refresh", post(refresh))
+        .route("/admin/reload", post(reload))
+        .route("/cache", delete(purge_cache))
+        .route("/cache/stats", get(cache_stats))
+        .route("/cache/artifacts/stats", get(artifact_stats))
+        .route("/cache/version/{id}", delete(purge_version))
+        .route_layer(middleware::from_fn_with_state(state, require_admin))
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdminStats {
+    pub cache: StoreStats,
+    pub circuit_breaker: &'static str,
+    pub last_manifest_refresh_age_secs: Option<u64>,
+}
+
+pub async fn stats(State(state): State<AppState>) -> Json<AdminStats> {
+    Json(AdminStats {
+        cache: cache::store().stats().await,
+        circuit_breaker: state.breaker.state().as_str(),
+        last_manifest_refresh_age_secs: cache::last_manifest_refresh()
+            .and_then(|t| t.elapsed().ok())
+            .map(|d| d.as_secs()),
+    })
+}
+
+/// Lanza un refresco del manifest y de las versiones precargadas.
+pub async fn refresh(State(state): State<AppState>) -> StatusCode {
+    info!("Manual manifest refresh requested");
+    tokio::spawn(async move {
+        let interval = Duration::from_secs(state.settings().refresher.interval_secs.max(1));
+        refresher::refresh_once(&state, interval).await;
+    });
+    StatusCode::ACCEPTED
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReloadResult {
+    /// Secciones que cambiaron pero solo se aplican al reiniciar.
+    pub restart_required: Vec<&'static str>,
+}
+
+/// Vuelve a leer la configuración (archivo y entorno) y la aplica.
+pub async fn reload(State(state): State<AppState>) -> Response {
+    let settings = match Settings::load() {
+        Ok(settings) => settings,
+        Err(e) => {
+            warn!("Configuration reload failed: {}", e);
+            return (StatusCode::BAD_REQUEST, e).into_response();
+        }
+    };
+
+    let current = state.settings();
+    let restart_required = [
+        ("server", changed(&current.server, &settings.server)),
+        ("cache", changed(&current.cache, &settings.cache)),
+        ("upstream", changed(&current.upstream, &settings.upstream)),
+        ("concurrency", changed(&current.concurrency, &settings.concurrency)),
+        ("signing", changed(&current.signing, &settings.signing)),
+    ]
+    .into_iter()
+    .filter_map(|(section, changed)| changed.then_some(section))
+    .collect();
+
+    state.replace_settings(settings);
+    info!("Configuration reloaded");
+    Json(ReloadResult { restart_required }).into_response()
+}
+
+fn changed<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() != serde_json::to_value(b).ok()
+}
+
+/// Invalida una versión, p. ej. tras una corrección en upstream.
+pub async fn purge_version(Path(id): Path<String>) -> StatusCode {
+    cache::purge_version(&id).await;
+    info!("Cache purged for version {}", id);
+    StatusCode::NO_CONTENT
+}
+
+pub async fn purge_cache() -> StatusCode {
+    cache::purge_all().await;
+    info!("Cache purged");
+    StatusCode::NO_CONTENT
+}
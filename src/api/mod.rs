@@ -1,32 +1,335 @@
-use axum::response::IntoResponse;
-use axum::{Json, Router, routing::get};
-use reqwest::StatusCode;
-use crate::manifest::{fetch_version_manifest, get_version_by_id};
-use crate::types::VersionManifest;
-use crate::cache::get_cached_manifest;
-
-pub fn create_router() -> Router {
-    Router::new()
-        .route("/manifest", get(get_versions))
-        .route("/version/{id}", get(get_version_by_id))
-        .fallback(not_found)
-}
-
-pub async fn get_versions() -> Result<Json<VersionManifest>, (axum::http::StatusCode, String)> {
-    let manifest = get_cached_manifest(|| async {
-        match fetch_version_manifest().await {
-            Ok(m) => m,
-            Err(_) => VersionManifest {
-                latest_release: "".to_string(),
-                latest_snapshot: "".to_string(),
-                versions: vec![],
-            }
-        }
-    }).await;
-
-    Ok(Json(manifest))
-}
-
-async fn not_found() -> impl IntoResponse {
-    (StatusCode::NOT_FOUND, "404 - Not found.")
-}
\ No newline at end of file
+use std::time::{Duration, Instant};
+
+use axum::body::{to_bytes, Body};
+use axum::extract::{MatchedPath, Query, Request, State};
+use axum::http::header::{AGE, CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_NONE_MATCH, WARNING};
+use axum::http::{HeaderValue, Method};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router, routing::{get, post}};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use crate::manifest::{arguments::{arguments_template, expand_arguments}, assets::diff_assets, batch::batch_versions, bundle::version_bundle, diff::diff_versions, fetch_version_manifest, java::version_java, get_version_by_id, normalize_version, resolve::resolve_version};
+use crate::admin;
+use crate::bedrock::bedrock_versions;
+use crate::cache::{self, compute_etag, etag_matches, get_cached_manifest, Freshness};
+use crate::compression::compress;
+use crate::concurrency::{self, Group};
+use crate::events::events;
+use crate::experimental;
+use crate::graphql::{graphql_get, graphql_post, graphql_schema};
+use crate::health::{healthz, readyz};
+use crate::history::{self, version_history};
+use crate::maven::resolve_handler;
+use crate::metrics::{self, metrics_handler};
+use crate::mirror::{self, MirrorChoice};
+use crate::openapi::{docs, openapi_json};
+use crate::profiles::{self, get_profile, list_profiles};
+use crate::proxy::proxy_artifact;
+use crate::ratelimit::rate_limit;
+use crate::request_id::trace_request;
+use crate::search::search_versions;
+use crate::signing::{public_key, sign_responses};
+use crate::state::AppState;
+use crate::types::{MinecraftVersion, VersionManifest};
+
+pub fn create_router(state: AppState) -> Router {
+    let limits = &state.settings().concurrency;
+    let manifest = Router::new()
+        .route("/manifest", get(get_versions))
+        .route("/version/{id}", get(get_version_by_id))
+        .route("/version/{id}/history", get(version_history))
+        .route("/versions/search", get(search_versions))
+        .route("/profiles", get(list_profiles))
+        .route("/profiles/{name}", get(get_profile))
+        .route("/bedrock/versions", get(bedrock_versions));
+    let normalize = Router::new()
+        .route("/version/resolve", post(resolve_version))
+        .route("/normalize", post(normalize_version))
+        .route("/version/{id}/diff/{other}", get(diff_versions))
+        .route("/assets/{id}/diff/{other}", get(diff_assets))
+        .route("/version/{id}/bundle/{platform}", get(version_bundle))
+        .route("/version/{id}/java", get(version_java))
+        .route("/version/{id}/arguments/template", get(arguments_template).post(expand_arguments))
+        .route("/versions/batch", post(batch_versions))
+        .route("/maven/resolve", get(resolve_handler))
+        .route("/graphql", get(graphql_get).post(graphql_post));
+    let proxy = Router::new().route("/proxy/{hash}", get(proxy_artifact));
+
+    Router::new()
+        .merge(concurrency::limit(manifest, Group::Manifest, limits))
+        .merge(concurrency::limit(normalize, Group::Normalize, limits))
+        .merge(concurrency::limit(proxy, Group::Proxy, limits))
+        .route("/events", get(events))
+        .route("/graphql/schema", get(graphql_schema))
+        .route("/metrics", get(metrics_handler))
+        .route("/openapi.json", get(openapi_json))
+        .route("/publickey", get(public_key))
+        .route("/docs", get(docs))
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .merge(admin::router(state.clone()))
+        .merge(concurrency::limit(profiles::router(state.clone()), Group::Normalize, limits))
+        .fallback(not_found)
+        .with_state(state.clone())
+        .layer(middleware::from_fn(etag_layer))
+        .layer(middleware::from_fn_with_state(state.clone(), sign_responses))
+        .layer(middleware::from_fn_with_state(state.clone(), compress))
+        .layer(middleware::from_fn_with_state(state, rate_limit))
+        .layer(middleware::from_fn(track_metrics))
+        .layer(middleware::from_fn(trace_request))
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ManifestQuery {
+    /// Uno o varios tipos separados por comas (`release`, `snapshot`, `old_beta`, ...).
+    #[serde(rename = "type")]
+    pub version_type: Option<String>,
+    /// Fecha mínima de publicación, p. ej. `2022-01-01`.
+    pub since: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    pub sort: Option<SortOrder>,
+    /// El manifest tal como se servía en esa fecha, desde el historial.
+    pub as_of: Option<String>,
+    /// `experimental`: sumar las versiones de los catálogos experimentales.
+    pub include: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ManifestPage {
+    pub latest_release: String,
+    pub latest_snapshot: String,
+    /// Versiones que cumplen el filtro, antes de paginar.
+    pub total: usize,
+    pub offset: usize,
+    pub count: usize,
+    pub versions: Vec<MinecraftVersion>,
+}
+
+pub async fn get_versions(
+    State(state): State<AppState>,
+    Query(query): Query<ManifestQuery>,
+    mirror: MirrorChoice,
+) -> impl IntoResponse {
+    if let Some(as_of) = &query.as_of {
+        let snapshot = match history::parse_timestamp(as_of) {
+            Ok(at) => history::manifest_as_of(&state, at).await,
+            Err(msg) => Err((StatusCode::BAD_REQUEST, msg)),
+        };
+        return match snapshot {
+            Ok(snapshot) => mirror::vary(&state, Json(manifest_page(snapshot.data, &query, &mirror)).into_response()),
+            Err(err) => err.into_response(),
+        };
+    }
+
+    let fetch_state = state.clone();
+    let cached = get_cached_manifest(move || async move { fetch_version_manifest(&fetch_state).await }).await;
+    let (mut manifest, etag, age) = (cached.data, cached.etag, cached.age);
+    let with_experimental = experimental::requested(query.include.as_deref());
+    if with_experimental {
+        experimental::merge(&mut manifest, experimental::versions(&state).await);
+    }
+    let freshness = if cached.freshness == Freshness::Stale && state.breaker.is_open() {
+        Freshness::Fallback
+    } else {
+        cached.freshness
+    };
+    let unfiltered = query.version_type.is_none()
+        && query.since.is_none()
+        && query.limit.is_none()
+        && query.offset.is_none()
+        && query.sort.is_none()
+        && !with_experimental;
+
+    let page = manifest_page(manifest, &query, &mirror);
+
+    // Sin filtros la página depende solo del manifest, así que su ETag sirve;
+    // con filtros (o mirror) lo calcula la capa de ETag a partir del cuerpo.
+    let response = if unfiltered && mirror.0.is_none() {
+        ([(ETAG, etag)], Json(page)).into_response()
+    } else {
+        Json(page).into_response()
+    };
+    let ttl = cache::settings().manifest_ttl();
+    with_cache_headers(mirror::vary(&state, response), age, ttl, freshness)
+}
+
+fn manifest_page(manifest: VersionManifest, query: &ManifestQuery, mirror: &MirrorChoice) -> ManifestPage {
+    let mut page = filter_manifest(manifest, query);
+    if let Some(mirror) = &mirror.0 {
+        for version in &mut page.versions {
+            if let Some(url) = mirror.rewrite(&version.url) {
+                version.url = url;
+            }
+        }
+    }
+    page
+}
+
+/// `Cache-Control` y `Age` según la edad de la entrada servida, más la
+/// cabecera `Warning` si no está al día.
+pub fn with_cache_headers(mut response: Response, age: Duration, ttl: Duration, freshness: Freshness) -> Response {
+    let cache_control = match freshness {
+        Freshness::Fresh => format!(
+            "public, max-age={}, stale-while-revalidate={}",
+            ttl.saturating_sub(age).as_secs(),
+            cache::stale_grace().as_secs()
+        ),
+        Freshness::Stale | Freshness::Fallback => "public, no-cache".to_string(),
+        Freshness::Unavailable => "no-store".to_string(),
+    };
+
+    let headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(&cache_control) {
+        headers.insert(CACHE_CONTROL, value);
+    }
+    headers.insert(AGE, HeaderValue::from(age.as_secs()));
+    with_freshness(response, freshness)
+}
+
+/// Añade la cabecera `Warning` si la respuesta no está al día.
+pub fn with_freshness(mut response: Response, freshness: Freshness) -> Response {
+    if let Some(warning) = freshness.warning() {
+        response.headers_mut().insert(WARNING, HeaderValue::from_static(warning));
+    }
+    response
+}
+
+pub(crate) fn filter_manifest(manifest: VersionManifest, query: &ManifestQuery) -> ManifestPage {
+    let types: Option<Vec<&str>> = query
+        .version_type
+        .as_deref()
+        .map(|t| t.split(',').map(str::trim).filter(|t| !t.is_empty()).collect());
+
+    // release_time es ISO 8601, así que comparar como texto ordena por fecha.
+    let mut versions: Vec<MinecraftVersion> = manifest
+        .versions
+        .into_iter()
+        .filter(|v| types.as_ref().is_none_or(|types| types.contains(&v.version_type.as_str())))
+        .filter(|v| query.since.as_deref().is_none_or(|since| v.release_time.as_str() >= since))
+        .collect();
+
+    match query.sort {
+        Some(SortOrder::Asc) => versions.sort_by(|a, b| a.release_time.cmp(&b.release_time)),
+        Some(SortOrder::Desc) => versions.sort_by(|a, b| b.release_time.cmp(&a.release_time)),
+        None => {}
+    }
+
+    let total = versions.len();
+    let offset = query.offset.unwrap_or(0);
+    let versions: Vec<MinecraftVersion> = versions
+        .into_iter()
+        .skip(offset)
+        .take(query.limit.unwrap_or(usize::MAX))
+        .collect();
+
+    ManifestPage {
+        latest_release: manifest.latest_release,
+        latest_snapshot: manifest.latest_snapshot,
+        total,
+        offset,
+        count: versions.len(),
+        versions,
+    }
+}
+
+// Un flujo SSE no termina nunca: no se puede hashear su cuerpo.
+fn is_event_stream(response: &Response) -> bool {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/event-stream"))
+}
+
+async fn not_found() -> impl IntoResponse {
+    (StatusCode::NOT_FOUND, "404 - Not found.")
+}
+
+// Cuenta peticiones y latencia por ruta (la plantilla, no la URL concreta).
+async fn track_metrics(request: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let response = next.run(request).await;
+
+    let status = response.status().as_u16().to_string();
+    metrics::increment_counter(
+        metrics::HTTP_REQUESTS,
+        &[("route", &route), ("method", &method), ("status", &status)],
+    );
+    metrics::observe_histogram(metrics::HTTP_DURATION, &[("route", &route)], started.elapsed().as_secs_f64());
+
+    response
+}
+
+// Añade ETag + Cache-Control a las respuestas GET exitosas y responde 304
+// cuando el cliente ya tiene la misma representación.
+async fn etag_layer(request: Request, next: Next) -> Response {
+    if request.method() != Method::GET {
+        return next.run(request).await;
+    }
+
+    let if_none_match = request
+        .headers()
+        .get(IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+
+    let response = next.run(request).await;
+    if !response.status().is_success() || is_event_stream(&response) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+
+    // Si el handler no trae un ETag precalculado, se hashea el cuerpo.
+    let (etag, body) = match parts.headers.get(ETAG).cloned() {
+        Some(etag) => (etag, body),
+        None => {
+            let Ok(bytes) = to_bytes(body, usize::MAX).await else {
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            };
+            let Ok(etag) = HeaderValue::from_str(&compute_etag(&bytes)) else {
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            };
+            (etag, Body::from(bytes))
+        }
+    };
+
+    parts.headers.insert(ETAG, etag.clone());
+    parts
+        .headers
+        .entry(CACHE_CONTROL)
+        .or_insert(HeaderValue::from_static("public, no-cache"));
+
+    let not_modified = match (if_none_match, etag.to_str()) {
+        (Some(inm), Ok(current)) => etag_matches(&inm, current),
+        _ => false,
+    };
+
+    if not_modified {
+        let mut not_modified = StatusCode::NOT_MODIFIED.into_response();
+        for name in [ETAG, CACHE_CONTROL] {
+            if let Some(value) = parts.headers.get(&name) {
+                not_modified.headers_mut().insert(name, value.clone());
+            }
+        }
+        return not_modified;
+    }
+
+    Response::from_parts(parts, body)
+}
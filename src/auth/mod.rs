@@ -0,0 +1,64 @@
+use axum::{
+    extract::{Request, State},
+    http::{
+        header::{AUTHORIZATION, WWW_AUTHENTICATE},
+        HeaderMap,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use reqwest::StatusCode;
+use ring::digest::{digest, SHA256};
+
+use crate::state::AppState;
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Middleware que exige el token de administración, como `Authorization:
+/// Bearer <token>` o `X-API-Key: <token>`. Se monta con
+/// `middleware::from_fn_with_state` sobre cualquier grupo de rutas.
+pub async fn require_admin(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let settings = state.settings();
+    let Some(expected) = settings.admin.token.as_deref().filter(|t| !t.is_empty()) else {
+        return (StatusCode::FORBIDDEN, "API de administración deshabilitada: falta admin.token").into_response();
+    };
+    authorized(request, next, expected, "Token de administración inválido").await
+}
+
+/// Como `require_admin`, para subir y borrar perfiles: vale `profiles.token`
+/// o, si no está configurado, el de administración.
+pub async fn require_publisher(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let settings = state.settings();
+    let expected = [settings.profiles.token.as_deref(), settings.admin.token.as_deref()]
+        .into_iter()
+        .flatten()
+        .find(|t| !t.is_empty());
+    let Some(expected) = expected else {
+        return (StatusCode::FORBIDDEN, "Subida de perfiles deshabilitada: falta profiles.token").into_response();
+    };
+    authorized(request, next, expected, "Token de publicación inválido").await
+}
+
+async fn authorized(request: Request, next: Next, expected: &str, invalid: &'static str) -> Response {
+    match provided_token(request.headers()) {
+        Some(token) if tokens_match(token, expected) => next.run(request).await,
+        _ => (StatusCode::UNAUTHORIZED, [(WWW_AUTHENTICATE, "Bearer")], invalid).into_response(),
+    }
+}
+
+fn provided_token(headers: &HeaderMap) -> Option<&str> {
+    let bearer = headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim);
+
+    bearer.or_else(|| headers.get(API_KEY_HEADER).and_then(|v| v.to_str().ok()).map(str::trim))
+}
+
+// Se comparan los hashes para que el tiempo no dependa del prefijo común.
+fn tokens_match(provided: &str, expected: &str) -> bool {
+    let a = digest(&SHA256, provided.as_bytes());
+    let b = digest(&SHA256, expected.as_bytes());
+    a.as_ref().iter().zip(b.as_ref()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
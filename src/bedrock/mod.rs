@@ -0,0 +1,187 @@
+use std::time::Duration;
+
+use axum::{
+    extract::State,
+    http::header::ETAG,
+    response::{IntoResponse, Response},
+    Json,
+};
+use once_cell::sync::Lazy;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+
+use crate::api::with_cache_headers;
+use crate::cache::{self, singleflight::SingleFlight, Freshness};
+use crate::metrics;
+use crate::state::AppState;
+
+const CACHE_KEY: &str = "bedrock:versions";
+// Cuánto se guarda la última lista para servirla si la fuente falla.
+const RETENTION: Duration = Duration::from_secs(7 * 24 * 3600);
+const CIRCUIT_OPEN: &str = "Circuit breaker abierto: la API de Bedrock no está disponible temporalmente";
+
+// Descarga de la lista en vuelo, compartida entre clientes concurrentes.
+static FLIGHT: Lazy<SingleFlight<Result<(BedrockVersions, String), String>>> = Lazy::new(SingleFlight::new);
+
+/// Versiones de Bedrock disponibles para descarga.
+///
+/// Solo servidor dedicado: es lo único que Mojang publica con descarga
+/// directa. El cliente se instala desde la tienda de cada plataforma
+/// (Microsoft Store, Google Play, App Store), que no ofrece una lista de
+/// versiones ni enlaces que se puedan servir desde aquí.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BedrockVersions {
+    pub latest: BedrockLatest,
+    pub versions: Vec<BedrockDownload>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BedrockLatest {
+    pub release: Option<String>,
+    pub preview: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BedrockDownload {
+    pub version: String,
+    /// `release` o `preview`.
+    #[serde(rename = "type")]
+    pub version_type: String,
+    /// `windows` o `linux`.
+    pub platform: String,
+    /// Por ahora siempre `server`.
+    pub edition: String,
+    pub url: String,
+}
+
+/// `GET /bedrock/versions`. Como el manifest: vencida dentro de la ventana
+/// de gracia se sirve y se refresca aparte, y si la API falla se sirve la
+/// última lista conocida.
+pub async fn bedrock_versions(State(state): State<AppState>) -> Response {
+    let ttl = Duration::from_secs(state.settings().bedrock.cache_ttl_secs);
+    let cached = cache::get_json::<BedrockVersions>(CACHE_KEY).await;
+    if let Some((versions, etag, age)) = &cached {
+        if *age < ttl {
+            metrics::cache_lookup("bedrock", true);
+            return with_cache_headers(versions_response(versions, etag), *age, ttl, Freshness::Fresh);
+        }
+
+        if *age < ttl + cache::stale_grace() {
+            metrics::cache_lookup("bedrock", true);
+            metrics::increment_counter(metrics::CACHE_STALE_HITS, &[("cache", "bedrock")]);
+            if let Some(guard) = cache::try_begin_refresh(CACHE_KEY) {
+                let state = state.clone();
+                tokio::spawn(async move {
+                    let _guard = guard;
+                    if let Err(e) = refresh(&state).await {
+                        warn!("Background Bedrock versions refresh failed: {}", e);
+                    }
+                });
+            }
+            let freshness = if state.bedrock_breaker.is_open() { Freshness::Fallback } else { Freshness::Stale };
+            return with_cache_headers(versions_response(versions, etag), *age, ttl, freshness);
+        }
+    }
+    metrics::cache_lookup("bedrock", false);
+
+    match refresh(&state).await {
+        Ok((versions, etag)) => with_cache_headers(versions_response(&versions, &etag), Duration::ZERO, ttl, Freshness::Fresh),
+        Err(e) => {
+            warn!("Bedrock versions fetch failed: {}", e);
+            match cached {
+                Some((versions, etag, age)) => {
+                    with_cache_headers(versions_response(&versions, &etag), age, ttl, Freshness::Fallback)
+                }
+                None => (StatusCode::BAD_GATEWAY, "Error obteniendo las versiones de Bedrock").into_response(),
+            }
+        }
+    }
+}
+
+// Descarga y guarda la lista; las peticiones concurrentes comparten la descarga.
+async fn refresh(state: &AppState) -> Result<(BedrockVersions, String), String> {
+    FLIGHT
+        .run(CACHE_KEY, || async {
+            let versions = fetch(state).await?;
+            let etag = cache::set_json(CACHE_KEY, &versions, RETENTION).await;
+            Ok((versions, etag))
+        })
+        .await
+}
+
+fn versions_response(versions: &BedrockVersions, etag: &str) -> Response {
+    ([(ETAG, etag.to_string())], Json(versions.clone())).into_response()
+}
+
+async fn fetch(state: &AppState) -> Result<BedrockVersions, String> {
+    let Some(attempt) = state.bedrock_breaker.allow() else {
+        return Err(CIRCUIT_OPEN.to_string());
+    };
+
+    let url = state.settings().bedrock.links_url.clone();
+    metrics::increment_counter(metrics::UPSTREAM_REQUESTS, &[("target", "bedrock")]);
+    let fetched = state
+        .upstream
+        .execute(&url, |client| {
+            let url = url.clone();
+            async move { client.get(&url).send().await?.error_for_status()?.json::<Value>().await }
+        })
+        .await;
+
+    let parsed = fetched
+        .map_err(|e| e.to_string())
+        .and_then(|links| parse_links(&links).ok_or_else(|| "Respuesta de enlaces sin el formato esperado".to_string()));
+    attempt.record(parsed.is_ok());
+    if parsed.is_err() {
+        metrics::increment_counter(metrics::UPSTREAM_ERRORS, &[("target", "bedrock")]);
+    }
+    parsed
+}
+
+// `{"result": {"links": [{"downloadType": "serverBedrockLinux", "downloadUrl": "..."}]}}`;
+// la versión solo aparece en el nombre del archivo (`bedrock-server-1.21.50.07.zip`).
+fn parse_links(links: &Value) -> Option<BedrockVersions> {
+    let entries = links.get("result")?.get("links")?.as_array()?;
+    let mut versions = vec![];
+    let mut latest = BedrockLatest::default();
+
+    for entry in entries {
+        let (Some(kind), Some(url)) = (
+            entry.get("downloadType").and_then(Value::as_str),
+            entry.get("downloadUrl").and_then(Value::as_str),
+        ) else {
+            continue;
+        };
+        let (version_type, platform) = match kind {
+            "serverBedrockWindows" => ("release", "windows"),
+            "serverBedrockLinux" => ("release", "linux"),
+            "serverBedrockPreviewWindows" => ("preview", "windows"),
+            "serverBedrockPreviewLinux" => ("preview", "linux"),
+            _ => continue,
+        };
+        let Some(version) = version_from_url(url) else {
+            continue;
+        };
+
+        let slot = if version_type == "release" { &mut latest.release } else { &mut latest.preview };
+        slot.get_or_insert_with(|| version.clone());
+        versions.push(BedrockDownload {
+            version,
+            version_type: version_type.to_string(),
+            platform: platform.to_string(),
+            edition: "server".to_string(),
+            url: url.to_string(),
+        });
+    }
+
+    Some(BedrockVersions { latest, versions })
+}
+
+fn version_from_url(url: &str) -> Option<String> {
+    let file = url.rsplit('/').next()?;
+    let version = file.strip_prefix("bedrock-server-")?.strip_suffix(".zip")?;
+    let valid = !version.is_empty() && version.split('.').all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()));
+    valid.then(|| version.to_string())
+}
@@ -0,0 +1,225 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tracing::warn;
+
+use crate::types::{NormalizedVersion, VersionManifest};
+
+const MANIFEST_FILE: &str = "manifest.json";
+const VERSIONS_DIR: &str = "versions";
+const ARTIFACTS_DIR: &str = "artifacts";
+
+#[derive(Serialize, Deserialize)]
+struct DiskEntry<T> {
+    key: String,
+    etag: String,
+    stored_at: u64, // segundos UNIX
+    data: T,
+}
+
+pub struct Restored<T> {
+    pub key: String,
+    pub etag: String,
+    pub age: Duration,
+    pub data: T,
+}
+
+pub async fn store_manifest(manifest: &VersionManifest, etag: &str) {
+    write_entry(&super::settings().dir.join(MANIFEST_FILE), "manifest", etag, manifest).await;
+}
+
+pub async fn load_manifest() -> Option<Restored<VersionManifest>> {
+    read_entry(&super::settings().dir.join(MANIFEST_FILE)).await
+}
+
+pub async fn store_version(id: &str, version: &NormalizedVersion, etag: &str) {
+    let path = super::settings().dir.join(VERSIONS_DIR).join(file_name(id));
+    write_entry(&path, id, etag, version).await;
+}
+
+/// Ids distintos pueden compartir archivo (ver [`safe_name`]): solo vale la
+/// entrada cuyo id guardado es el pedido.
+pub async fn load_version(id: &str) -> Option<Restored<NormalizedVersion>> {
+    read_entry(&super::settings().dir.join(VERSIONS_DIR).join(file_name(id)))
+        .await
+        .filter(|restored| restored.key == id)
+}
+
+pub async fn load_versions() -> Vec<Restored<NormalizedVersion>> {
+    let mut restored = vec![];
+    let Ok(mut dir) = tokio::fs::read_dir(super::settings().dir.join(VERSIONS_DIR)).await else {
+        return restored;
+    };
+
+    while let Ok(Some(entry)) = dir.next_entry().await {
+        if let Some(version) = read_entry(&entry.path()).await {
+            restored.push(version);
+        }
+    }
+
+    restored
+}
+
+pub async fn remove_version(id: &str) {
+    remove(&super::settings().dir.join(VERSIONS_DIR).join(file_name(id))).await;
+}
+
+/// Borra el manifest y las versiones guardadas; los artefactos verificados se
+/// conservan porque su contenido queda fijado por el SHA1.
+pub async fn clear() {
+    let dir = &super::settings().dir;
+    remove(&dir.join(MANIFEST_FILE)).await;
+    if let Err(e) = tokio::fs::remove_dir_all(dir.join(VERSIONS_DIR)).await
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        warn!("Could not clear the on-disk version cache: {}", e);
+    }
+}
+
+async fn remove(path: &Path) {
+    if let Err(e) = tokio::fs::remove_file(path).await
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        warn!("Could not delete {:?}: {}", path, e);
+    }
+}
+
+/// Ruta de un artefacto verificado, direccionado por su checksum (`ab/abcdef...`).
+pub fn artifact_path(hash: &str) -> PathBuf {
+    super::settings().dir.join(ARTIFACTS_DIR).join(&hash[..2]).join(hash)
+}
+
+/// Artefactos guardados en disco: número, bytes y edad del más antiguo y
+/// del más reciente.
+#[derive(Debug, Default)]
+pub struct ArtifactStats {
+    pub files: usize,
+    pub bytes: u64,
+    pub oldest: Option<Duration>,
+    pub newest: Option<Duration>,
+}
+
+pub async fn artifact_stats() -> ArtifactStats {
+    let mut stats = ArtifactStats::default();
+    for file in artifact_files().await {
+        let age = file.modified.elapsed().unwrap_or_default();
+        stats.files += 1;
+        stats.bytes += file.bytes;
+        stats.oldest = stats.oldest.max(Some(age));
+        stats.newest = Some(stats.newest.map_or(age, |newest| newest.min(age)));
+    }
+    stats
+}
+
+/// Un artefacto en disco; `modified` es también la hora del último uso.
+pub struct ArtifactFile {
+    pub path: PathBuf,
+    pub bytes: u64,
+    pub modified: SystemTime,
+}
+
+pub async fn artifact_files() -> Vec<ArtifactFile> {
+    artifact_dir_files(false).await
+}
+
+/// Temporales de las descargas que se están copiando a disco
+/// (`<hash>.<pid>.<n>.tmp`), incluidos los que dejó un proceso anterior.
+pub async fn artifact_spools() -> Vec<ArtifactFile> {
+    artifact_dir_files(true).await
+}
+
+async fn artifact_dir_files(spools: bool) -> Vec<ArtifactFile> {
+    let mut artifacts = vec![];
+    let Ok(mut shards) = tokio::fs::read_dir(super::settings().dir.join(ARTIFACTS_DIR)).await else {
+        return artifacts;
+    };
+
+    while let Ok(Some(shard)) = shards.next_entry().await {
+        let Ok(mut files) = tokio::fs::read_dir(shard.path()).await else {
+            continue;
+        };
+        while let Ok(Some(file)) = files.next_entry().await {
+            let Ok(metadata) = file.metadata().await else {
+                continue;
+            };
+            // Los artefactos no tienen extensión; los `.tmp` son descargas a medias.
+            let wanted = match file.path().extension() {
+                None => !spools,
+                Some(ext) => spools && ext == "tmp",
+            };
+            if !metadata.is_file() || !wanted {
+                continue;
+            }
+            artifacts.push(ArtifactFile {
+                path: file.path(),
+                bytes: metadata.len(),
+                modified: metadata.modified().unwrap_or(UNIX_EPOCH),
+            });
+        }
+    }
+    artifacts
+}
+
+fn file_name(id: &str) -> String {
+    format!("{}.json", safe_name(id))
+}
+
+/// Los ids de Mojang pueden traer espacios u otros caracteres raros; el id
+/// real se guarda dentro del archivo, así que basta con un nombre seguro.
+pub(crate) fn safe_name(id: &str) -> String {
+    id.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+async fn write_entry<T: Serialize>(path: &Path, key: &str, etag: &str, data: &T) {
+    let entry = DiskEntry {
+        key: key.to_string(),
+        etag: etag.to_string(),
+        stored_at: unix_now(),
+        data,
+    };
+
+    let Ok(bytes) = serde_json::to_vec(&entry) else {
+        return;
+    };
+
+    if let Err(e) = write_atomic(path, &bytes).await {
+        warn!("Could not write on-disk cache entry {:?}: {}", path, e);
+    }
+}
+
+/// Escritura atómica: archivo temporal + rename.
+pub(crate) async fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(&tmp, bytes).await?;
+    tokio::fs::rename(&tmp, path).await
+}
+
+async fn read_entry<T: DeserializeOwned>(path: &Path) -> Option<Restored<T>> {
+    let bytes = tokio::fs::read(path).await.ok()?;
+    let entry: DiskEntry<T> = match serde_json::from_slice(&bytes) {
+        Ok(entry) => entry,
+        Err(e) => {
+            warn!("Invalid on-disk cache entry {:?}: {}", path, e);
+            return None;
+        }
+    };
+
+    Some(Restored {
+        key: entry.key,
+        etag: entry.etag,
+        age: Duration::from_secs(unix_now().saturating_sub(entry.stored_at)),
+        data: entry.data,
+    })
+}
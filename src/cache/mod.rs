@@ -1,43 +1,327 @@
-use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
-use once_cell::sync::Lazy;
-use crate::types::VersionManifest;
-
-static VERSION_MANIFEST_CACHE: Lazy<RwLock<ManifestCache>> = Lazy::new(|| {
-    RwLock::new(ManifestCache {
-        data: None,
-        updated_at: None,
-    })
-});
-
-struct ManifestCache {
-    data: Option<VersionManifest>,
-    updated_at: Option<Instant>,
-}
-
-const TTL: Duration = Duration::from_secs(60 * 50); // 50 minutos
-
-pub async fn get_cached_manifest<F, Fut>(fetch_fn: F) -> VersionManifest
-where
-    F: FnOnce() -> Fut,
-    Fut: std::future::Future<Output = VersionManifest>,
-{
-    let now = Instant::now();
-
-    {
-        let read_guard = VERSION_MANIFEST_CACHE.read().await;
-        if let (Some(data), Some(updated)) = (&read_guard.data, read_guard.updated_at) {
-            if now.duration_since(updated) < TTL {
-                return data.clone();
-            }
-        }
-    }
-
-    let new_manifest = fetch_fn().await;
-
-    let mut write_guard = VERSION_MANIFEST_CACHE.write().await;
-    write_guard.data = Some(new_manifest.clone());
-    write_guard.updated_at = Some(now);
-
-    new_manifest
-}
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use once_cell::sync::{Lazy, OnceCell};
+use ring::digest::{digest, SHA1_FOR_LEGACY_USE_ONLY};
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::{error, info, warn};
+use crate::config::CacheSettings;
+use crate::metrics;
+use crate::types::VersionManifest;
+use manifestor_core::Platform;
+
+pub mod disk;
+pub mod redis;
+pub mod singleflight;
+pub mod stats;
+pub mod store;
+
+pub use redis::RedisStore;
+pub use store::{CacheEntry, CacheStore, KeyInfo, MemoryStore, StoreStats};
+
+static SETTINGS: OnceCell<CacheSettings> = OnceCell::new();
+static STORE: Lazy<Box<dyn CacheStore>> = Lazy::new(|| build_store(settings()));
+static REFRESHING: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+// Última escritura desde upstream por tipo de caché (ver `class`).
+static LAST_REFRESH: Lazy<Mutex<HashMap<&'static str, SystemTime>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub const MANIFEST_KEY: &str = "manifest";
+
+/// Qué tan actual es una respuesta servida desde caché.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freshness {
+    Fresh,
+    /// Vencida, dentro de la ventana de gracia mientras se revalida.
+    Stale,
+    /// Upstream falló y se sirve la última copia conocida.
+    Fallback,
+    /// Upstream falló y no hay ninguna copia que servir.
+    Unavailable,
+}
+
+impl Freshness {
+    /// Valor de la cabecera `Warning` (RFC 7234), si corresponde.
+    pub fn warning(self) -> Option<&'static str> {
+        match self {
+            Freshness::Fresh => None,
+            Freshness::Stale => Some("110 manifestor \"Response is Stale\""),
+            Freshness::Fallback => Some("111 manifestor \"Revalidation Failed\""),
+            Freshness::Unavailable => Some("199 manifestor \"Upstream unavailable\""),
+        }
+    }
+}
+
+/// Valor servido desde caché junto con su ETag, edad y frescura.
+#[derive(Debug, Clone)]
+pub struct Cached<T> {
+    pub data: T,
+    pub etag: String,
+    pub age: Duration,
+    pub freshness: Freshness,
+}
+
+/// Fija la configuración de caché; debe llamarse antes de usar la caché.
+/// Sin llamarla se usan los valores por defecto.
+pub fn init(settings: CacheSettings) {
+    if SETTINGS.set(settings).is_err() {
+        warn!("Cache already initialized, ignoring the new settings");
+    }
+}
+
+pub fn settings() -> &'static CacheSettings {
+    SETTINGS.get_or_init(CacheSettings::default)
+}
+
+fn build_store(settings: &CacheSettings) -> Box<dyn CacheStore> {
+    match settings.backend.as_str() {
+        "redis" => match RedisStore::from_url(&settings.redis_url) {
+            Ok(store) => {
+                info!("Using Redis cache backend at {}", settings.redis_url);
+                return Box::new(store);
+            }
+            Err(e) => error!("{}, falling back to memory", e),
+        },
+        "memory" => {}
+        other => error!("Unknown cache backend '{}', falling back to memory", other),
+    }
+    Box::new(MemoryStore::new(settings.max_entries, settings.max_bytes))
+}
+
+pub fn store() -> &'static dyn CacheStore {
+    STORE.as_ref()
+}
+
+/// Ventana durante la que se sirve una entrada vencida mientras se refresca en segundo plano.
+pub fn stale_grace() -> Duration {
+    settings().stale_grace()
+}
+
+/// Marca de refresco en segundo plano en curso para una clave; se libera al soltarla.
+pub struct RefreshGuard(String);
+
+impl Drop for RefreshGuard {
+    fn drop(&mut self) {
+        if let Ok(mut refreshing) = REFRESHING.lock() {
+            refreshing.remove(&self.0);
+        }
+    }
+}
+
+/// Devuelve `None` si ya hay un refresco en curso para `key`.
+pub fn try_begin_refresh(key: &str) -> Option<RefreshGuard> {
+    let mut refreshing = REFRESHING.lock().ok()?;
+    refreshing.insert(key.to_string()).then(|| RefreshGuard(key.to_string()))
+}
+
+pub fn version_key(id: &str) -> String {
+    format!("version:{}", id)
+}
+
+pub fn negative_version_key(id: &str) -> String {
+    format!("negative:version:{}", id)
+}
+
+pub fn bundle_key(id: &str, platform: &Platform) -> String {
+    format!("bundle:{}:{}", id, platform.id())
+}
+
+/// Tipo de caché al que pertenece una clave; es la etiqueta `cache` de las
+/// métricas de aciertos y fallos.
+pub fn class(key: &str) -> &'static str {
+    match key.split(':').next().unwrap_or_default() {
+        MANIFEST_KEY => "manifest",
+        "version" => "version",
+        "negative" => "version_negative",
+        "maven" => "maven",
+        "bundle" => "bundle",
+        "bedrock" => "bedrock",
+        "java" => "java",
+        "assets" => "assets",
+        "experimental" => "experimental",
+        _ => "other",
+    }
+}
+
+/// Anota que un tipo de caché acaba de recibir datos de upstream.
+pub fn mark_refreshed(class: &'static str) {
+    if let Ok(mut last) = LAST_REFRESH.lock() {
+        last.insert(class, SystemTime::now());
+    }
+}
+
+/// Momento de la última escritura desde upstream de un tipo de caché.
+pub fn last_refresh(class: &str) -> Option<SystemTime> {
+    LAST_REFRESH.lock().ok().and_then(|last| last.get(class).copied())
+}
+
+/// Olvida una versión en todas las capas (memoria/Redis y disco).
+pub async fn purge_version(id: &str) {
+    store().invalidate(&version_key(id)).await;
+    store().invalidate(&negative_version_key(id)).await;
+    for platform in Platform::all() {
+        store().invalidate(&bundle_key(id, &platform)).await;
+    }
+    disk::remove_version(id).await;
+}
+
+/// Vacía la caché por completo, incluida la copia en disco.
+pub async fn purge_all() {
+    store().clear().await;
+    disk::clear().await;
+}
+
+/// Lee y deserializa una entrada; devuelve el valor, su ETag y su edad.
+pub async fn get_json<T: DeserializeOwned>(key: &str) -> Option<(T, String, Duration)> {
+    let entry = store().get(key).await?;
+    let value = serde_json::from_slice(&entry.data).ok()?;
+    let age = entry.age();
+    Some((value, entry.etag, age))
+}
+
+/// Serializa y guarda una entrada nueva, devolviendo su ETag.
+pub async fn set_json<T: Serialize>(key: &str, value: &T, ttl: Duration) -> String {
+    let data = serde_json::to_vec(value).unwrap_or_default();
+    let etag = compute_etag(&data);
+    let entry = CacheEntry {
+        data,
+        etag: etag.clone(),
+        stored_at: SystemTime::now(),
+    };
+    store().set(key, entry, ttl).await;
+    mark_refreshed(class(key));
+    etag
+}
+
+/// Guarda una entrada con antigüedad conocida (p. ej. recuperada de disco)
+/// si el backend no tiene ya una, respetando el TTL restante.
+pub async fn restore_json<T: Serialize>(key: &str, value: &T, etag: String, age: Duration, ttl: Duration) -> bool {
+    if age >= ttl || store().get(key).await.is_some() {
+        return false;
+    }
+    let Some(stored_at) = SystemTime::now().checked_sub(age) else {
+        return false;
+    };
+
+    let entry = CacheEntry {
+        data: serde_json::to_vec(value).unwrap_or_default(),
+        etag,
+        stored_at,
+    };
+    store().set(key, entry, ttl - age).await;
+    true
+}
+
+pub async fn get_cached_manifest<F, Fut, E>(fetch_fn: F) -> Cached<VersionManifest>
+where
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = Result<VersionManifest, E>> + Send,
+    E: Display + Send,
+{
+    let ttl = settings().manifest_ttl();
+    let cached = get_json::<VersionManifest>(MANIFEST_KEY).await;
+
+    if let Some((data, etag, age)) = cached {
+        if age < ttl {
+            metrics::cache_lookup("manifest", true);
+            return Cached { data, etag, age, freshness: Freshness::Fresh };
+        }
+
+        // Vencido pero dentro de la ventana de gracia: servir y refrescar aparte.
+        if age < ttl + stale_grace() {
+            metrics::cache_lookup("manifest", true);
+            metrics::increment_counter(metrics::CACHE_STALE_HITS, &[("cache", "manifest")]);
+            if let Some(guard) = try_begin_refresh(MANIFEST_KEY) {
+                tokio::spawn(async move {
+                    let _guard = guard;
+                    match fetch_fn().await {
+                        Ok(manifest) => {
+                            store_manifest(&manifest).await;
+                        }
+                        Err(e) => warn!("Background manifest refresh failed: {}", e),
+                    }
+                });
+            }
+            return Cached { data, etag, age, freshness: Freshness::Stale };
+        }
+    }
+
+    metrics::cache_lookup("manifest", false);
+    match fetch_fn().await {
+        Ok(manifest) => {
+            let etag = store_manifest(&manifest).await;
+            Cached { data: manifest, etag, age: Duration::ZERO, freshness: Freshness::Fresh }
+        }
+        Err(e) => {
+            warn!("Manifest fetch failed: {}", e);
+
+            // Última copia en disco, sin importar su antigüedad.
+            if let Some(restored) = disk::load_manifest().await {
+                return Cached {
+                    data: restored.data,
+                    etag: restored.etag,
+                    age: restored.age,
+                    freshness: Freshness::Fallback,
+                };
+            }
+
+            let empty = VersionManifest {
+                latest_release: "".to_string(),
+                latest_snapshot: "".to_string(),
+                versions: vec![],
+            };
+            let etag = etag_for_json(&empty);
+            Cached { data: empty, etag, age: Duration::ZERO, freshness: Freshness::Unavailable }
+        }
+    }
+}
+
+/// Guarda un manifest recién descargado en la caché y en disco.
+pub async fn store_manifest(manifest: &VersionManifest) -> String {
+    let etag = set_json(MANIFEST_KEY, manifest, settings().manifest_ttl() + stale_grace()).await;
+    disk::store_manifest(manifest, &etag).await;
+    etag
+}
+
+/// Vuelca a disco el manifest en caché antes de apagar. Las escrituras ya
+/// van a disco al guardar; esto cubre una escritura anterior que falló.
+pub async fn flush() {
+    if let Some(entry) = store().get(MANIFEST_KEY).await
+        && let Ok(manifest) = serde_json::from_slice::<VersionManifest>(&entry.data)
+    {
+        disk::store_manifest(&manifest, &entry.etag).await;
+    }
+}
+
+/// Momento de la última descarga exitosa del manifest en este proceso.
+pub fn last_manifest_refresh() -> Option<SystemTime> {
+    last_refresh("manifest")
+}
+
+/// Carga el manifest guardado en disco si todavía está dentro del TTL.
+pub async fn rehydrate_manifest() -> bool {
+    let Some(restored) = disk::load_manifest().await else {
+        return false;
+    };
+    let ttl = settings().manifest_ttl() + stale_grace();
+    restore_json(MANIFEST_KEY, &restored.data, restored.etag, restored.age, ttl).await
+}
+
+/// ETag fuerte (entre comillas) calculado como SHA1 del cuerpo.
+pub fn compute_etag(body: &[u8]) -> String {
+    let hash = digest(&SHA1_FOR_LEGACY_USE_ONLY, body);
+    let hex: String = hash.as_ref().iter().map(|b| format!("{:02x}", b)).collect();
+    format!("\"{}\"", hex)
+}
+
+/// Mismo ETag que produciría la respuesta `Json(value)`.
+pub fn etag_for_json<T: serde::Serialize>(value: &T) -> String {
+    compute_etag(&serde_json::to_vec(value).unwrap_or_default())
+}
+
+/// Comprueba si algún valor de `If-None-Match` coincide con el ETag actual.
+pub fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match.split(',').map(str::trim).any(|candidate| {
+        candidate == "*" || candidate.strip_prefix("W/").unwrap_or(candidate) == etag
+    })
+}
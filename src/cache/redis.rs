@@ -0,0 +1,281 @@
+use std::{
+    io,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use reqwest::Url;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufStream},
+    net::TcpStream,
+    sync::Mutex,
+    time::timeout,
+};
+use tracing::warn;
+
+use super::store::{CacheEntry, CacheStore, KeyInfo, StoreFuture, StoreStats};
+
+const KEY_PREFIX: &str = "manifestor:";
+const IO_TIMEOUT: Duration = Duration::from_secs(2);
+
+enum Reply {
+    Simple,
+    Integer,
+    Bulk(Option<Vec<u8>>),
+    Array(Vec<Reply>),
+}
+
+/// Backend Redis mínimo (RESP2 sobre TCP) para compartir la caché entre réplicas.
+///
+/// Mantiene una única conexión que se reabre de forma perezosa si falla; los
+/// errores se registran y se tratan como un fallo de caché.
+pub struct RedisStore {
+    addr: String,
+    password: Option<String>,
+    username: Option<String>,
+    db: u32,
+    conn: Mutex<Option<BufStream<TcpStream>>>,
+}
+
+impl RedisStore {
+    /// Acepta URLs del tipo `redis://[usuario:contraseña@]host[:puerto][/db]`.
+    pub fn from_url(url: &str) -> Result<Self, String> {
+        let parsed = Url::parse(url).map_err(|e| format!("Invalid REDIS_URL: {}", e))?;
+        if parsed.scheme() != "redis" {
+            return Err(format!("Unsupported REDIS_URL scheme: {}", parsed.scheme()));
+        }
+
+        let host = parsed.host_str().ok_or("REDIS_URL sin host")?;
+        let port = parsed.port().unwrap_or(6379);
+        let db = match parsed.path().trim_start_matches('/') {
+            "" => 0,
+            db => db.parse().map_err(|_| format!("Invalid database in REDIS_URL: {}", db))?,
+        };
+
+        Ok(Self {
+            addr: format!("{}:{}", host, port),
+            password: parsed.password().map(String::from),
+            username: Some(parsed.username()).filter(|u| !u.is_empty()).map(String::from),
+            db,
+            conn: Mutex::new(None),
+        })
+    }
+
+    async fn connect(&self) -> io::Result<BufStream<TcpStream>> {
+        let stream = TcpStream::connect(&self.addr).await?;
+        let mut conn = BufStream::new(stream);
+
+        if let Some(password) = &self.password {
+            match &self.username {
+                Some(user) => send(&mut conn, &[b"AUTH", user.as_bytes(), password.as_bytes()]).await?,
+                None => send(&mut conn, &[b"AUTH", password.as_bytes()]).await?,
+            };
+        }
+        if self.db != 0 {
+            send(&mut conn, &[b"SELECT", self.db.to_string().as_bytes()]).await?;
+        }
+
+        Ok(conn)
+    }
+
+    async fn command(&self, args: &[&[u8]]) -> io::Result<Reply> {
+        let mut guard = self.conn.lock().await;
+        if guard.is_none() {
+            let conn = timeout(IO_TIMEOUT, self.connect())
+                .await
+                .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "timeout conectando a Redis"))??;
+            *guard = Some(conn);
+        }
+
+        let conn = guard.as_mut().expect("conexión recién establecida");
+        let result = timeout(IO_TIMEOUT, send(conn, args))
+            .await
+            .unwrap_or_else(|_| Err(io::Error::new(io::ErrorKind::TimedOut, "Redis timed out")));
+
+        // Ante cualquier error la conexión queda en estado desconocido.
+        if result.is_err() {
+            *guard = None;
+        }
+        result
+    }
+}
+
+impl CacheStore for RedisStore {
+    fn get<'a>(&'a self, key: &'a str) -> StoreFuture<'a, Option<CacheEntry>> {
+        Box::pin(async move {
+            let key = format!("{}{}", KEY_PREFIX, key);
+            match self.command(&[b"GET", key.as_bytes()]).await {
+                Ok(Reply::Bulk(Some(raw))) => decode_entry(&raw),
+                Ok(_) => None,
+                Err(e) => {
+                    warn!("Could not read {} from Redis: {}", key, e);
+                    None
+                }
+            }
+        })
+    }
+
+    fn set<'a>(&'a self, key: &'a str, entry: CacheEntry, ttl: Duration) -> StoreFuture<'a, ()> {
+        Box::pin(async move {
+            let key = format!("{}{}", KEY_PREFIX, key);
+            let ttl_ms = ttl.as_millis().max(1).to_string();
+            let value = encode_entry(&entry);
+            let args: [&[u8]; 5] = [b"SET", key.as_bytes(), &value, b"PX", ttl_ms.as_bytes()];
+            if let Err(e) = self.command(&args).await {
+                warn!("Could not write {} to Redis: {}", key, e);
+            }
+        })
+    }
+
+    fn invalidate<'a>(&'a self, key: &'a str) -> StoreFuture<'a, ()> {
+        Box::pin(async move {
+            let key = format!("{}{}", KEY_PREFIX, key);
+            if let Err(e) = self.command(&[b"DEL", key.as_bytes()]).await {
+                warn!("Could not invalidate {} in Redis: {}", key, e);
+            }
+        })
+    }
+
+    // DBSIZE contaría también claves ajenas a manifestor.
+    fn stats(&self) -> StoreFuture<'_, StoreStats> {
+        Box::pin(async move {
+            StoreStats {
+                backend: "redis",
+                entries: None,
+                bytes: None,
+                max_entries: None,
+                max_bytes: None,
+            }
+        })
+    }
+
+    // Habría que leer cada valor para saber su edad.
+    fn keys(&self) -> StoreFuture<'_, Option<Vec<KeyInfo>>> {
+        Box::pin(async { None })
+    }
+
+    fn clear(&self) -> StoreFuture<'_, ()> {
+        Box::pin(async move {
+            if let Err(e) = self.delete_prefixed().await {
+                warn!("Could not clear the Redis cache: {}", e);
+            }
+        })
+    }
+}
+
+impl RedisStore {
+    // SCAN en lugar de KEYS para no bloquear Redis con bases grandes.
+    async fn delete_prefixed(&self) -> io::Result<()> {
+        let pattern = format!("{}*", KEY_PREFIX);
+        let mut cursor = b"0".to_vec();
+
+        loop {
+            let reply = self
+                .command(&[b"SCAN", &cursor, b"MATCH", pattern.as_bytes(), b"COUNT", b"500"])
+                .await?;
+            let Reply::Array(mut parts) = reply else {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "Unexpected SCAN reply"));
+            };
+            let (Some(Reply::Array(keys)), Some(Reply::Bulk(Some(next)))) = (parts.pop(), parts.pop()) else {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "Unexpected SCAN reply"));
+            };
+
+            let keys: Vec<Vec<u8>> = keys
+                .into_iter()
+                .filter_map(|k| match k {
+                    Reply::Bulk(Some(key)) => Some(key),
+                    _ => None,
+                })
+                .collect();
+            if !keys.is_empty() {
+                let mut args: Vec<&[u8]> = vec![b"DEL"];
+                args.extend(keys.iter().map(Vec::as_slice));
+                self.command(&args).await?;
+            }
+
+            if next == b"0" {
+                return Ok(());
+            }
+            cursor = next;
+        }
+    }
+}
+
+// Formato del valor: "<stored_at en ms> <etag>\n<datos>".
+fn encode_entry(entry: &CacheEntry) -> Vec<u8> {
+    let stored_at = entry
+        .stored_at
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let mut value = format!("{} {}\n", stored_at, entry.etag).into_bytes();
+    value.extend_from_slice(&entry.data);
+    value
+}
+
+fn decode_entry(raw: &[u8]) -> Option<CacheEntry> {
+    let newline = raw.iter().position(|b| *b == b'\n')?;
+    let header = std::str::from_utf8(&raw[..newline]).ok()?;
+    let (stored_at, etag) = header.split_once(' ')?;
+    let stored_at = UNIX_EPOCH + Duration::from_millis(stored_at.parse().ok()?);
+
+    Some(CacheEntry {
+        data: raw[newline + 1..].to_vec(),
+        etag: etag.to_string(),
+        stored_at: stored_at.min(SystemTime::now()),
+    })
+}
+
+async fn send(conn: &mut BufStream<TcpStream>, args: &[&[u8]]) -> io::Result<Reply> {
+    let mut request = format!("*{}\r\n", args.len()).into_bytes();
+    for arg in args {
+        request.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+        request.extend_from_slice(arg);
+        request.extend_from_slice(b"\r\n");
+    }
+    conn.write_all(&request).await?;
+    conn.flush().await?;
+
+    read_reply(conn).await
+}
+
+fn read_reply(conn: &mut BufStream<TcpStream>) -> StoreFuture<'_, io::Result<Reply>> {
+    Box::pin(read_reply_inner(conn))
+}
+
+async fn read_reply_inner(conn: &mut BufStream<TcpStream>) -> io::Result<Reply> {
+    let mut line = String::new();
+    if conn.read_line(&mut line).await? == 0 {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Redis closed the connection"));
+    }
+
+    let line = line.trim_end();
+    let (kind, rest) = line.split_at(line.len().min(1));
+    match kind {
+        "+" => Ok(Reply::Simple),
+        ":" => Ok(Reply::Integer),
+        "-" => Err(io::Error::other(format!("Redis: {}", rest))),
+        "$" => {
+            let len: i64 = rest.parse().map_err(|_| invalid_reply(line))?;
+            if len < 0 {
+                return Ok(Reply::Bulk(None));
+            }
+            let mut data = vec![0; len as usize + 2];
+            conn.read_exact(&mut data).await?;
+            data.truncate(len as usize);
+            Ok(Reply::Bulk(Some(data)))
+        }
+        "*" => {
+            let len: i64 = rest.parse().map_err(|_| invalid_reply(line))?;
+            let mut items = vec![];
+            for _ in 0..len.max(0) {
+                items.push(read_reply(conn).await?);
+            }
+            Ok(Reply::Array(items))
+        }
+        _ => Err(invalid_reply(line)),
+    }
+}
+
+fn invalid_reply(line: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("Unexpected Redis reply: {}", line))
+}
@@ -0,0 +1,68 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::OnceCell;
+
+/// Deduplica peticiones concurrentes por clave: mientras una está en vuelo,
+/// el resto espera y recibe una copia del mismo resultado.
+pub struct SingleFlight<T> {
+    inflight: Mutex<HashMap<String, Arc<OnceCell<T>>>>,
+}
+
+impl<T: Clone> Default for SingleFlight<T> {
+    fn default() -> Self {
+        Self {
+            inflight: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<T: Clone> SingleFlight<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn run<F, Fut>(&self, key: &str, f: F) -> T
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let cell = self
+            .inflight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(key.to_string())
+            .or_insert_with(|| Arc::new(OnceCell::new()))
+            .clone();
+
+        // Quita la celda ya resuelta aunque este future se cancele, para que
+        // la siguiente llamada vuelva a ejecutar `f`.
+        let _guard = Forget { flight: self, key, cell: &cell };
+
+        // Si quien inicia la petición se cancela, otro de los que esperan la retoma.
+        cell.get_or_init(f).await.clone()
+    }
+}
+
+struct Forget<'a, T> {
+    flight: &'a SingleFlight<T>,
+    key: &'a str,
+    cell: &'a Arc<OnceCell<T>>,
+}
+
+impl<T> Drop for Forget<'_, T> {
+    fn drop(&mut self) {
+        if !self.cell.initialized() {
+            return;
+        }
+        let mut inflight = self.flight.inflight.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(current) = inflight.get(self.key)
+            && Arc::ptr_eq(current, self.cell)
+        {
+            inflight.remove(self.key);
+        }
+    }
+}
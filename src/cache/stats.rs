@@ -0,0 +1,116 @@
+use std::{
+    collections::BTreeMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use axum::Json;
+use serde::Serialize;
+
+use super::{disk, StoreStats};
+use crate::metrics;
+
+// Tipos de caché que se listan aunque todavía no tengan entradas.
+const CLASSES: &[&str] = &["manifest", "version", "version_negative", "bundle", "maven", "bedrock", "java", "assets", "experimental"];
+
+/// Foto de la caché para ajustar TTLs: tamaño, aciertos y edades por tipo.
+#[derive(Debug, Serialize)]
+pub struct CacheSnapshot {
+    pub store: StoreStats,
+    pub caches: BTreeMap<&'static str, ClassStats>,
+    /// Artefactos del proxy guardados en disco.
+    pub artifacts: ClassStats,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ClassStats {
+    /// `null` si el backend no puede listar sus entradas (Redis).
+    pub entries: Option<usize>,
+    /// Estimación en bytes, incluida la sobrecarga por entrada.
+    pub bytes: Option<u64>,
+    pub oldest_age_secs: Option<u64>,
+    pub newest_age_secs: Option<u64>,
+    pub hits: u64,
+    pub misses: u64,
+    /// Aciertos sobre el total de consultas; `null` sin consultas.
+    pub hit_ratio: Option<f64>,
+    pub last_refresh_unix: Option<u64>,
+    pub last_refresh_age_secs: Option<u64>,
+}
+
+impl ClassStats {
+    fn lookups(mut self, hits: u64, misses: u64) -> Self {
+        self.hits = hits;
+        self.misses = misses;
+        let total = hits + misses;
+        self.hit_ratio = (total > 0).then(|| hits as f64 / total as f64);
+        self
+    }
+
+    fn refreshed(mut self, at: Option<SystemTime>) -> Self {
+        self.last_refresh_unix = at.and_then(|t| t.duration_since(UNIX_EPOCH).ok()).map(|d| d.as_secs());
+        self.last_refresh_age_secs = at.and_then(|t| t.elapsed().ok()).map(|d| d.as_secs());
+        self
+    }
+
+    fn add(&mut self, bytes: u64, age: Duration) {
+        *self.entries.get_or_insert(0) += 1;
+        *self.bytes.get_or_insert(0) += bytes;
+        let age = age.as_secs();
+        self.oldest_age_secs = self.oldest_age_secs.max(Some(age));
+        self.newest_age_secs = Some(self.newest_age_secs.map_or(age, |newest| newest.min(age)));
+    }
+}
+
+/// `GET /cache/stats`. Las entradas se leen de una vez bajo el lock del
+/// backend, así que los totales son coherentes entre sí.
+pub async fn cache_stats() -> Json<CacheSnapshot> {
+    let store = super::store();
+    let (stats, keys) = (store.stats().await, store.keys().await);
+
+    let mut caches: BTreeMap<&'static str, ClassStats> = CLASSES.iter().map(|c| (*c, ClassStats::default())).collect();
+    if let Some(keys) = keys {
+        for class in caches.values_mut() {
+            class.entries = Some(0);
+            class.bytes = Some(0);
+        }
+        for info in keys {
+            caches.entry(super::class(&info.key)).or_default().add(info.bytes as u64, info.age);
+        }
+    }
+
+    let counters = metrics::snapshot().counters;
+    let count = |name: &str, labels: &[(&str, &str)]| -> u64 {
+        counters
+            .iter()
+            .filter(|s| s.name == name && labels.iter().all(|(k, v)| s.labels.iter().any(|(lk, lv)| lk == k && lv == v)))
+            .map(|s| s.value)
+            .sum()
+    };
+
+    let caches = caches
+        .into_iter()
+        .map(|(class, stats)| {
+            let stats = stats
+                .lookups(count(metrics::CACHE_HITS, &[("cache", class)]), count(metrics::CACHE_MISSES, &[("cache", class)]))
+                .refreshed(super::last_refresh(class));
+            (class, stats)
+        })
+        .collect();
+
+    let on_disk = disk::artifact_stats().await;
+    let artifacts = ClassStats {
+        entries: Some(on_disk.files),
+        bytes: Some(on_disk.bytes),
+        oldest_age_secs: on_disk.oldest.map(|d| d.as_secs()),
+        newest_age_secs: on_disk.newest.map(|d| d.as_secs()),
+        ..Default::default()
+    }
+    // Los errores y los checksums que no cuadran no son ni aciertos ni fallos.
+    .lookups(
+        count(metrics::PROXY_DOWNLOADS, &[("source", "disk"), ("result", "ok")]),
+        count(metrics::PROXY_DOWNLOADS, &[("source", "upstream"), ("result", "ok")]),
+    )
+    .refreshed(super::last_refresh("artifact"));
+
+    Json(CacheSnapshot { store: stats, caches, artifacts })
+}
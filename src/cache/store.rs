@@ -0,0 +1,211 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    pin::Pin,
+    time::{Duration, Instant, SystemTime},
+};
+
+use serde::Serialize;
+use tokio::sync::Mutex;
+
+use crate::metrics;
+
+pub type StoreFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Valor cacheado ya serializado, junto con su ETag y el momento en que se obtuvo.
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    pub data: Vec<u8>,
+    pub etag: String,
+    pub stored_at: SystemTime,
+}
+
+impl CacheEntry {
+    pub fn age(&self) -> Duration {
+        self.stored_at.elapsed().unwrap_or_default()
+    }
+}
+
+/// Tamaño actual de un backend; `None` si el backend no lo sabe.
+#[derive(Debug, Clone, Serialize)]
+pub struct StoreStats {
+    pub backend: &'static str,
+    pub entries: Option<usize>,
+    pub bytes: Option<usize>,
+    pub max_entries: Option<usize>,
+    pub max_bytes: Option<usize>,
+}
+
+/// Tamaño y edad de una entrada, para `/cache/stats`.
+#[derive(Debug, Clone)]
+pub struct KeyInfo {
+    pub key: String,
+    pub bytes: usize,
+    pub age: Duration,
+}
+
+/// Backend de caché compartido por el manifest y las versiones normalizadas.
+///
+/// El `ttl` de `set` es el tiempo tras el cual el backend puede descartar la
+/// entrada; la frescura la decide quien llama a partir de `CacheEntry::age`.
+pub trait CacheStore: Send + Sync {
+    fn get<'a>(&'a self, key: &'a str) -> StoreFuture<'a, Option<CacheEntry>>;
+    fn set<'a>(&'a self, key: &'a str, entry: CacheEntry, ttl: Duration) -> StoreFuture<'a, ()>;
+    fn invalidate<'a>(&'a self, key: &'a str) -> StoreFuture<'a, ()>;
+    /// Elimina todas las entradas de manifestor.
+    fn clear(&self) -> StoreFuture<'_, ()>;
+    fn stats(&self) -> StoreFuture<'_, StoreStats>;
+    /// Entradas vigentes, leídas de una vez; `None` si el backend no puede
+    /// listarlas de forma barata.
+    fn keys(&self) -> StoreFuture<'_, Option<Vec<KeyInfo>>>;
+}
+
+// Coste fijo aproximado por entrada (HashMap, BTreeMap, SystemTime...).
+const ENTRY_OVERHEAD: usize = 64;
+
+/// Caché en memoria con expulsión LRU, acotada en número de entradas y en
+/// una estimación del total de bytes; las entradas vencidas se eliminan de
+/// forma perezosa.
+pub struct MemoryStore {
+    max_entries: usize,
+    max_bytes: usize,
+    lru: Mutex<Lru>,
+}
+
+#[derive(Default)]
+struct Lru {
+    slots: HashMap<String, Slot>,
+    // Orden de uso: el tick más bajo es el menos usado recientemente.
+    order: BTreeMap<u64, String>,
+    tick: u64,
+    bytes: usize,
+}
+
+struct Slot {
+    entry: CacheEntry,
+    expires_at: Instant,
+    tick: u64,
+    size: usize,
+}
+
+impl MemoryStore {
+    pub fn new(max_entries: usize, max_bytes: usize) -> Self {
+        Self {
+            max_entries: max_entries.max(1),
+            max_bytes,
+            lru: Mutex::new(Lru::default()),
+        }
+    }
+}
+
+impl Lru {
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Slot> {
+        let slot = self.slots.remove(key)?;
+        self.order.remove(&slot.tick);
+        self.bytes -= slot.size;
+        Some(slot)
+    }
+
+    fn insert(&mut self, key: String, slot: Slot) {
+        self.remove(&key);
+        self.bytes += slot.size;
+        self.order.insert(slot.tick, key.clone());
+        self.slots.insert(key, slot);
+    }
+
+    fn evict(&mut self, max_entries: usize, max_bytes: usize) {
+        while self.slots.len() > max_entries || (self.bytes > max_bytes && self.slots.len() > 1) {
+            let Some((_, key)) = self.order.pop_first() else {
+                break;
+            };
+            if let Some(slot) = self.slots.remove(&key) {
+                self.bytes -= slot.size;
+                metrics::increment_counter(metrics::CACHE_EVICTIONS, &[]);
+            }
+        }
+    }
+}
+
+impl CacheStore for MemoryStore {
+    fn get<'a>(&'a self, key: &'a str) -> StoreFuture<'a, Option<CacheEntry>> {
+        Box::pin(async move {
+            let mut lru = self.lru.lock().await;
+            let tick = lru.next_tick();
+            let lru = &mut *lru;
+
+            let slot = lru.slots.get_mut(key)?;
+            if Instant::now() >= slot.expires_at {
+                lru.remove(key);
+                return None;
+            }
+
+            lru.order.remove(&slot.tick);
+            lru.order.insert(tick, key.to_string());
+            slot.tick = tick;
+            Some(slot.entry.clone())
+        })
+    }
+
+    fn set<'a>(&'a self, key: &'a str, entry: CacheEntry, ttl: Duration) -> StoreFuture<'a, ()> {
+        Box::pin(async move {
+            let size = key.len() + entry.data.len() + entry.etag.len() + ENTRY_OVERHEAD;
+            let mut lru = self.lru.lock().await;
+            let slot = Slot {
+                entry,
+                expires_at: Instant::now() + ttl,
+                tick: lru.next_tick(),
+                size,
+            };
+            lru.insert(key.to_string(), slot);
+            lru.evict(self.max_entries, self.max_bytes);
+        })
+    }
+
+    fn invalidate<'a>(&'a self, key: &'a str) -> StoreFuture<'a, ()> {
+        Box::pin(async move {
+            self.lru.lock().await.remove(key);
+        })
+    }
+
+    fn clear(&self) -> StoreFuture<'_, ()> {
+        Box::pin(async move {
+            *self.lru.lock().await = Lru::default();
+        })
+    }
+
+    fn stats(&self) -> StoreFuture<'_, StoreStats> {
+        Box::pin(async move {
+            let lru = self.lru.lock().await;
+            StoreStats {
+                backend: "memory",
+                entries: Some(lru.slots.len()),
+                bytes: Some(lru.bytes),
+                max_entries: Some(self.max_entries),
+                max_bytes: Some(self.max_bytes),
+            }
+        })
+    }
+
+    fn keys(&self) -> StoreFuture<'_, Option<Vec<KeyInfo>>> {
+        Box::pin(async move {
+            let lru = self.lru.lock().await;
+            let now = Instant::now();
+            let keys = lru
+                .slots
+                .iter()
+                .filter(|(_, slot)| now < slot.expires_at)
+                .map(|(key, slot)| KeyInfo {
+                    key: key.clone(),
+                    bytes: slot.size,
+                    age: slot.entry.age(),
+                })
+                .collect();
+            Some(keys)
+        })
+    }
+}
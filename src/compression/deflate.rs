@@ -0,0 +1,172 @@
+//! Compresor DEFLATE (RFC 1951) mínimo: LZ77 con cadenas de hash y códigos
+//! Huffman fijos, empaquetado como gzip (RFC 1952). Comprime bastante peor
+//! que zlib, pero el JSON de Mojang es muy repetitivo y con esto basta.
+
+const WINDOW: usize = 32 * 1024;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const MAX_CHAIN: usize = 64;
+const HASH_BITS: u32 = 15;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+    8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+
+/// Comprime `data` en formato gzip.
+pub fn gzip(data: &[u8]) -> Vec<u8> {
+    // Cabecera: magic, CM=deflate, sin flags, sin mtime, XFL=0, OS=desconocido.
+    let mut out = vec![0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff];
+    out.extend(deflate(data));
+    out.extend(crc32(data).to_le_bytes());
+    out.extend((data.len() as u32).to_le_bytes());
+    out
+}
+
+/// Un único bloque final con códigos Huffman fijos.
+pub fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut bits = BitWriter::default();
+    bits.write(1, 1); // BFINAL
+    bits.write(1, 2); // BTYPE = 01
+
+    let mut head = vec![usize::MAX; 1 << HASH_BITS];
+    let mut prev = vec![usize::MAX; WINDOW];
+    let mut pos = 0;
+
+    while pos < data.len() {
+        let (len, dist) = longest_match(data, pos, &head, &prev);
+        let advance = if len >= MIN_MATCH {
+            write_match(&mut bits, len, dist);
+            len
+        } else {
+            write_literal(&mut bits, data[pos] as u16);
+            1
+        };
+
+        for p in pos..(pos + advance).min(data.len().saturating_sub(MIN_MATCH - 1)) {
+            let h = hash(data, p);
+            prev[p % WINDOW] = head[h];
+            head[h] = p;
+        }
+        pos += advance;
+    }
+
+    write_literal(&mut bits, 256);
+    bits.finish()
+}
+
+fn hash(data: &[u8], pos: usize) -> usize {
+    let v = u32::from(data[pos]) << 16 | u32::from(data[pos + 1]) << 8 | u32::from(data[pos + 2]);
+    (v.wrapping_mul(2654435761) >> (32 - HASH_BITS)) as usize
+}
+
+fn longest_match(data: &[u8], pos: usize, head: &[usize], prev: &[usize]) -> (usize, usize) {
+    if pos + MIN_MATCH > data.len() {
+        return (0, 0);
+    }
+
+    let max_len = MAX_MATCH.min(data.len() - pos);
+    let (mut best_len, mut best_dist) = (0, 0);
+    let mut candidate = head[hash(data, pos)];
+
+    for _ in 0..MAX_CHAIN {
+        if candidate == usize::MAX || candidate >= pos || pos - candidate > WINDOW {
+            break;
+        }
+        let len = data[candidate..]
+            .iter()
+            .zip(&data[pos..pos + max_len])
+            .take_while(|(a, b)| a == b)
+            .count();
+        if len > best_len {
+            best_len = len;
+            best_dist = pos - candidate;
+            if len == max_len {
+                break;
+            }
+        }
+
+        let next = prev[candidate % WINDOW];
+        // Una entrada más nueva pisó este hueco del buffer circular.
+        if next != usize::MAX && next >= candidate {
+            break;
+        }
+        candidate = next;
+    }
+
+    (best_len, best_dist)
+}
+
+fn write_literal(bits: &mut BitWriter, symbol: u16) {
+    let (code, len) = match symbol {
+        0..=143 => (0x30 + symbol, 8),
+        144..=255 => (0x190 + symbol - 144, 9),
+        256..=279 => (symbol - 256, 7),
+        _ => (0xc0 + symbol - 280, 8),
+    };
+    bits.write_huffman(code, len);
+}
+
+fn write_match(bits: &mut BitWriter, len: usize, dist: usize) {
+    let li = LENGTH_BASE.iter().rposition(|&base| base as usize <= len).unwrap_or(0);
+    write_literal(bits, 257 + li as u16);
+    bits.write((len - LENGTH_BASE[li] as usize) as u32, LENGTH_EXTRA[li]);
+
+    let di = DIST_BASE.iter().rposition(|&base| base as usize <= dist).unwrap_or(0);
+    bits.write_huffman(di as u16, 5);
+    bits.write((dist - DIST_BASE[di] as usize) as u32, DIST_EXTRA[di]);
+}
+
+#[derive(Default)]
+struct BitWriter {
+    out: Vec<u8>,
+    acc: u64,
+    count: u8,
+}
+
+impl BitWriter {
+    // DEFLATE empaqueta desde el bit menos significativo.
+    fn write(&mut self, value: u32, count: u8) {
+        self.acc |= u64::from(value) << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.out.push(self.acc as u8);
+            self.acc >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    // Los códigos Huffman, en cambio, van del bit más significativo.
+    fn write_huffman(&mut self, code: u16, len: u8) {
+        let reversed = code.reverse_bits() >> (16 - len);
+        self.write(u32::from(reversed), len);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.out.push(self.acc as u8);
+        }
+        self.out
+    }
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    static TABLE: once_cell::sync::Lazy<[u32; 256]> = once_cell::sync::Lazy::new(|| {
+        let mut table = [0u32; 256];
+        for (i, entry) in table.iter_mut().enumerate() {
+            let mut c = i as u32;
+            for _ in 0..8 {
+                c = if c & 1 != 0 { 0xedb88320 ^ (c >> 1) } else { c >> 1 };
+            }
+            *entry = c;
+        }
+        table
+    });
+
+    !data.iter().fold(!0u32, |crc, &b| TABLE[((crc ^ u32::from(b)) & 0xff) as usize] ^ (crc >> 8))
+}
@@ -0,0 +1,125 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{
+        header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, ETAG, IF_NONE_MATCH, VARY},
+        HeaderMap, HeaderValue,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use reqwest::StatusCode;
+
+use crate::state::AppState;
+
+pub mod deflate;
+
+// Sufijo del ETag de la variante comprimida, como hace Apache.
+const GZIP_SUFFIX: &str = "-gzip";
+// A partir de este tamaño se comprime en el pool de bloqueo, para no
+// retener el worker async (un JSON de versión ronda los 100 KiB).
+const BLOCKING_MIN_SIZE: usize = 32 * 1024;
+
+/// Comprime con gzip las respuestas JSON y de texto si el cliente lo acepta.
+///
+/// Solo hay gzip: sin `flate2`/`brotli` disponibles el compresor es propio
+/// (ver `deflate`). Los streams (artefactos del proxy, eventos SSE) no se
+/// tocan porque habría que leerlos enteros.
+pub async fn compress(State(state): State<AppState>, mut request: Request, next: Next) -> Response {
+    let settings = state.settings();
+    let config = &settings.compression;
+    let accepts_gzip = config.enabled && accepts_gzip(request.headers());
+
+    // El ETag comprimido lleva sufijo; la capa de ETag compara sin él.
+    let had_suffix = accepts_gzip && strip_etag_suffix(request.headers_mut());
+
+    let mut response = next.run(request).await;
+    if response.status() == StatusCode::NOT_MODIFIED {
+        if had_suffix {
+            tag_etag(response.headers_mut());
+        }
+        return response;
+    }
+    if !config.enabled || !compressible(response.headers()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    parts.headers.append(VARY, HeaderValue::from_static("accept-encoding"));
+    if !accepts_gzip || parts.headers.contains_key(CONTENT_ENCODING) {
+        return Response::from_parts(parts, body);
+    }
+
+    let Ok(bytes) = to_bytes(body, usize::MAX).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    if bytes.len() < config.min_size {
+        return Response::from_parts(parts, Body::from(bytes));
+    }
+
+    let compressed = if bytes.len() >= BLOCKING_MIN_SIZE {
+        match tokio::task::spawn_blocking(move || deflate::gzip(&bytes)).await {
+            Ok(compressed) => compressed,
+            Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        }
+    } else {
+        deflate::gzip(&bytes)
+    };
+    parts.headers.insert(CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+    parts.headers.remove(CONTENT_LENGTH);
+    tag_etag(&mut parts.headers);
+    Response::from_parts(parts, Body::from(compressed))
+}
+
+fn accepts_gzip(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|coding| {
+            let mut params = coding.split(';').map(str::trim);
+            let name = params.next().unwrap_or_default();
+            let disabled = params.any(|p| p.strip_prefix("q=").is_some_and(|q| q.parse::<f32>() == Ok(0.0)));
+            (name.eq_ignore_ascii_case("gzip") || name == "*") && !disabled
+        })
+}
+
+fn compressible(headers: &HeaderMap) -> bool {
+    let Some(content_type) = headers.get(CONTENT_TYPE).and_then(|v| v.to_str().ok()) else {
+        return false;
+    };
+    (content_type.starts_with("application/json") || content_type.starts_with("text/"))
+        && !content_type.starts_with("text/event-stream")
+}
+
+/// Devuelve `true` si el `If-None-Match` traía ETags de la variante gzip.
+fn strip_etag_suffix(headers: &mut HeaderMap) -> bool {
+    let suffixed = format!("{}\"", GZIP_SUFFIX);
+    let Some(current) = headers.get(IF_NONE_MATCH).and_then(|v| v.to_str().ok()) else {
+        return false;
+    };
+    if !current.contains(&suffixed) {
+        return false;
+    }
+
+    match HeaderValue::from_str(&current.replace(&suffixed, "\"")) {
+        Ok(value) => {
+            headers.insert(IF_NONE_MATCH, value);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+fn tag_etag(headers: &mut HeaderMap) {
+    let tagged = headers
+        .get(ETAG)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_suffix('"'))
+        .map(|v| format!("{}{}\"", v, GZIP_SUFFIX))
+        .and_then(|v| HeaderValue::from_str(&v).ok());
+    if let Some(value) = tagged {
+        headers.insert(ETAG, value);
+    }
+}
@@ -0,0 +1,113 @@
+//! Límite de peticiones simultáneas por grupo de rutas. Las que llegan con el
+//! grupo lleno no esperan: se responde 503 con `Retry-After`, así una ráfaga
+//! cara (normalizaciones, descargas) no deja sin servicio al resto.
+
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::{Body, Bytes, HttpBody},
+    extract::Request,
+    http::header::RETRY_AFTER,
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Router,
+};
+use http_body::{Frame, SizeHint};
+use reqwest::StatusCode;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::config::ConcurrencySettings;
+use crate::metrics;
+
+/// Grupos de rutas con límite propio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Group {
+    Manifest,
+    Normalize,
+    Proxy,
+}
+
+impl Group {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Group::Manifest => "manifest",
+            Group::Normalize => "normalize",
+            Group::Proxy => "proxy",
+        }
+    }
+
+    fn limit(self, settings: &ConcurrencySettings) -> usize {
+        match self {
+            Group::Manifest => settings.manifest,
+            Group::Normalize => settings.normalize,
+            Group::Proxy => settings.proxy,
+        }
+    }
+}
+
+/// Aplica el límite del grupo a todas las rutas de `router`, que comparten
+/// un mismo semáforo. El permiso se suelta al terminar de enviar el cuerpo,
+/// no al tener las cabeceras: las descargas de `/proxy` cuentan mientras duran.
+pub fn limit<S>(router: Router<S>, group: Group, settings: &ConcurrencySettings) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let max = group.limit(settings);
+    if !settings.enabled || max == 0 {
+        return router;
+    }
+
+    let retry_after = settings.retry_after_secs.max(1);
+    let semaphore = Arc::new(Semaphore::new(max));
+    router.route_layer(middleware::from_fn(move |request: Request, next: Next| {
+        let semaphore = semaphore.clone();
+        async move {
+            let Ok(permit) = semaphore.try_acquire_owned() else {
+                return shed(group, retry_after);
+            };
+            next.run(request).await.map(|body| Body::new(PermitBody { inner: body, permit: Some(permit) }))
+        }
+    }))
+}
+
+/// Cuerpo que retiene el permiso del grupo hasta enviarse entero o soltarse
+/// (si el cliente se va a medias).
+struct PermitBody {
+    inner: Body,
+    permit: Option<OwnedSemaphorePermit>,
+}
+
+impl HttpBody for PermitBody {
+    type Data = Bytes;
+    type Error = axum::Error;
+
+    fn poll_frame(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, axum::Error>>> {
+        let frame = Pin::new(&mut self.inner).poll_frame(cx);
+        if matches!(frame, Poll::Ready(None)) || self.inner.is_end_stream() {
+            self.permit = None;
+        }
+        frame
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+fn shed(group: Group, retry_after: u64) -> Response {
+    metrics::increment_counter(metrics::LOAD_SHED, &[("group", group.as_str())]);
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(RETRY_AFTER, retry_after.to_string())],
+        "Servicio saturado, inténtalo más tarde",
+    )
+        .into_response()
+}
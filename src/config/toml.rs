@@ -0,0 +1,203 @@
+use serde_json::{Map, Number, Value};
+
+// Subconjunto de TOML suficiente para el archivo de configuración: tablas
+// (`[a]`, `[a.b]`), pares `clave = valor`, strings básicos y literales,
+// enteros, flotantes, booleanos y arrays (también multilínea).
+
+pub fn parse(input: &str) -> Result<Value, String> {
+    let mut root = Map::new();
+    let mut current: Vec<String> = vec![];
+    let mut lines = input.lines().enumerate();
+
+    while let Some((index, raw)) = lines.next() {
+        let line_no = index + 1;
+        let mut line = strip_comment(raw).trim().to_string();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            let header = header
+                .strip_suffix(']')
+                .ok_or_else(|| format!("línea {}: cabecera de tabla sin cerrar", line_no))?;
+            current = header.split('.').map(|k| unquote_key(k.trim())).collect();
+            table_at(&mut root, &current).map_err(|e| format!("línea {}: {}", line_no, e))?;
+            continue;
+        }
+
+        let Some((key, _)) = line.split_once('=') else {
+            return Err(format!("línea {}: se esperaba `clave = valor`", line_no));
+        };
+        let key = unquote_key(key.trim());
+
+        // Arrays multilínea: acumular hasta cerrar los corchetes.
+        while bracket_depth(&line) > 0 {
+            let Some((_, next)) = lines.next() else {
+                return Err(format!("línea {}: array sin cerrar", line_no));
+            };
+            line.push(' ');
+            line.push_str(strip_comment(next).trim());
+        }
+
+        let (_, value) = line.split_once('=').unwrap_or_default();
+        let (value, rest) = parse_value(value.trim()).map_err(|e| format!("línea {}: {}", line_no, e))?;
+        if !rest.trim().is_empty() {
+            return Err(format!("línea {}: contenido inesperado tras el valor", line_no));
+        }
+
+        let table = table_at(&mut root, &current).map_err(|e| format!("línea {}: {}", line_no, e))?;
+        table.insert(key, value);
+    }
+
+    Ok(Value::Object(root))
+}
+
+/// Interpreta un valor suelto (p. ej. de una variable de entorno).
+pub fn parse_scalar(input: &str) -> Option<Value> {
+    match parse_value(input.trim()) {
+        Ok((value, rest)) if rest.trim().is_empty() => Some(value),
+        _ => None,
+    }
+}
+
+fn table_at<'a>(root: &'a mut Map<String, Value>, path: &[String]) -> Result<&'a mut Map<String, Value>, String> {
+    let mut table = root;
+    for key in path {
+        let entry = table.entry(key.clone()).or_insert_with(|| Value::Object(Map::new()));
+        table = entry
+            .as_object_mut()
+            .ok_or_else(|| format!("`{}` ya está definido y no es una tabla", key))?;
+    }
+    Ok(table)
+}
+
+fn unquote_key(key: &str) -> String {
+    key.trim_matches('"').trim_matches('\'').to_string()
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+
+    for (i, c) in line.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match (quote, c) {
+            (Some('"'), '\\') => escaped = true,
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '#') => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn bracket_depth(line: &str) -> i32 {
+    let (_, value) = line.split_once('=').unwrap_or_default();
+    let mut depth = 0;
+    let mut quote = None;
+    let mut escaped = false;
+
+    for c in value.chars() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match (quote, c) {
+            (Some('"'), '\\') => escaped = true,
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[') => depth += 1,
+            (None, ']') => depth -= 1,
+            _ => {}
+        }
+    }
+    depth
+}
+
+fn parse_value(input: &str) -> Result<(Value, &str), String> {
+    let Some(first) = input.chars().next() else {
+        return Err("falta el valor".to_string());
+    };
+
+    match first {
+        '"' => parse_basic_string(input),
+        '\'' => {
+            let end = input[1..].find('\'').ok_or("string literal sin cerrar")?;
+            Ok((Value::String(input[1..end + 1].to_string()), &input[end + 2..]))
+        }
+        '[' => parse_array(input),
+        _ => {
+            let end = input.find([',', ']']).unwrap_or(input.len());
+            let token = input[..end].trim();
+            let value = match token {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                _ => parse_number(token).ok_or_else(|| format!("valor no soportado: {}", token))?,
+            };
+            Ok((value, &input[end..]))
+        }
+    }
+}
+
+fn parse_number(token: &str) -> Option<Value> {
+    let clean = token.replace('_', "");
+    if let Ok(int) = clean.parse::<i64>() {
+        return Some(Value::Number(int.into()));
+    }
+    clean.parse::<f64>().ok().and_then(Number::from_f64).map(Value::Number)
+}
+
+fn parse_basic_string(input: &str) -> Result<(Value, &str), String> {
+    let mut out = String::new();
+    let mut chars = input.char_indices().skip(1);
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((Value::String(out), &input[i + 1..])),
+            '\\' => {
+                let (_, esc) = chars.next().ok_or("escape incompleto")?;
+                match esc {
+                    'n' => out.push('\n'),
+                    't' => out.push('\t'),
+                    'r' => out.push('\r'),
+                    '"' => out.push('"'),
+                    '\\' => out.push('\\'),
+                    'u' => {
+                        let hex: String = (0..4).filter_map(|_| chars.next().map(|(_, c)| c)).collect();
+                        let code = u32::from_str_radix(&hex, 16).map_err(|_| "escape \\u inválido")?;
+                        out.push(char::from_u32(code).ok_or("escape \\u inválido")?);
+                    }
+                    other => return Err(format!("escape no soportado: \\{}", other)),
+                }
+            }
+            c => out.push(c),
+        }
+    }
+
+    Err("string sin cerrar".to_string())
+}
+
+fn parse_array(input: &str) -> Result<(Value, &str), String> {
+    let mut items = vec![];
+    let mut rest = input[1..].trim_start();
+
+    loop {
+        if let Some(after) = rest.strip_prefix(']') {
+            return Ok((Value::Array(items), after));
+        }
+
+        let (item, after) = parse_value(rest)?;
+        items.push(item);
+        rest = after.trim_start();
+
+        if let Some(after) = rest.strip_prefix(',') {
+            rest = after.trim_start();
+        } else if !rest.starts_with(']') {
+            return Err("se esperaba `,` o `]` en el array".to_string());
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use std::{collections::HashSet, convert::Infallible, sync::Mutex, time::Duration};
+
+use axum::{
+    extract::State,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
+};
+use futures_util::stream::{self, Stream};
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+use crate::state::AppState;
+use crate::types::VersionManifest;
+
+// Eventos pendientes por suscriptor antes de que empiece a perderlos.
+const CHANNEL_CAPACITY: usize = 64;
+
+/// Aviso enviado a los clientes de `/events`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum VersionEvent {
+    VersionAdded {
+        id: String,
+        #[serde(rename = "type")]
+        version_type: String,
+        release_time: String,
+    },
+    LatestChanged {
+        release: String,
+        snapshot: String,
+    },
+}
+
+impl VersionEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            VersionEvent::VersionAdded { .. } => "version_added",
+            VersionEvent::LatestChanged { .. } => "latest_changed",
+        }
+    }
+}
+
+/// Canal de eventos entre el refresher y los clientes SSE.
+///
+/// Guarda el último manifest anunciado, de modo que las descargas hechas al
+/// atender peticiones no hacen que el refresher se salte versiones nuevas.
+pub struct EventBus {
+    sender: broadcast::Sender<VersionEvent>,
+    known: Mutex<Option<Snapshot>>,
+}
+
+struct Snapshot {
+    ids: HashSet<String>,
+    latest_release: String,
+    latest_snapshot: String,
+}
+
+impl Snapshot {
+    fn of(manifest: &VersionManifest) -> Self {
+        Self {
+            ids: manifest.versions.iter().map(|v| v.id.clone()).collect(),
+            latest_release: manifest.latest_release.clone(),
+            latest_snapshot: manifest.latest_snapshot.clone(),
+        }
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            sender,
+            known: Mutex::new(None),
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<VersionEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Toma un manifest como referencia sin anunciar nada, si aún no hay una.
+    pub fn seed(&self, manifest: &VersionManifest) {
+        if let Ok(mut known) = self.known.lock()
+            && known.is_none()
+        {
+            *known = Some(Snapshot::of(manifest));
+        }
+    }
+
+    /// Compara con el último manifest visto y publica lo que haya cambiado;
+    /// sin referencia previa solo la establece. Devuelve los eventos emitidos.
+    pub fn observe(&self, manifest: &VersionManifest) -> usize {
+        let events = {
+            let Ok(mut known) = self.known.lock() else {
+                return 0;
+            };
+            let events = known.as_ref().map(|previous| diff(previous, manifest)).unwrap_or_default();
+            *known = Some(Snapshot::of(manifest));
+            events
+        };
+
+        for event in &events {
+            // Sin suscriptores `send` falla; no es un error.
+            let _ = self.sender.send(event.clone());
+        }
+        events.len()
+    }
+}
+
+fn diff(previous: &Snapshot, manifest: &VersionManifest) -> Vec<VersionEvent> {
+    // El manifest viene de más nueva a más antigua; se anuncian en orden cronológico.
+    let mut events: Vec<VersionEvent> = manifest
+        .versions
+        .iter()
+        .rev()
+        .filter(|v| !previous.ids.contains(&v.id))
+        .map(|v| VersionEvent::VersionAdded {
+            id: v.id.clone(),
+            version_type: v.version_type.clone(),
+            release_time: v.release_time.clone(),
+        })
+        .collect();
+
+    if previous.latest_release != manifest.latest_release || previous.latest_snapshot != manifest.latest_snapshot {
+        events.push(VersionEvent::LatestChanged {
+            release: manifest.latest_release.clone(),
+            snapshot: manifest.latest_snapshot.clone(),
+        });
+    }
+    events
+}
+
+/// `GET /events`: flujo SSE con las versiones nuevas que detecta el refresher.
+pub async fn events(State(state): State<AppState>) -> impl IntoResponse {
+    Sse::new(event_stream(state.events.subscribe(), state.shutdown.clone())).keep_alive(KeepAlive::new().interval(Duration::from_secs(15)))
+}
+
+// Termina al apagar el servidor: si no, el drenaje esperaría a conexiones
+// que no se cierran nunca.
+fn event_stream(
+    receiver: broadcast::Receiver<VersionEvent>,
+    shutdown: CancellationToken,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    stream::unfold((receiver, shutdown), |(mut receiver, shutdown)| async move {
+        let event = next_event(&mut receiver, &shutdown).await?;
+        let sse = Event::default()
+            .event(event.name())
+            .json_data(&event)
+            .unwrap_or_else(|_| Event::default().comment("evento no serializable"));
+        Some((Ok(sse), (receiver, shutdown)))
+    })
+}
+
+/// Siguiente evento para un suscriptor; `None` al apagar el servidor o si el
+/// canal se cierra. Los eventos perdidos por ir atrasado solo se registran.
+pub async fn next_event(receiver: &mut broadcast::Receiver<VersionEvent>, shutdown: &CancellationToken) -> Option<VersionEvent> {
+    loop {
+        let received = tokio::select! {
+            received = receiver.recv() => received,
+            _ = shutdown.cancelled() => return None,
+        };
+        match received {
+            Ok(event) => return Some(event),
+            Err(RecvError::Lagged(skipped)) => {
+                warn!("Event subscriber lagged, {} events dropped", skipped);
+            }
+            Err(RecvError::Closed) => {
+                debug!("Event bus closed, ending event stream");
+                return None;
+            }
+        }
+    }
+}
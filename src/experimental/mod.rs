@@ -0,0 +1,97 @@
+//! Versiones que Mojang publicó fuera del manifest (combat tests, snapshots
+//! experimentales, algunas de April Fools), sacadas de los catálogos de
+//! `experimental.catalogs`. Se suman al manifest con `?include=experimental`
+//! y `/version/{id}` las encuentra aquí si no están en el de Mojang.
+
+use std::{collections::HashSet, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::cache;
+use crate::metrics;
+use crate::state::AppState;
+use crate::types::{MinecraftVersion, VersionManifest};
+
+const CACHE_KEY: &str = "experimental:catalog";
+// Cuánto se guarda el último catálogo para servirlo si una fuente falla.
+const RETENTION: Duration = Duration::from_secs(7 * 24 * 3600);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Catalog {
+    /// Fuentes con las que se construyó; si cambian en una recarga se descarta.
+    sources: Vec<String>,
+    versions: Vec<MinecraftVersion>,
+}
+
+/// `include=experimental` entre los valores separados por comas.
+pub fn requested(include: Option<&str>) -> bool {
+    include.is_some_and(|include| include.split(',').any(|v| v.trim() == "experimental"))
+}
+
+/// Versiones de todos los catálogos, sin repetir ids; la primera fuente gana.
+/// Una fuente que falla no impide servir las demás.
+pub async fn versions(state: &AppState) -> Vec<MinecraftVersion> {
+    let sources = state.settings().experimental.catalogs.clone();
+    if sources.is_empty() {
+        return vec![];
+    }
+
+    let cached = cache::get_json::<Catalog>(CACHE_KEY).await;
+    if let Some((catalog, _, age)) = &cached
+        && catalog.sources == sources
+        && *age < cache::settings().manifest_ttl()
+    {
+        metrics::cache_lookup("experimental", true);
+        return catalog.versions.clone();
+    }
+    metrics::cache_lookup("experimental", false);
+
+    let mut seen = HashSet::new();
+    let mut versions = vec![];
+    let mut failed = false;
+    for url in &sources {
+        metrics::increment_counter(metrics::UPSTREAM_REQUESTS, &[("target", "experimental")]);
+        match state.source.manifest(url).await {
+            Ok(catalog) => {
+                versions.extend(catalog.versions.into_iter().filter(|v| seen.insert(v.id.clone())));
+            }
+            Err(e) => {
+                metrics::increment_counter(metrics::UPSTREAM_ERRORS, &[("target", "experimental")]);
+                warn!("Experimental catalog {} fetch failed: {}", url, e);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        // Sin guardar, para reintentar en la próxima petición.
+        return match cached {
+            Some((catalog, _, _)) if catalog.sources == sources => catalog.versions,
+            _ => versions,
+        };
+    }
+    cache::set_json(CACHE_KEY, &Catalog { sources, versions: versions.clone() }, RETENTION).await;
+    versions
+}
+
+/// Entrada del catálogo para un id que no está en el manifest de Mojang.
+pub async fn find(state: &AppState, version_id: &str) -> Option<MinecraftVersion> {
+    versions(state).await.into_iter().find(|v| v.id == version_id)
+}
+
+/// Intercala las versiones experimentales en el manifest por fecha de
+/// publicación, sin reordenar las de Mojang. Los ids que Mojang ya lista se
+/// quedan con la entrada de Mojang.
+pub fn merge(manifest: &mut VersionManifest, experimental: Vec<MinecraftVersion>) {
+    let known: HashSet<String> = manifest.versions.iter().map(|v| v.id.clone()).collect();
+    for version in experimental.into_iter().filter(|v| !known.contains(&v.id)) {
+        // El manifest va de más reciente a más antigua; release_time es ISO 8601.
+        let at = manifest
+            .versions
+            .iter()
+            .position(|v| v.release_time <= version.release_time)
+            .unwrap_or(manifest.versions.len());
+        manifest.versions.insert(at, version);
+    }
+}
@@ -0,0 +1,656 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use axum::{
+    extract::{Query, State},
+    http::header::CONTENT_TYPE,
+    response::{IntoResponse, Response},
+    Json,
+};
+use futures_util::{future::BoxFuture, stream, StreamExt};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use tokio::sync::OnceCell;
+
+use crate::api::{filter_manifest, ManifestQuery};
+use crate::cache::{get_cached_manifest, Freshness};
+use crate::manifest::{assets::index_objects, fetch_version_manifest, lookup_version};
+use crate::mirror::MirrorChoice;
+use crate::profiles::{stored_profile, stored_profiles, ProfileSummary};
+use crate::state::AppState;
+use crate::types::{AssetIndex, MinecraftVersion, NormalizedVersion, VersionManifest};
+
+mod parser;
+
+use parser::{Directive, Field, Fragment, Selection, MAX_DEPTH};
+
+/// Esquema que sirve `/graphql`, también en `GET /graphql/schema`.
+pub const SCHEMA: &str = r#"type Query {
+  latest: Latest!
+  "Mismos filtros que GET /manifest; `type` admite varios separados por comas."
+  versions(type: String, since: String, limit: Int, offset: Int, sort: SortOrder): [Version!]!
+  version(id: String!): Version
+  "Registro de perfiles de /profiles; requiere `profiles.enabled`."
+  profiles: [Profile!]!
+  profile(name: String!): Profile
+}
+
+enum SortOrder { ASC DESC }
+
+type Latest {
+  release: String!
+  snapshot: String!
+}
+
+"Los campos del manifest salen gratis; el resto carga la versión normalizada."
+type Version {
+  id: String!
+  type: String!
+  release_time: String!
+  url: String!
+  sha1: String!
+  main_class: String
+  java_version: Int
+  compliance_level: Int
+  minimum_launcher_version: Int
+  legacy: Boolean!
+  client_jar: Download
+  server_jar: Download
+  asset_index: AssetIndex
+  libraries: [Library!]!
+  natives: [Native!]!
+  arguments: Arguments!
+  logging: Logging
+}
+
+type Download { url: String! sha1: String! size: Int! }
+
+type AssetIndex {
+  id: String!
+  url: String!
+  sha1: String!
+  size: Int!
+  "Carga el índice completo; como máximo 4 listas por consulta."
+  objects(prefix: String): [AssetObject!]!
+}
+
+type AssetObject { name: String! hash: String! size: Int! url: String! }
+type Library { name: String! url: String sha1: String size: Int path: String }
+type Native { name: String! classifier: String! url: String! sha1: String! size: Int! path: String! }
+type Arguments { game: [String!]! jvm: [String!]! }
+type Logging { argument: String! type: String! file: LoggingFile! }
+type LoggingFile { id: String! url: String! sha1: String! size: Int! }
+
+"Perfil subido a /profiles, ya resuelto y normalizado."
+type Profile {
+  name: String!
+  id: String!
+  inherits_from: String
+  etag: String!
+  uploaded_at: String!
+  type: String
+  release_time: String
+  main_class: String
+  java_version: Int
+  compliance_level: Int
+  minimum_launcher_version: Int
+  legacy: Boolean!
+  client_jar: Download
+  server_jar: Download
+  asset_index: AssetIndex
+  libraries: [Library!]!
+  natives: [Native!]!
+  arguments: Arguments!
+  logging: Logging
+}
+"#;
+
+const MAX_QUERY_BYTES: usize = 16 * 1024;
+// Versiones con detalles por consulta (como `/versions/batch`) y cuántas se
+// cargan a la vez.
+const MAX_DETAILED: usize = 64;
+const PARALLELISM: usize = 8;
+// Listas de objetos de assets por consulta: cada una carga un índice entero.
+const MAX_ASSET_LISTS: usize = 4;
+
+// Campo y, si es un objeto o una lista de objetos, su tipo.
+type FieldDef = (&'static str, Option<&'static str>);
+
+// Campos de cada tipo; `None` si el campo es escalar.
+const TYPES: &[(&str, &[FieldDef])] = &[
+    ("Latest", &[("release", None), ("snapshot", None)]),
+    (
+        "Version",
+        &[
+            ("id", None),
+            ("type", None),
+            ("release_time", None),
+            ("url", None),
+            ("sha1", None),
+            ("main_class", None),
+            ("java_version", None),
+            ("compliance_level", None),
+            ("minimum_launcher_version", None),
+            ("legacy", None),
+            ("client_jar", Some("Download")),
+            ("server_jar", Some("Download")),
+            ("asset_index", Some("AssetIndex")),
+            ("libraries", Some("Library")),
+            ("natives", Some("Native")),
+            ("arguments", Some("Arguments")),
+            ("logging", Some("Logging")),
+        ],
+    ),
+    ("Download", &[("url", None), ("sha1", None), ("size", None)]),
+    ("AssetIndex", &[("id", None), ("url", None), ("sha1", None), ("size", None), ("objects", Some("AssetObject"))]),
+    ("AssetObject", &[("name", None), ("hash", None), ("size", None), ("url", None)]),
+    ("Library", &[("name", None), ("url", None), ("sha1", None), ("size", None), ("path", None)]),
+    (
+        "Native",
+        &[("name", None), ("classifier", None), ("url", None), ("sha1", None), ("size", None), ("path", None)],
+    ),
+    ("Arguments", &[("game", None), ("jvm", None)]),
+    ("Logging", &[("argument", None), ("type", None), ("file", Some("LoggingFile"))]),
+    ("LoggingFile", &[("id", None), ("url", None), ("sha1", None), ("size", None)]),
+    (
+        "Profile",
+        &[
+            ("name", None),
+            ("id", None),
+            ("inherits_from", None),
+            ("etag", None),
+            ("uploaded_at", None),
+            ("type", None),
+            ("release_time", None),
+            ("main_class", None),
+            ("java_version", None),
+            ("compliance_level", None),
+            ("minimum_launcher_version", None),
+            ("legacy", None),
+            ("client_jar", Some("Download")),
+            ("server_jar", Some("Download")),
+            ("asset_index", Some("AssetIndex")),
+            ("libraries", Some("Library")),
+            ("natives", Some("Native")),
+            ("arguments", Some("Arguments")),
+            ("logging", Some("Logging")),
+        ],
+    ),
+];
+
+// Campos de `Version` que están en el manifest y no obligan a cargarla.
+const MANIFEST_FIELDS: &[&str] = &["id", "type", "release_time", "url", "sha1"];
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphQLRequest {
+    pub query: String,
+    #[serde(default)]
+    pub variables: Option<Map<String, Value>>,
+    pub operation_name: Option<String>,
+}
+
+/// Parámetros de `GET /graphql`; `variables` va como JSON.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphQLQuery {
+    pub query: String,
+    pub variables: Option<String>,
+    pub operation_name: Option<String>,
+}
+
+/// `POST /graphql`.
+pub async fn graphql_post(State(state): State<AppState>, mirror: MirrorChoice, Json(request): Json<GraphQLRequest>) -> Response {
+    run(&state, &mirror, request).await
+}
+
+/// `GET /graphql?query=...`.
+pub async fn graphql_get(State(state): State<AppState>, mirror: MirrorChoice, Query(query): Query<GraphQLQuery>) -> Response {
+    let variables = match query.variables.as_deref().map(serde_json::from_str::<Map<String, Value>>) {
+        None => None,
+        Some(Ok(variables)) => Some(variables),
+        Some(Err(e)) => return request_error(format!("`variables` no es un objeto JSON: {}", e)),
+    };
+    let request = GraphQLRequest { query: query.query, variables, operation_name: query.operation_name };
+    run(&state, &mirror, request).await
+}
+
+/// `GET /graphql/schema`: el esquema en SDL.
+pub async fn graphql_schema() -> impl IntoResponse {
+    ([(CONTENT_TYPE, "text/plain; charset=utf-8")], SCHEMA)
+}
+
+fn request_error(message: impl Into<String>) -> Response {
+    (StatusCode::BAD_REQUEST, Json(json!({ "errors": [{ "message": message.into() }] }))).into_response()
+}
+
+async fn run(state: &AppState, mirror: &MirrorChoice, request: GraphQLRequest) -> Response {
+    if request.query.len() > MAX_QUERY_BYTES {
+        return request_error(format!("La consulta supera los {} bytes", MAX_QUERY_BYTES));
+    }
+    let document = match parser::parse(&request.query) {
+        Ok(document) => document,
+        Err(msg) => return request_error(format!("Consulta inválida: {}", msg)),
+    };
+
+    let operation = match &request.operation_name {
+        Some(name) => document.operations.iter().find(|op| op.name.as_deref() == Some(name.as_str())),
+        None if document.operations.len() == 1 => document.operations.first(),
+        None => return request_error("Hay varias operaciones: falta `operationName`"),
+    };
+    let Some(operation) = operation else {
+        return request_error("No existe la operación pedida");
+    };
+    if operation.kind != "query" {
+        return request_error("Solo se admiten consultas (`query`)");
+    }
+
+    let mut variables = request.variables.unwrap_or_default();
+    for (name, default) in &operation.variables {
+        if let Some(default) = default
+            && !variables.contains_key(name)
+        {
+            variables.insert(name.clone(), default.resolve(&Map::new()));
+        }
+    }
+
+    let ctx = Ctx {
+        state,
+        mirror,
+        fragments: &document.fragments,
+        variables,
+        manifest: OnceCell::new(),
+        asset_lists: AtomicUsize::new(0),
+        errors: Mutex::new(vec![]),
+    };
+    let data = execute(&ctx, Node::Query, operation.selection.iter().collect(), vec![], 0).await;
+
+    let errors = ctx.errors.into_inner().unwrap_or_default();
+    let mut body = json!({ "data": data });
+    if !errors.is_empty() {
+        body["errors"] = Value::Array(errors);
+    }
+    Json(body).into_response()
+}
+
+struct Ctx<'a> {
+    state: &'a AppState,
+    mirror: &'a MirrorChoice,
+    fragments: &'a HashMap<String, Fragment>,
+    variables: Map<String, Value>,
+    manifest: OnceCell<Option<VersionManifest>>,
+    asset_lists: AtomicUsize,
+    errors: Mutex<Vec<Value>>,
+}
+
+impl Ctx<'_> {
+    fn error(&self, message: impl Into<String>, path: &[Value]) {
+        if let Ok(mut errors) = self.errors.lock() {
+            errors.push(json!({ "message": message.into(), "path": path }));
+        }
+    }
+
+    async fn manifest(&self) -> Result<&VersionManifest, String> {
+        let manifest = self
+            .manifest
+            .get_or_init(|| async {
+                let fetch_state = self.state.clone();
+                let cached = get_cached_manifest(move || async move { fetch_version_manifest(&fetch_state).await }).await;
+                (cached.freshness != Freshness::Unavailable).then_some(cached.data)
+            })
+            .await;
+        manifest.as_ref().ok_or_else(|| "Error obteniendo manifest".to_string())
+    }
+}
+
+#[derive(Clone)]
+enum Node {
+    Query,
+    Version(Arc<VersionNode>),
+    Json(&'static str, Value),
+}
+
+struct VersionNode {
+    entry: MinecraftVersion,
+    details: OnceCell<Result<Value, String>>,
+}
+
+impl Node {
+    fn version(entry: MinecraftVersion) -> Self {
+        Node::Version(Arc::new(VersionNode { entry, details: OnceCell::new() }))
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            Node::Query => "Query",
+            Node::Version(_) => "Version",
+            Node::Json(type_name, _) => type_name,
+        }
+    }
+}
+
+enum Output {
+    Scalar(Value),
+    Object(Option<Node>),
+    List(Vec<Node>),
+}
+
+type Fields<'a> = Vec<(String, Vec<&'a Field>)>;
+
+fn execute<'a>(
+    ctx: &'a Ctx<'a>,
+    node: Node,
+    selection: Vec<&'a Selection>,
+    path: Vec<Value>,
+    depth: usize,
+) -> BoxFuture<'a, Value> {
+    Box::pin(async move {
+        if depth >= MAX_DEPTH {
+            ctx.error(format!("La consulta anida más de {} niveles", MAX_DEPTH), &path);
+            return Value::Null;
+        }
+        let mut fields = vec![];
+        collect(ctx, node.type_name(), selection, &mut fields, &mut HashSet::new(), &path, 0);
+
+        let mut out = Map::new();
+        for (key, group) in fields {
+            let field = group[0];
+            let mut path = path.clone();
+            path.push(Value::String(key.clone()));
+            let selection: Vec<&Selection> = group.iter().flat_map(|f| &f.selection).collect();
+
+            let value = match resolve(ctx, &node, field, &selection).await {
+                Ok(output) => complete(ctx, field, output, selection, path, depth + 1).await,
+                Err(msg) => {
+                    ctx.error(msg, &path);
+                    Value::Null
+                }
+            };
+            out.insert(key, value);
+        }
+        Value::Object(out)
+    })
+}
+
+async fn complete<'a>(
+    ctx: &'a Ctx<'a>,
+    field: &Field,
+    output: Output,
+    selection: Vec<&'a Selection>,
+    path: Vec<Value>,
+    depth: usize,
+) -> Value {
+    let is_object = !matches!(output, Output::Scalar(_));
+    if is_object == selection.is_empty() {
+        let msg = if is_object {
+            format!("`{}` es un objeto: hay que elegir sus campos", field.name)
+        } else {
+            format!("`{}` es escalar y no admite selección de campos", field.name)
+        };
+        ctx.error(msg, &path);
+        return Value::Null;
+    }
+
+    match output {
+        Output::Scalar(value) => value,
+        Output::Object(None) => Value::Null,
+        Output::Object(Some(node)) => execute(ctx, node, selection, path, depth).await,
+        Output::List(nodes) => {
+            let items: Vec<Value> = stream::iter(nodes.into_iter().enumerate())
+                .map(|(i, node)| {
+                    let mut path = path.clone();
+                    path.push(Value::from(i));
+                    execute(ctx, node, selection.clone(), path, depth)
+                })
+                .buffered(PARALLELISM)
+                .collect()
+                .await;
+            Value::Array(items)
+        }
+    }
+}
+
+// Expande fragmentos y aplica `@skip`/`@include`; los campos con la misma
+// clave se agrupan y sus selecciones se unen. `depth` cuenta los fragmentos
+// anidados, que el parser no ve.
+fn collect<'a>(
+    ctx: &Ctx<'a>,
+    type_name: &str,
+    selection: Vec<&'a Selection>,
+    out: &mut Fields<'a>,
+    visited: &mut HashSet<&'a str>,
+    path: &[Value],
+    depth: usize,
+) {
+    if depth >= MAX_DEPTH {
+        ctx.error(format!("Los fragmentos anidan más de {} niveles", MAX_DEPTH), path);
+        return;
+    }
+    for item in selection {
+        match item {
+            Selection::Field(field) => {
+                if !included(ctx, &field.directives) {
+                    continue;
+                }
+                match out.iter_mut().find(|(key, _)| key == field.key()) {
+                    Some((_, group)) => group.push(field),
+                    None => out.push((field.key().to_string(), vec![field])),
+                }
+            }
+            Selection::Spread { name, directives } => {
+                if !included(ctx, directives) || !visited.insert(name.as_str()) {
+                    continue;
+                }
+                match ctx.fragments.get(name) {
+                    Some(fragment) if fragment.type_condition == type_name => {
+                        collect(ctx, type_name, fragment.selection.iter().collect(), out, visited, path, depth + 1);
+                    }
+                    Some(_) => {}
+                    None => ctx.error(format!("No existe el fragmento `{}`", name), path),
+                }
+            }
+            Selection::Inline { type_condition, directives, selection } => {
+                if included(ctx, directives) && type_condition.as_deref().is_none_or(|t| t == type_name) {
+                    collect(ctx, type_name, selection.iter().collect(), out, visited, path, depth + 1);
+                }
+            }
+        }
+    }
+}
+
+fn included(ctx: &Ctx<'_>, directives: &[Directive]) -> bool {
+    directives.iter().all(|directive| {
+        let condition = directive
+            .arguments
+            .iter()
+            .find(|(name, _)| name == "if")
+            .map(|(_, value)| value.resolve(&ctx.variables))
+            .and_then(|value| value.as_bool());
+        match directive.name.as_str() {
+            "skip" => condition != Some(true),
+            "include" => condition != Some(false),
+            _ => true,
+        }
+    })
+}
+
+fn arguments(ctx: &Ctx<'_>, field: &Field, allowed: &[&str]) -> Result<Map<String, Value>, String> {
+    let mut args = Map::new();
+    for (name, value) in &field.arguments {
+        if !allowed.contains(&name.as_str()) {
+            return Err(format!("`{}` no admite el argumento `{}`", field.name, name));
+        }
+        let value = value.resolve(&ctx.variables);
+        if !value.is_null() {
+            args.insert(name.clone(), value);
+        }
+    }
+    Ok(args)
+}
+
+async fn resolve(ctx: &Ctx<'_>, node: &Node, field: &Field, selection: &[&Selection]) -> Result<Output, String> {
+    if field.name == "__typename" {
+        return Ok(Output::Scalar(Value::String(node.type_name().to_string())));
+    }
+    if field.name.starts_with("__") {
+        return Err("Sin introspección: el esquema está en /graphql/schema".to_string());
+    }
+
+    match node {
+        Node::Query => resolve_query(ctx, field, selection).await,
+        Node::Version(version) => {
+            arguments(ctx, field, &[])?;
+            let entry = &version.entry;
+            let value = match field.name.as_str() {
+                "id" => &entry.id,
+                "type" => &entry.version_type,
+                "release_time" => &entry.release_time,
+                "url" => &entry.url,
+                "sha1" => &entry.hash,
+                _ => {
+                    let details = version.details.get_or_init(|| load_details(ctx, &entry.id)).await;
+                    return json_field(ctx, "Version", details.as_ref().map_err(Clone::clone)?, &field.name);
+                }
+            };
+            Ok(Output::Scalar(Value::String(value.clone())))
+        }
+        Node::Json("AssetIndex", index) if field.name == "objects" => asset_objects(ctx, field, index).await,
+        Node::Json(type_name, value) => {
+            arguments(ctx, field, &[])?;
+            json_field(ctx, type_name, value, &field.name)
+        }
+    }
+}
+
+async fn resolve_query(ctx: &Ctx<'_>, field: &Field, selection: &[&Selection]) -> Result<Output, String> {
+    match field.name.as_str() {
+        "latest" => {
+            arguments(ctx, field, &[])?;
+            let manifest = ctx.manifest().await?;
+            let latest = json!({ "release": manifest.latest_release, "snapshot": manifest.latest_snapshot });
+            Ok(Output::Object(Some(Node::Json("Latest", latest))))
+        }
+        "versions" => {
+            let mut args = arguments(ctx, field, &["type", "since", "limit", "offset", "sort"])?;
+            if let Some(Value::String(sort)) = args.get_mut("sort") {
+                *sort = sort.to_ascii_lowercase();
+            }
+            let query: ManifestQuery =
+                serde_json::from_value(Value::Object(args)).map_err(|e| format!("Argumentos inválidos: {}", e))?;
+            let page = filter_manifest(ctx.manifest().await?.clone(), &query);
+
+            if page.versions.len() > MAX_DETAILED && needs_details(ctx, selection) {
+                return Err(format!(
+                    "Como máximo {} versiones con campos que no están en el manifest; usa `limit`",
+                    MAX_DETAILED
+                ));
+            }
+            Ok(Output::List(page.versions.into_iter().map(|v| Node::version(mirrored(ctx, v))).collect()))
+        }
+        "version" => {
+            let args = arguments(ctx, field, &["id"])?;
+            let Some(id) = args.get("id").and_then(Value::as_str) else {
+                return Err("`version` necesita el argumento `id`".to_string());
+            };
+            let manifest = ctx.manifest().await?;
+            let entry = manifest.versions.iter().find(|v| v.id == id).cloned();
+            Ok(Output::Object(entry.map(|v| Node::version(mirrored(ctx, v)))))
+        }
+        "profiles" => {
+            arguments(ctx, field, &[])?;
+            let profiles = stored_profiles(ctx.state).await.ok_or_else(profiles_disabled)?;
+            Ok(Output::List(profiles.into_iter().map(profile_node).collect()))
+        }
+        "profile" => {
+            let args = arguments(ctx, field, &["name"])?;
+            let Some(name) = args.get("name").and_then(Value::as_str) else {
+                return Err("`profile` necesita el argumento `name`".to_string());
+            };
+            if !ctx.state.settings().profiles.enabled {
+                return Err(profiles_disabled());
+            }
+            Ok(Output::Object(stored_profile(ctx.state, name).await.map(profile_node)))
+        }
+        other => Err(format!("El tipo `Query` no tiene el campo `{}`", other)),
+    }
+}
+
+fn mirrored(ctx: &Ctx<'_>, mut entry: MinecraftVersion) -> MinecraftVersion {
+    if let Some(url) = ctx.mirror.0.as_ref().and_then(|m| m.rewrite(&entry.url)) {
+        entry.url = url;
+    }
+    entry
+}
+
+fn needs_details(ctx: &Ctx<'_>, selection: &[&Selection]) -> bool {
+    let mut fields = vec![];
+    collect(ctx, "Version", selection.to_vec(), &mut fields, &mut HashSet::new(), &[], 0);
+    fields
+        .iter()
+        .any(|(_, group)| !MANIFEST_FIELDS.contains(&group[0].name.as_str()) && group[0].name != "__typename")
+}
+
+fn profiles_disabled() -> String {
+    "El registro de perfiles no está activado (profiles.enabled)".to_string()
+}
+
+// Los campos del resumen van junto a los de la versión normalizada.
+fn profile_node((summary, version): (ProfileSummary, NormalizedVersion)) -> Node {
+    let mut value = serde_json::to_value(&version).unwrap_or_default();
+    if let (Value::Object(fields), Ok(Value::Object(summary))) = (&mut value, serde_json::to_value(&summary)) {
+        fields.extend(summary);
+    }
+    Node::Json("Profile", value)
+}
+
+// Las URLs se guardan tal cual y el mirror se aplica al devolverlas, así
+// los índices de assets se siguen pidiendo a su URL original.
+async fn load_details(ctx: &Ctx<'_>, id: &str) -> Result<Value, String> {
+    let version = lookup_version(ctx.state, id).await.map_err(|(_, msg)| msg)?.data;
+    serde_json::to_value(&version).map_err(|e| e.to_string())
+}
+
+async fn asset_objects(ctx: &Ctx<'_>, field: &Field, index: &Value) -> Result<Output, String> {
+    let args = arguments(ctx, field, &["prefix"])?;
+    if ctx.asset_lists.fetch_add(1, Ordering::SeqCst) >= MAX_ASSET_LISTS {
+        return Err(format!("Como máximo {} listas de objetos de assets por consulta", MAX_ASSET_LISTS));
+    }
+    let index: AssetIndex = serde_json::from_value(index.clone()).map_err(|e| e.to_string())?;
+    let prefix = args.get("prefix").and_then(Value::as_str).unwrap_or_default();
+
+    let objects = index_objects(ctx.state, &index).await.map_err(|(_, msg)| msg)?;
+    Ok(Output::List(
+        objects
+            .into_iter()
+            .filter(|object| object.name.starts_with(prefix))
+            .filter_map(|object| serde_json::to_value(object).ok())
+            .map(|object| Node::Json("AssetObject", object))
+            .collect(),
+    ))
+}
+
+fn json_field(ctx: &Ctx<'_>, type_name: &'static str, value: &Value, name: &str) -> Result<Output, String> {
+    let fields = TYPES.iter().find(|(t, _)| *t == type_name).map(|(_, f)| *f).unwrap_or_default();
+    let Some((_, child)) = fields.iter().find(|(field, _)| *field == name) else {
+        return Err(format!("El tipo `{}` no tiene el campo `{}`", type_name, name));
+    };
+
+    let mut value = value.get(name).cloned().unwrap_or(Value::Null);
+    if name == "url"
+        && let Some(url) = value.as_str().and_then(|url| ctx.mirror.0.as_ref()?.rewrite(url))
+    {
+        value = Value::String(url);
+    }
+    Ok(match (child, value) {
+        (None, value) => Output::Scalar(value),
+        (Some(child), Value::Array(items)) => Output::List(items.into_iter().map(|v| Node::Json(child, v)).collect()),
+        (Some(_), Value::Null) => Output::Object(None),
+        (Some(child), value) => Output::Object(Some(Node::Json(child, value))),
+    })
+}
@@ -0,0 +1,404 @@
+//! Parser de documentos ejecutables de GraphQL: operaciones, fragmentos,
+//! variables y directivas. No hay definiciones de tipos (SDL).
+
+use std::collections::HashMap;
+
+use serde_json::{Map, Number, Value};
+
+/// Anidamiento máximo de selecciones, listas, objetos y tipos: más allá, la
+/// recursión del parser (y la de la ejecución) podría agotar la pila.
+pub const MAX_DEPTH: usize = 32;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Punct(char),
+    Spread,
+    Name(String),
+    Int(i64),
+    Float(f64),
+    Str(String),
+}
+
+#[derive(Debug)]
+pub struct Document {
+    pub operations: Vec<Operation>,
+    pub fragments: HashMap<String, Fragment>,
+}
+
+#[derive(Debug)]
+pub struct Operation {
+    pub name: Option<String>,
+    /// `query`, `mutation` o `subscription`.
+    pub kind: String,
+    pub variables: Vec<(String, Option<Input>)>,
+    pub selection: Vec<Selection>,
+}
+
+#[derive(Debug)]
+pub struct Fragment {
+    pub type_condition: String,
+    pub selection: Vec<Selection>,
+}
+
+#[derive(Debug)]
+pub enum Selection {
+    Field(Field),
+    Spread { name: String, directives: Vec<Directive> },
+    Inline { type_condition: Option<String>, directives: Vec<Directive>, selection: Vec<Selection> },
+}
+
+#[derive(Debug)]
+pub struct Field {
+    pub alias: Option<String>,
+    pub name: String,
+    pub arguments: Vec<(String, Input)>,
+    pub directives: Vec<Directive>,
+    pub selection: Vec<Selection>,
+}
+
+impl Field {
+    /// Clave del campo en la respuesta.
+    pub fn key(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.name)
+    }
+}
+
+#[derive(Debug)]
+pub struct Directive {
+    pub name: String,
+    pub arguments: Vec<(String, Input)>,
+}
+
+/// Valor literal de un argumento; los enums se tratan como strings.
+#[derive(Debug, Clone)]
+pub enum Input {
+    Variable(String),
+    Const(Value),
+    List(Vec<Input>),
+    Object(Vec<(String, Input)>),
+}
+
+impl Input {
+    /// Sustituye las variables (las que faltan valen `null`).
+    pub fn resolve(&self, variables: &Map<String, Value>) -> Value {
+        match self {
+            Input::Variable(name) => variables.get(name).cloned().unwrap_or(Value::Null),
+            Input::Const(value) => value.clone(),
+            Input::List(items) => Value::Array(items.iter().map(|i| i.resolve(variables)).collect()),
+            Input::Object(fields) => Value::Object(fields.iter().map(|(k, v)| (k.clone(), v.resolve(variables))).collect()),
+        }
+    }
+}
+
+pub fn parse(source: &str) -> Result<Document, String> {
+    let tokens = lex(source)?;
+    let mut parser = Parser { tokens, pos: 0, depth: 0 };
+    let mut document = Document { operations: vec![], fragments: HashMap::new() };
+
+    while parser.peek().is_some() {
+        if parser.peek() == Some(&Token::Punct('{')) {
+            let selection = parser.selection_set()?;
+            document.operations.push(Operation { name: None, kind: "query".to_string(), variables: vec![], selection });
+            continue;
+        }
+        match parser.name()?.as_str() {
+            "fragment" => {
+                let name = parser.name()?;
+                if parser.name()? != "on" {
+                    return Err(format!("Se esperaba `on` en el fragmento `{}`", name));
+                }
+                let type_condition = parser.name()?;
+                parser.directives()?;
+                let selection = parser.selection_set()?;
+                if document.fragments.insert(name.clone(), Fragment { type_condition, selection }).is_some() {
+                    return Err(format!("Fragmento `{}` repetido", name));
+                }
+            }
+            kind @ ("query" | "mutation" | "subscription") => {
+                let name = match parser.peek() {
+                    Some(Token::Name(_)) => Some(parser.name()?),
+                    _ => None,
+                };
+                let variables = parser.variable_definitions()?;
+                parser.directives()?;
+                let selection = parser.selection_set()?;
+                document.operations.push(Operation { name, kind: kind.to_string(), variables, selection });
+            }
+            other => return Err(format!("Definición inesperada `{}`", other)),
+        }
+    }
+
+    if document.operations.is_empty() {
+        return Err("El documento no tiene ninguna operación".to_string());
+    }
+    Ok(document)
+}
+
+fn lex(source: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            ' ' | '\t' | '\n' | '\r' | ',' | '\u{feff}' => i += 1,
+            '#' => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '!' | '$' | '(' | ')' | ':' | '=' | '@' | '[' | ']' | '{' | '}' | '|' | '&' => {
+                tokens.push(Token::Punct(c));
+                i += 1;
+            }
+            '.' => {
+                if chars.get(i..i + 3) != Some(&['.', '.', '.']) {
+                    return Err("Se esperaba `...`".to_string());
+                }
+                tokens.push(Token::Spread);
+                i += 3;
+            }
+            '"' => {
+                let (value, next) = lex_string(&chars, i + 1)?;
+                tokens.push(Token::Str(value));
+                i = next;
+            }
+            c if c == '_' || c.is_ascii_alphabetic() => {
+                let start = i;
+                while i < chars.len() && (chars[i] == '_' || chars[i].is_ascii_alphanumeric()) {
+                    i += 1;
+                }
+                tokens.push(Token::Name(chars[start..i].iter().collect()));
+            }
+            c if c == '-' || c.is_ascii_digit() => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_digit() || matches!(chars[i], '.' | 'e' | 'E' | '+' | '-')) {
+                    i += 1;
+                }
+                let raw: String = chars[start..i].iter().collect();
+                let token = if raw.contains(['.', 'e', 'E']) {
+                    raw.parse().map(Token::Float).ok()
+                } else {
+                    raw.parse().map(Token::Int).ok()
+                };
+                tokens.push(token.ok_or_else(|| format!("Número inválido `{}`", raw))?);
+            }
+            other => return Err(format!("Carácter inesperado `{}`", other)),
+        }
+    }
+    Ok(tokens)
+}
+
+fn lex_string(chars: &[char], mut i: usize) -> Result<(String, usize), String> {
+    let mut value = String::new();
+    loop {
+        match chars.get(i) {
+            None | Some('\n') => return Err("String sin cerrar".to_string()),
+            Some('"') => return Ok((value, i + 1)),
+            Some('\\') => {
+                let escaped = match chars.get(i + 1) {
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some('r') => '\r',
+                    Some('b') => '\u{8}',
+                    Some('f') => '\u{c}',
+                    Some(c @ ('"' | '\\' | '/')) => *c,
+                    Some('u') => {
+                        let hex: String = chars.get(i + 2..i + 6).ok_or("Escape \\u incompleto")?.iter().collect();
+                        let code = u32::from_str_radix(&hex, 16).map_err(|_| "Escape \\u inválido")?;
+                        i += 4;
+                        char::from_u32(code).ok_or("Escape \\u inválido")?
+                    }
+                    _ => return Err("Escape inválido en string".to_string()),
+                };
+                value.push(escaped);
+                i += 2;
+            }
+            Some(c) => {
+                value.push(*c);
+                i += 1;
+            }
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<Token, String> {
+        let token = self.tokens.get(self.pos).cloned().ok_or("Fin inesperado del documento")?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(&Token::Punct(c)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.eat(c) { Ok(()) } else { Err(format!("Se esperaba `{}`", c)) }
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        match self.next()? {
+            Token::Name(name) => Ok(name),
+            other => Err(format!("Se esperaba un nombre, no {:?}", other)),
+        }
+    }
+
+    // Cuenta un nivel de anidamiento mientras se ejecuta `parse`.
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T, String>) -> Result<T, String> {
+        if self.depth >= MAX_DEPTH {
+            return Err(format!("La consulta anida más de {} niveles", MAX_DEPTH));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn selection_set(&mut self) -> Result<Vec<Selection>, String> {
+        self.nested(Self::selection_set_inner)
+    }
+
+    fn selection_set_inner(&mut self) -> Result<Vec<Selection>, String> {
+        self.expect('{')?;
+        let mut selection = vec![];
+        while !self.eat('}') {
+            selection.push(self.selection()?);
+        }
+        if selection.is_empty() {
+            return Err("Selección vacía".to_string());
+        }
+        Ok(selection)
+    }
+
+    fn selection(&mut self) -> Result<Selection, String> {
+        if self.peek() == Some(&Token::Spread) {
+            self.pos += 1;
+            return match self.peek() {
+                Some(Token::Name(name)) if name != "on" => {
+                    let name = self.name()?;
+                    Ok(Selection::Spread { name, directives: self.directives()? })
+                }
+                _ => {
+                    let type_condition = match self.peek() {
+                        Some(Token::Name(_)) => {
+                            self.name()?;
+                            Some(self.name()?)
+                        }
+                        _ => None,
+                    };
+                    let directives = self.directives()?;
+                    Ok(Selection::Inline { type_condition, directives, selection: self.selection_set()? })
+                }
+            };
+        }
+
+        let mut name = self.name()?;
+        let mut alias = None;
+        if self.eat(':') {
+            alias = Some(name);
+            name = self.name()?;
+        }
+        let arguments = self.arguments()?;
+        let directives = self.directives()?;
+        let selection = if self.peek() == Some(&Token::Punct('{')) { self.selection_set()? } else { vec![] };
+        Ok(Selection::Field(Field { alias, name, arguments, directives, selection }))
+    }
+
+    fn arguments(&mut self) -> Result<Vec<(String, Input)>, String> {
+        let mut arguments = vec![];
+        if self.eat('(') {
+            while !self.eat(')') {
+                let name = self.name()?;
+                self.expect(':')?;
+                arguments.push((name, self.value()?));
+            }
+        }
+        Ok(arguments)
+    }
+
+    fn directives(&mut self) -> Result<Vec<Directive>, String> {
+        let mut directives = vec![];
+        while self.eat('@') {
+            let name = self.name()?;
+            directives.push(Directive { name, arguments: self.arguments()? });
+        }
+        Ok(directives)
+    }
+
+    fn variable_definitions(&mut self) -> Result<Vec<(String, Option<Input>)>, String> {
+        let mut variables = vec![];
+        if self.eat('(') {
+            while !self.eat(')') {
+                self.expect('$')?;
+                let name = self.name()?;
+                self.expect(':')?;
+                self.skip_type()?;
+                let default = if self.eat('=') { Some(self.value()?) } else { None };
+                self.directives()?;
+                variables.push((name, default));
+            }
+        }
+        Ok(variables)
+    }
+
+    // Los tipos de las variables no se comprueban: cada campo valida sus argumentos.
+    fn skip_type(&mut self) -> Result<(), String> {
+        if self.eat('[') {
+            self.nested(Self::skip_type)?;
+            self.expect(']')?;
+        } else {
+            self.name()?;
+        }
+        self.eat('!');
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<Input, String> {
+        Ok(match self.next()? {
+            Token::Punct('$') => Input::Variable(self.name()?),
+            Token::Int(n) => Input::Const(Value::from(n)),
+            Token::Float(f) => Input::Const(Number::from_f64(f).map(Value::Number).unwrap_or(Value::Null)),
+            Token::Str(s) => Input::Const(Value::String(s)),
+            Token::Name(name) => Input::Const(match name.as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                "null" => Value::Null,
+                _ => Value::String(name),
+            }),
+            Token::Punct('[') => self.nested(|parser| {
+                let mut items = vec![];
+                while !parser.eat(']') {
+                    items.push(parser.value()?);
+                }
+                Ok(Input::List(items))
+            })?,
+            Token::Punct('{') => self.nested(|parser| {
+                let mut fields = vec![];
+                while !parser.eat('}') {
+                    let name = parser.name()?;
+                    parser.expect(':')?;
+                    fields.push((name, parser.value()?));
+                }
+                Ok(Input::Object(fields))
+            })?,
+            other => return Err(format!("Valor inesperado {:?}", other)),
+        })
+    }
+}
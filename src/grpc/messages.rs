@@ -0,0 +1,64 @@
+use manifestor_core::types::{DownloadPlan, JavaRuntime};
+
+use super::wire::Encoder;
+use crate::events::VersionEvent;
+use crate::types::VersionManifest;
+
+pub fn manifest(manifest: &VersionManifest, stale: bool) -> Encoder {
+    let mut message = Encoder::new();
+    message.string(1, &manifest.latest_release).string(2, &manifest.latest_snapshot);
+    for version in &manifest.versions {
+        let mut entry = Encoder::new();
+        entry
+            .string(1, &version.id)
+            .string(2, &version.version_type)
+            .string(3, &version.url)
+            .string(4, &version.release_time)
+            .string(5, &version.hash);
+        message.message(3, entry);
+    }
+    message.bool(4, stale);
+    message
+}
+
+pub fn version(id: &str, json: &[u8], etag: &str, stale: bool) -> Encoder {
+    let mut message = Encoder::new();
+    message.string(1, id).bytes(2, json).string(3, etag).bool(4, stale);
+    message
+}
+
+pub fn download_plan(plan: &DownloadPlan, java: &JavaRuntime) -> Encoder {
+    let mut message = Encoder::new();
+    message.string(1, &plan.version_id).string(2, &plan.platform);
+    for item in &plan.items {
+        let mut entry = Encoder::new();
+        entry
+            .string(1, &item.kind)
+            .string(2, &item.path)
+            .string(3, &item.url)
+            .string(4, item.sha1.as_deref().unwrap_or_default())
+            .uint64(5, item.size.unwrap_or(0));
+        message.message(3, entry);
+    }
+    message
+        .uint64(4, plan.total_size)
+        .string(5, &java.component)
+        .uint64(6, java.major_version as u64);
+    message
+}
+
+pub fn version_event(event: &VersionEvent) -> Encoder {
+    let mut message = Encoder::new();
+    let mut inner = Encoder::new();
+    match event {
+        VersionEvent::VersionAdded { id, version_type, release_time } => {
+            inner.string(1, id).string(2, version_type).string(3, release_time);
+            message.message(1, inner);
+        }
+        VersionEvent::LatestChanged { release, snapshot } => {
+            inner.string(1, release).string(2, snapshot);
+            message.message(2, inner);
+        }
+    }
+    message
+}
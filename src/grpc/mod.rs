@@ -0,0 +1,221 @@
+//! Servidor gRPC opcional (feature `grpc`) con el servicio de
+//! `proto/manifestor.proto`. Usa las mismas búsquedas en caché que la API
+//! HTTP; escucha aparte, en HTTP/2 sin TLS.
+
+use std::{convert::Infallible, net::SocketAddr};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::State,
+    http::{header::CONTENT_TYPE, HeaderMap, HeaderValue},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::post,
+    Router,
+};
+use futures_util::stream;
+use http_body::Frame;
+use http_body_util::StreamBody;
+use manifestor_core::{download_plan, Platform};
+use reqwest::StatusCode;
+use tokio::{net::TcpListener, task::JoinHandle};
+use tracing::{info, warn};
+
+use crate::cache::{self, get_cached_manifest, Freshness};
+use crate::events;
+use crate::manifest::{bundle::lookup_bundle, fetch_version_manifest, lookup_version};
+use crate::mirror::MirrorChoice;
+use crate::request_id::trace_request;
+use crate::state::AppState;
+
+mod messages;
+pub mod wire;
+
+const SERVICE: &str = "/manifestor.v1.Manifestor";
+
+/// Códigos de estado de gRPC que usa el servicio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Code {
+    InvalidArgument = 3,
+    NotFound = 5,
+    Unimplemented = 12,
+    Internal = 13,
+    Unavailable = 14,
+}
+
+#[derive(Debug)]
+pub struct Status {
+    pub code: Code,
+    pub message: String,
+}
+
+impl Status {
+    pub fn new(code: Code, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+// Los errores de los handlers HTTP, traducidos a su código gRPC.
+impl From<(StatusCode, String)> for Status {
+    fn from((status, message): (StatusCode, String)) -> Self {
+        let code = match status {
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => Code::InvalidArgument,
+            StatusCode::NOT_FOUND => Code::NotFound,
+            StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT => Code::Unavailable,
+            _ => Code::Internal,
+        };
+        Self::new(code, message)
+    }
+}
+
+// Respuesta "trailers-only": el estado va en las cabeceras y no hay cuerpo.
+impl IntoResponse for Status {
+    fn into_response(self) -> Response {
+        let mut headers = HeaderMap::new();
+        headers.insert("grpc-status", HeaderValue::from(self.code as u16));
+        if let Ok(message) = HeaderValue::from_str(&percent_encode(&self.message)) {
+            headers.insert("grpc-message", message);
+        }
+        let mut response = grpc_response(Body::empty());
+        response.headers_mut().extend(headers);
+        response
+    }
+}
+
+// `grpc-message` va con percent-encoding sobre UTF-8.
+fn percent_encode(message: &str) -> String {
+    message
+        .bytes()
+        .map(|b| match b {
+            b' '..=b'~' if b != b'%' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route(&format!("{}/GetManifest", SERVICE), post(get_manifest))
+        .route(&format!("{}/GetVersion", SERVICE), post(get_version))
+        .route(&format!("{}/GetDownloadPlan", SERVICE), post(get_download_plan))
+        .route(&format!("{}/WatchVersions", SERVICE), post(watch_versions))
+        .fallback(unimplemented)
+        .with_state(state)
+        .layer(middleware::from_fn(trace_request))
+}
+
+/// Abre el puerto gRPC y lo sirve en segundo plano hasta el apagado.
+pub async fn spawn(state: AppState, addr: SocketAddr) -> std::io::Result<JoinHandle<()>> {
+    let listener = TcpListener::bind(addr).await?;
+    info!("gRPC server started at {:?}", listener.local_addr()?);
+    let shutdown = state.shutdown.clone();
+    Ok(tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, router(state)).with_graceful_shutdown(shutdown.cancelled_owned()).await {
+            warn!("gRPC server stopped: {}", e);
+        }
+    }))
+}
+
+async fn unimplemented() -> Status {
+    Status::new(Code::Unimplemented, "Método no implementado")
+}
+
+fn grpc_response(body: Body) -> Response {
+    let mut response = Response::new(body);
+    response.headers_mut().insert(CONTENT_TYPE, HeaderValue::from_static("application/grpc"));
+    response
+}
+
+fn ok_trailers() -> HeaderMap {
+    let mut trailers = HeaderMap::new();
+    trailers.insert("grpc-status", HeaderValue::from(0));
+    trailers
+}
+
+fn unary(message: wire::Encoder) -> Response {
+    let frames = [Frame::data(wire::frame(&message.finish())), Frame::trailers(ok_trailers())];
+    let frames = stream::iter(frames.map(Ok::<_, Infallible>));
+    grpc_response(Body::new(StreamBody::new(frames)))
+}
+
+fn request_fields(body: &[u8]) -> Result<Vec<(u32, &[u8])>, Status> {
+    if body.first() == Some(&1) {
+        return Err(Status::new(Code::Unimplemented, "Mensajes comprimidos no soportados"));
+    }
+    let message = wire::unframe(body).map_err(|msg| Status::new(Code::InvalidArgument, msg))?;
+    wire::length_delimited_fields(message).map_err(|msg| Status::new(Code::InvalidArgument, msg))
+}
+
+fn required_string(fields: &[(u32, &[u8])], field: u32, name: &str) -> Result<String, Status> {
+    let value = wire::string_field(fields, field).map_err(|msg| Status::new(Code::InvalidArgument, msg))?;
+    if value.is_empty() {
+        return Err(Status::new(Code::InvalidArgument, format!("Falta el campo `{}`", name)));
+    }
+    Ok(value)
+}
+
+async fn get_manifest(State(state): State<AppState>, mirror: MirrorChoice, body: Bytes) -> Result<Response, Status> {
+    request_fields(&body)?;
+    let fetch_state = state.clone();
+    let cached = get_cached_manifest(move || async move { fetch_version_manifest(&fetch_state).await }).await;
+    if cached.freshness == Freshness::Unavailable {
+        return Err(Status::new(Code::Unavailable, "Error obteniendo manifest"));
+    }
+
+    let mut manifest = cached.data;
+    if let Some(mirror) = &mirror.0 {
+        for version in &mut manifest.versions {
+            if let Some(url) = mirror.rewrite(&version.url) {
+                version.url = url;
+            }
+        }
+    }
+    Ok(unary(messages::manifest(&manifest, cached.freshness != Freshness::Fresh)))
+}
+
+async fn get_version(State(state): State<AppState>, mirror: MirrorChoice, body: Bytes) -> Result<Response, Status> {
+    let fields = request_fields(&body)?;
+    let id = required_string(&fields, 1, "id")?;
+
+    let cached = lookup_version(&state, &id).await?;
+    let mut version = cached.data;
+    let etag = match &mirror.0 {
+        Some(m) => {
+            m.apply(&mut version);
+            cache::etag_for_json(&version)
+        }
+        None => cached.etag,
+    };
+    let json = serde_json::to_vec(&version).map_err(|e| Status::new(Code::Internal, e.to_string()))?;
+    Ok(unary(messages::version(&version.id, &json, &etag, cached.freshness != Freshness::Fresh)))
+}
+
+async fn get_download_plan(State(state): State<AppState>, mirror: MirrorChoice, body: Bytes) -> Result<Response, Status> {
+    let fields = request_fields(&body)?;
+    let id = required_string(&fields, 1, "id")?;
+    let platform = required_string(&fields, 2, "platform")?;
+    let platform = Platform::parse(&platform).map_err(|msg| Status::new(Code::InvalidArgument, msg))?;
+
+    let mut bundle = lookup_bundle(&state, &id, &platform).await?.data;
+    mirror.apply(&mut bundle.version);
+    let plan = download_plan(&bundle);
+    Ok(unary(messages::download_plan(&plan, &bundle.java_runtime)))
+}
+
+async fn watch_versions(State(state): State<AppState>, body: Bytes) -> Result<Response, Status> {
+    request_fields(&body)?;
+    let receiver = state.events.subscribe();
+    let frames = stream::unfold(Some((receiver, state.shutdown.clone())), |subscription| async move {
+        let (mut receiver, shutdown) = subscription?;
+        let frame = match events::next_event(&mut receiver, &shutdown).await {
+            Some(event) => {
+                let frame = Frame::data(wire::frame(&messages::version_event(&event).finish()));
+                return Some((Ok::<_, Infallible>(frame), Some((receiver, shutdown))));
+            }
+            // Apagado: se cierra el flujo con estado OK.
+            None => Frame::trailers(ok_trailers()),
+        };
+        Some((Ok(frame), None))
+    });
+    Ok(grpc_response(Body::new(StreamBody::new(frames))))
+}
@@ -0,0 +1,139 @@
+//! Lo justo de protobuf y del framing de gRPC para los mensajes de
+//! `proto/manifestor.proto`, sin generador de código.
+
+use axum::body::Bytes;
+
+const TRUNCATED: &str = "Mensaje protobuf truncado";
+
+/// Mensaje protobuf en construcción. Como en proto3, los escalares con el
+/// valor por defecto no se escriben.
+#[derive(Default)]
+pub struct Encoder {
+    buf: Vec<u8>,
+}
+
+impl Encoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn string(&mut self, field: u32, value: &str) -> &mut Self {
+        self.bytes(field, value.as_bytes())
+    }
+
+    pub fn bytes(&mut self, field: u32, value: &[u8]) -> &mut Self {
+        if !value.is_empty() {
+            self.length_delimited(field, value);
+        }
+        self
+    }
+
+    pub fn uint64(&mut self, field: u32, value: u64) -> &mut Self {
+        if value != 0 {
+            self.key(field, 0);
+            varint(&mut self.buf, value);
+        }
+        self
+    }
+
+    pub fn bool(&mut self, field: u32, value: bool) -> &mut Self {
+        self.uint64(field, value as u64)
+    }
+
+    /// Submensaje; se escribe aunque esté vacío (lo necesitan los `oneof`).
+    pub fn message(&mut self, field: u32, message: Encoder) -> &mut Self {
+        self.length_delimited(field, &message.buf);
+        self
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+
+    fn length_delimited(&mut self, field: u32, value: &[u8]) {
+        self.key(field, 2);
+        varint(&mut self.buf, value.len() as u64);
+        self.buf.extend_from_slice(value);
+    }
+
+    fn key(&mut self, field: u32, wire_type: u64) {
+        varint(&mut self.buf, ((field as u64) << 3) | wire_type);
+    }
+}
+
+fn varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn read_varint(input: &mut &[u8]) -> Result<u64, &'static str> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = input.split_first().ok_or(TRUNCATED)?;
+        *input = rest;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("Varint demasiado largo")
+}
+
+/// Campos de longitud delimitada (strings, bytes, submensajes) de un
+/// mensaje, en orden. Los demás tipos se validan y se saltan.
+pub fn length_delimited_fields(mut input: &[u8]) -> Result<Vec<(u32, &[u8])>, &'static str> {
+    let mut fields = vec![];
+    while !input.is_empty() {
+        let key = read_varint(&mut input)?;
+        let field = (key >> 3) as u32;
+        match key & 7 {
+            0 => {
+                read_varint(&mut input)?;
+            }
+            1 => input = input.get(8..).ok_or(TRUNCATED)?,
+            2 => {
+                let len = read_varint(&mut input)? as usize;
+                let value = input.get(..len).ok_or(TRUNCATED)?;
+                fields.push((field, value));
+                input = &input[len..];
+            }
+            5 => input = input.get(4..).ok_or(TRUNCATED)?,
+            _ => return Err("Tipo de campo protobuf no soportado"),
+        }
+    }
+    Ok(fields)
+}
+
+/// Último valor del campo `string` número `field` (vacío si no viene).
+pub fn string_field(fields: &[(u32, &[u8])], field: u32) -> Result<String, &'static str> {
+    let Some((_, value)) = fields.iter().rev().find(|(n, _)| *n == field) else {
+        return Ok(String::new());
+    };
+    String::from_utf8(value.to_vec()).map_err(|_| "Campo string con UTF-8 inválido")
+}
+
+/// Mensaje con la cabecera de gRPC: sin comprimir y longitud big-endian.
+pub fn frame(message: &[u8]) -> Bytes {
+    let mut framed = Vec::with_capacity(message.len() + 5);
+    framed.push(0);
+    framed.extend_from_slice(&(message.len() as u32).to_be_bytes());
+    framed.extend_from_slice(message);
+    Bytes::from(framed)
+}
+
+/// Mensaje de una petición unaria, sin la cabecera.
+pub fn unframe(body: &[u8]) -> Result<&[u8], &'static str> {
+    let [compressed, a, b, c, d, message @ ..] = body else {
+        return Err("Mensaje gRPC incompleto");
+    };
+    if *compressed != 0 {
+        return Err("Mensajes comprimidos no soportados");
+    }
+    if message.len() != u32::from_be_bytes([*a, *b, *c, *d]) as usize {
+        return Err("Se esperaba exactamente un mensaje gRPC");
+    }
+    Ok(message)
+}
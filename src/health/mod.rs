@@ -0,0 +1,117 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant, UNIX_EPOCH},
+};
+
+use axum::{extract::State, response::IntoResponse, Json};
+use once_cell::sync::Lazy;
+use reqwest::StatusCode;
+use serde::Serialize;
+
+use crate::cache::{self, MANIFEST_KEY};
+use crate::state::AppState;
+
+// Último sondeo a upstream: (URL sondeada, cuándo, resultado).
+static LAST_PROBE: Lazy<Mutex<Option<(String, Instant, Probe)>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Debug, Clone)]
+struct Probe {
+    reachable: bool,
+    latency_ms: Option<u64>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Readiness {
+    pub ready: bool,
+    pub cache_populated: bool,
+    pub upstream_reachable: bool,
+    pub upstream_latency_ms: Option<u64>,
+    pub upstream_error: Option<String>,
+    pub last_manifest_refresh_unix: Option<u64>,
+    pub last_manifest_refresh_age_secs: Option<u64>,
+}
+
+/// El proceso está vivo y atendiendo peticiones.
+pub async fn healthz() -> impl IntoResponse {
+    (StatusCode::OK, "ok")
+}
+
+/// Listo si hay un manifest en caché o Mojang responde dentro del timeout.
+/// El sondeo a upstream se reutiliza durante `upstream.ready_probe_ttl_secs`,
+/// para que los probes de Kubernetes no se conviertan en tráfico constante.
+pub async fn readyz(State(state): State<AppState>) -> impl IntoResponse {
+    let cache_populated = cache::store().get(MANIFEST_KEY).await.is_some();
+    let probe = probe_upstream(&state).await;
+
+    let last_refresh = cache::last_manifest_refresh();
+    let readiness = Readiness {
+        ready: cache_populated || probe.reachable,
+        cache_populated,
+        upstream_reachable: probe.reachable,
+        upstream_latency_ms: probe.latency_ms,
+        upstream_error: probe.error,
+        last_manifest_refresh_unix: last_refresh
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs()),
+        last_manifest_refresh_age_secs: last_refresh
+            .and_then(|t| t.elapsed().ok())
+            .map(|d| d.as_secs()),
+    };
+
+    let status = if readiness.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(readiness))
+}
+
+/// Con el breaker abierto no se sondea: upstream ya se da por caído.
+async fn probe_upstream(state: &AppState) -> Probe {
+    if state.breaker.is_open() {
+        return Probe {
+            reachable: false,
+            latency_ms: None,
+            error: Some("upstream circuit breaker is open".to_string()),
+        };
+    }
+
+    let settings = state.settings();
+    let upstream = &settings.upstream;
+    let ttl = Duration::from_secs(upstream.ready_probe_ttl_secs);
+    if let Ok(last) = LAST_PROBE.lock()
+        && let Some((url, at, probe)) = last.as_ref()
+        && *url == upstream.manifest_url
+        && at.elapsed() < ttl
+    {
+        return probe.clone();
+    }
+
+    let started = Instant::now();
+    let result = state
+        .upstream
+        .client()
+        .head(&upstream.manifest_url)
+        .timeout(Duration::from_secs(upstream.ready_timeout_secs))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status());
+    let probe = match result {
+        Ok(_) => Probe {
+            reachable: true,
+            latency_ms: Some(started.elapsed().as_millis() as u64),
+            error: None,
+        },
+        Err(e) => Probe {
+            reachable: false,
+            latency_ms: None,
+            error: Some(e.to_string()),
+        },
+    };
+
+    if let Ok(mut last) = LAST_PROBE.lock() {
+        *last = Some((upstream.manifest_url.clone(), Instant::now(), probe.clone()));
+    }
+    probe
+}
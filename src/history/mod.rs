@@ -0,0 +1,258 @@
+//! Historial de lo que ha servido upstream: cada versión normalizada distinta
+//! (con el SHA1 del JSON original) y cada manifest distinto, con la hora de
+//! descarga. Permite ver re-subidas de Mojang y reconstruir lo que se servía
+//! en una fecha. Son archivos JSON bajo `history.dir`, uno por registro.
+
+use std::path::{Path, PathBuf};
+
+use axum::{
+    extract::{Path as UrlPath, State},
+    response::{IntoResponse, Response},
+    Json,
+};
+use reqwest::StatusCode;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tracing::warn;
+
+use crate::cache::{self, disk::{self, unix_now}};
+use crate::state::AppState;
+use crate::types::{NormalizedVersion, VersionManifest};
+
+const VERSIONS_DIR: &str = "versions";
+const MANIFESTS_DIR: &str = "manifests";
+
+#[derive(Serialize, Deserialize)]
+struct Record<T> {
+    fetched_at: u64, // segundos UNIX
+    /// SHA1 del JSON de Mojang según el manifest; no existe para el manifest.
+    sha1: Option<String>,
+    etag: String,
+    data: T,
+}
+
+/// Copia histórica de una versión o del manifest.
+pub struct Snapshot<T> {
+    pub fetched_at: u64,
+    pub etag: String,
+    pub data: T,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HistoryEntry {
+    pub sha1: Option<String>,
+    pub etag: String,
+    /// RFC 3339, en UTC.
+    pub fetched_at: String,
+    pub fetched_at_unix: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VersionHistory {
+    pub id: String,
+    /// De más reciente a más antigua.
+    pub records: Vec<HistoryEntry>,
+}
+
+fn root(state: &AppState) -> Option<PathBuf> {
+    let settings = &state.settings().history;
+    settings
+        .enabled
+        .then(|| settings.dir.clone().unwrap_or_else(|| cache::settings().dir.join("history")))
+}
+
+fn version_dir(root: &Path, id: &str) -> PathBuf {
+    root.join(VERSIONS_DIR).join(disk::safe_name(id))
+}
+
+pub fn disabled() -> (StatusCode, String) {
+    (StatusCode::NOT_FOUND, "El historial no está activado (history.enabled)".to_string())
+}
+
+/// Guarda la versión si difiere (en SHA1 o en contenido) de la última guardada.
+pub async fn record_version(state: &AppState, id: &str, sha1: Option<&str>, version: &NormalizedVersion, etag: &str) {
+    let Some(root) = root(state) else {
+        return;
+    };
+    let record = Record { fetched_at: unix_now(), sha1: sha1.map(String::from), etag: etag.to_string(), data: version };
+    append(&version_dir(&root, id), record).await;
+}
+
+/// Guarda el manifest si difiere del último guardado.
+pub async fn record_manifest(state: &AppState, manifest: &VersionManifest) {
+    let Some(root) = root(state) else {
+        return;
+    };
+    let record = Record { fetched_at: unix_now(), sha1: None, etag: cache::etag_for_json(manifest), data: manifest };
+    append(&root.join(MANIFESTS_DIR), record).await;
+}
+
+async fn append<T: Serialize>(dir: &Path, record: Record<&T>) {
+    if let Some(name) = list(dir).await.pop()
+        && let Some(last) = read::<serde_json::Value>(&dir.join(name)).await
+        && last.etag == record.etag
+        && last.sha1 == record.sha1
+    {
+        return;
+    }
+
+    let path = dir.join(format!("{:020}.json", record.fetched_at));
+    let result = match serde_json::to_vec(&record) {
+        Ok(bytes) => disk::write_atomic(&path, &bytes).await,
+        Err(e) => Err(std::io::Error::other(e)),
+    };
+    if let Err(e) = result {
+        warn!("Could not write history record {:?}: {}", path, e);
+    }
+}
+
+// Nombres de los registros de un directorio, de más antiguo a más reciente.
+async fn list(dir: &Path) -> Vec<String> {
+    let mut names = vec![];
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return names;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if record_time(&name).is_some() {
+            names.push(name);
+        }
+    }
+    names.sort();
+    names
+}
+
+fn record_time(name: &str) -> Option<u64> {
+    name.strip_suffix(".json")?.parse().ok()
+}
+
+async fn read<T: DeserializeOwned>(path: &Path) -> Option<Record<T>> {
+    let bytes = tokio::fs::read(path).await.ok()?;
+    match serde_json::from_slice(&bytes) {
+        Ok(record) => Some(record),
+        Err(e) => {
+            warn!("Invalid history record {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+// Último registro descargado no después de `as_of`.
+async fn as_of<T: DeserializeOwned>(dir: &Path, as_of: u64) -> Option<Snapshot<T>> {
+    let name = list(dir).await.into_iter().rev().find(|name| record_time(name).is_some_and(|t| t <= as_of))?;
+    let record = read::<T>(&dir.join(name)).await?;
+    Some(Snapshot { fetched_at: record.fetched_at, etag: record.etag, data: record.data })
+}
+
+pub async fn version_as_of(state: &AppState, id: &str, at: u64) -> Result<Snapshot<NormalizedVersion>, (StatusCode, String)> {
+    let root = root(state).ok_or_else(disabled)?;
+    as_of(&version_dir(&root, id), at)
+        .await
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("No hay copia de '{}' anterior a esa fecha", id)))
+}
+
+pub async fn manifest_as_of(state: &AppState, at: u64) -> Result<Snapshot<VersionManifest>, (StatusCode, String)> {
+    let root = root(state).ok_or_else(disabled)?;
+    as_of(&root.join(MANIFESTS_DIR), at)
+        .await
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "No hay copia del manifest anterior a esa fecha".to_string()))
+}
+
+/// `GET /version/{id}/history`: las copias guardadas de una versión.
+pub async fn version_history(State(state): State<AppState>, UrlPath(version_id): UrlPath<String>) -> Response {
+    let Some(root) = root(&state) else {
+        return disabled().into_response();
+    };
+    let dir = version_dir(&root, &version_id);
+
+    let mut records = vec![];
+    for name in list(&dir).await.into_iter().rev() {
+        if let Some(record) = read::<serde::de::IgnoredAny>(&dir.join(name)).await {
+            records.push(HistoryEntry {
+                sha1: record.sha1,
+                etag: record.etag,
+                fetched_at: format_timestamp(record.fetched_at),
+                fetched_at_unix: record.fetched_at,
+            });
+        }
+    }
+    if records.is_empty() {
+        return (StatusCode::NOT_FOUND, format!("No hay historial de '{}'", version_id)).into_response();
+    }
+    Json(VersionHistory { id: version_id, records }).into_response()
+}
+
+/// Segundos UNIX o RFC 3339 (`2023-06-12T13:25:51Z`, con desfase o solo la fecha).
+pub fn parse_timestamp(raw: &str) -> Result<u64, String> {
+    let invalid = || format!("Fecha inválida '{}': se esperaba RFC 3339 o segundos UNIX", raw);
+    if let Ok(secs) = raw.parse::<u64>() {
+        return Ok(secs);
+    }
+
+    // Un `+` sin escapar en la query string llega como espacio.
+    let raw = raw.trim().replace(' ', "+");
+    let (date, time) = raw.split_once(['T', 't']).unwrap_or((&raw, "00:00:00Z"));
+    let mut parts = date.splitn(3, '-').map(str::parse::<i64>);
+    let (Some(Ok(year)), Some(Ok(month)), Some(Ok(day))) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(invalid());
+    };
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return Err(invalid());
+    }
+
+    // Hora, fracción de segundo (se ignora) y zona: `Z` o `±HH:MM`.
+    let (clock, offset) = match time.find(['Z', 'z', '+', '-']) {
+        Some(i) => (&time[..i], &time[i..]),
+        None => (time, "Z"),
+    };
+    let clock = clock.split('.').next().unwrap_or_default();
+    let mut hms = clock.splitn(3, ':').map(str::parse::<i64>);
+    let (Some(Ok(hour)), Some(Ok(minute))) = (hms.next(), hms.next()) else {
+        return Err(invalid());
+    };
+    let second = match hms.next() {
+        Some(Ok(second)) => second,
+        Some(Err(_)) => return Err(invalid()),
+        None => 0,
+    };
+    let offset = match offset {
+        "Z" | "z" => 0,
+        _ => {
+            let sign = if offset.starts_with('-') { -1 } else { 1 };
+            let (h, m) = offset[1..].split_once(':').ok_or_else(invalid)?;
+            sign * (h.parse::<i64>().map_err(|_| invalid())? * 3600 + m.parse::<i64>().map_err(|_| invalid())? * 60)
+        }
+    };
+
+    let secs = days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second - offset;
+    u64::try_from(secs).map_err(|_| invalid())
+}
+
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let rest = secs % 86400;
+    let (year, month, day) = civil_from_days(days);
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, rest / 3600, rest % 3600 / 60, rest % 60)
+}
+
+// Días desde 1970-01-01 en el calendario gregoriano proléptico.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
@@ -1,4 +1,36 @@
-pub mod manifest;
-pub mod api;
-mod types;
-mod cache;
\ No newline at end of file
+pub mod manifest;
+pub mod maven;
+pub mod admin;
+pub mod api;
+pub mod auth;
+pub mod bedrock;
+pub use manifestor_core::types;
+pub mod cache;
+pub mod compression;
+pub mod concurrency;
+pub mod config;
+pub mod events;
+pub mod experimental;
+pub mod graphql;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod health;
+pub mod history;
+pub mod listener;
+pub mod metrics;
+pub mod mirror;
+pub mod notify;
+pub mod openapi;
+pub mod profiles;
+pub mod proxy;
+pub mod ratelimit;
+pub mod refresher;
+pub mod request_id;
+pub mod schema;
+pub mod search;
+pub mod shutdown;
+pub mod signing;
+pub mod source;
+pub mod state;
+pub mod telemetry;
+pub mod upstream;
\ No newline at end of file
@@ -0,0 +1,89 @@
+//! Puertos en los que escucha la API: `server.listen_addr`, las direcciones
+//! extra de `server.listen_addrs` y, con la feature `tls`, las HTTPS de
+//! `server.tls.listen_addrs`. Todos sirven el mismo router.
+
+#[cfg(feature = "tls")]
+pub mod tls;
+
+use std::{
+    future::{Future, IntoFuture},
+    io,
+    net::SocketAddr,
+    pin::Pin,
+};
+
+use axum::Router;
+use futures_util::future::try_join_all;
+use socket2::{Domain, Socket, Type};
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+#[cfg(not(feature = "tls"))]
+use tracing::warn;
+
+use crate::config::ServerSettings;
+
+type Server = Pin<Box<dyn Future<Output = io::Result<()>> + Send>>;
+
+/// Abre un puerto. Los IPv6 se abren solo para IPv6, así `0.0.0.0:3000` y
+/// `[::]:3000` pueden ir a la vez.
+pub fn bind(addr: SocketAddr) -> io::Result<TcpListener> {
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, None)?;
+    if addr.is_ipv6() {
+        socket.set_only_v6(true)?;
+    }
+    socket.set_reuse_address(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(1024)?;
+    TcpListener::from_std(socket.into())
+}
+
+/// Direcciones HTTP configuradas, sin repetir.
+pub fn http_addrs(settings: &ServerSettings) -> Vec<SocketAddr> {
+    let mut addrs = vec![settings.listen_addr];
+    for addr in &settings.listen_addrs {
+        if !addrs.contains(addr) {
+            addrs.push(*addr);
+        }
+    }
+    addrs
+}
+
+/// Abre todos los puertos; el futuro devuelto los sirve hasta que se cancele
+/// `shutdown`. Si alguno no se puede abrir no se sirve ninguno.
+pub fn serve(
+    settings: &ServerSettings,
+    app: Router,
+    shutdown: CancellationToken,
+) -> io::Result<impl Future<Output = io::Result<()>> + Send + 'static> {
+    let mut servers: Vec<Server> = vec![];
+    for addr in http_addrs(settings) {
+        let listener = bind(addr)?;
+        info!("Server started at {}", listener.local_addr()?);
+        let service = app.clone().into_make_service_with_connect_info::<SocketAddr>();
+        let server = axum::serve(listener, service).with_graceful_shutdown(shutdown.clone().cancelled_owned());
+        servers.push(Box::pin(server.into_future()));
+    }
+
+    #[cfg(feature = "tls")]
+    if !settings.tls.listen_addrs.is_empty() {
+        use axum::serve::ListenerExt;
+
+        let acceptor = tls::acceptor(&settings.tls, shutdown.clone())?;
+        for addr in &settings.tls.listen_addrs {
+            // tap_io para que ConnectInfo<SocketAddr> funcione igual que en HTTP.
+            let listener = tls::TlsListener::new(bind(*addr)?, acceptor.clone())?.tap_io(|_| {});
+            info!("TLS server started at {}", addr);
+            let service = app.clone().into_make_service_with_connect_info::<SocketAddr>();
+            let server = axum::serve(listener, service).with_graceful_shutdown(shutdown.clone().cancelled_owned());
+            servers.push(Box::pin(server.into_future()));
+        }
+    }
+    #[cfg(not(feature = "tls"))]
+    if !settings.tls.listen_addrs.is_empty() {
+        warn!("server.tls.listen_addrs is set but this build has no `tls` feature; ignoring it");
+    }
+
+    Ok(async move { try_join_all(servers).await.map(|_| ()) })
+}
@@ -0,0 +1,176 @@
+//! HTTPS con rustls. El certificado se vuelve a leer de disco con SIGHUP,
+//! para renovarlo (p. ej. con certbot) sin reiniciar; si el nuevo no es
+//! válido se sigue sirviendo el anterior.
+
+use std::{
+    fs::File,
+    io::{self, BufReader},
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use axum::serve::Listener;
+use rustls::{
+    server::{ClientHello, ResolvesServerCert},
+    sign::CertifiedKey,
+    ServerConfig,
+};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+use tokio_rustls::{server::TlsStream, TlsAcceptor};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::config::TlsSettings;
+
+// Un cliente que no termina el handshake no debe ocupar la conexión.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Lee la cadena de certificados y la clave privada, ambas en PEM.
+pub fn load_certified_key(cert_file: &Path, key_file: &Path) -> Result<CertifiedKey, String> {
+    let open = |path: &Path| {
+        File::open(path).map(BufReader::new).map_err(|e| format!("No se pudo leer {}: {}", path.display(), e))
+    };
+
+    let certs = rustls_pemfile::certs(&mut open(cert_file)?)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("{}: {}", cert_file.display(), e))?;
+    if certs.is_empty() {
+        return Err(format!("{}: no hay ningún bloque CERTIFICATE", cert_file.display()));
+    }
+    let key = rustls_pemfile::private_key(&mut open(key_file)?)
+        .map_err(|e| format!("{}: {}", key_file.display(), e))?
+        .ok_or_else(|| format!("{}: no hay ninguna clave privada", key_file.display()))?;
+    let key = rustls::crypto::ring::sign::any_supported_type(&key).map_err(|e| format!("{}: {}", key_file.display(), e))?;
+    Ok(CertifiedKey::new(certs, key))
+}
+
+#[derive(Debug)]
+struct ReloadableCert {
+    current: RwLock<Arc<CertifiedKey>>,
+}
+
+impl ResolvesServerCert for ReloadableCert {
+    fn resolve(&self, _: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        self.current.read().ok().map(|key| key.clone())
+    }
+}
+
+/// Configuración TLS compartida por todos los puertos HTTPS. Con SIGHUP se
+/// recarga el certificado hasta que se cancele `shutdown`.
+pub fn acceptor(settings: &TlsSettings, shutdown: CancellationToken) -> io::Result<TlsAcceptor> {
+    let (Some(cert_file), Some(key_file)) = (settings.cert_file.clone(), settings.key_file.clone()) else {
+        return Err(io::Error::other("server.tls.listen_addrs requiere server.tls.cert_file y server.tls.key_file"));
+    };
+    let key = load_certified_key(&cert_file, &key_file).map_err(io::Error::other)?;
+    let resolver = Arc::new(ReloadableCert { current: RwLock::new(Arc::new(key)) });
+
+    let mut config = ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(io::Error::other)?
+        .with_no_client_auth()
+        .with_cert_resolver(resolver.clone());
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+    // La señal se registra ya, antes de servir, para que un SIGHUP temprano
+    // no termine el proceso.
+    #[cfg(unix)]
+    match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+        Ok(hangup) => spawn_reload(hangup, resolver, cert_file, key_file, shutdown),
+        Err(e) => warn!("Could not listen for SIGHUP, TLS certificate reload disabled: {}", e),
+    }
+    #[cfg(not(unix))]
+    let _ = (resolver, cert_file, key_file, shutdown);
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+#[cfg(unix)]
+fn spawn_reload(
+    mut hangup: tokio::signal::unix::Signal,
+    resolver: Arc<ReloadableCert>,
+    cert_file: PathBuf,
+    key_file: PathBuf,
+    shutdown: CancellationToken,
+) {
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = hangup.recv() => {}
+                _ = shutdown.cancelled() => return,
+            }
+            match load_certified_key(&cert_file, &key_file) {
+                Ok(key) => {
+                    if let Ok(mut current) = resolver.current.write() {
+                        *current = Arc::new(key);
+                    }
+                    info!("TLS certificate reloaded from {}", cert_file.display());
+                }
+                Err(e) => warn!("TLS certificate reload failed, keeping the previous one: {}", e),
+            }
+        }
+    });
+}
+
+/// Puerto HTTPS para `axum::serve`. Los handshakes se hacen en tareas
+/// aparte, para que un cliente lento no frene a los demás.
+pub struct TlsListener {
+    local_addr: SocketAddr,
+    incoming: mpsc::Receiver<(TlsStream<TcpStream>, SocketAddr)>,
+}
+
+impl TlsListener {
+    pub fn new(listener: TcpListener, acceptor: TlsAcceptor) -> io::Result<Self> {
+        let local_addr = listener.local_addr()?;
+        let (sender, incoming) = mpsc::channel(64);
+        tokio::spawn(async move {
+            loop {
+                // Termina cuando axum suelta el listener al apagarse.
+                let (stream, peer) = tokio::select! {
+                    accepted = listener.accept() => match accepted {
+                        Ok(accepted) => accepted,
+                        Err(e) => {
+                            debug!("TLS accept failed: {}", e);
+                            tokio::time::sleep(Duration::from_millis(50)).await;
+                            continue;
+                        }
+                    },
+                    _ = sender.closed() => return,
+                };
+                let acceptor = acceptor.clone();
+                let sender = sender.clone();
+                tokio::spawn(async move {
+                    match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                        Ok(Ok(stream)) => {
+                            let _ = sender.send((stream, peer)).await;
+                        }
+                        Ok(Err(e)) => debug!("TLS handshake with {} failed: {}", peer, e),
+                        Err(_) => debug!("TLS handshake with {} timed out", peer),
+                    }
+                });
+            }
+        });
+        Ok(Self { local_addr, incoming })
+    }
+}
+
+impl Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.incoming.recv().await {
+            Some(connection) => connection,
+            // Solo si la tarea de aceptación ha terminado; axum espera para siempre.
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        Ok(self.local_addr)
+    }
+}
@@ -1,21 +1,94 @@
-use tracing_subscriber;
-use manifestor::api;
-use tracing::{info};
-use std::{env, net::SocketAddr};
+use std::time::Duration;
+
+use manifestor::{
+    api, cache, config::Settings, listener, manifest, notify, proxy, refresher, shutdown, state::AppState, telemetry,
+};
+use tracing::{info, warn};
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tracing_subscriber::fmt::init();
-    let port: u16 = env::var("PORT")
-        .unwrap_or_else(|_| "3000".to_string())
-        .parse()
-        .expect("PORT debe ser un número entero válido");
-
-    // 2. Construir dirección con tipo seguro
-    let addr = SocketAddr::from(([0, 0, 0, 0], port));
-
-    let app = api::create_router();
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    info!("Server started at {:?}", &listener.local_addr().unwrap().ip());
-    axum::serve(listener, app).await?;
+    telemetry::init_tracing();
+    let settings = Settings::load()?;
+    let drain_timeout = Duration::from_secs(settings.server.shutdown_timeout_secs);
+
+    cache::init(settings.cache.clone());
+    let state = AppState::new(settings)?;
+
+    // Trazas y métricas por OTLP, si está configurado
+    let exporter = telemetry::spawn(&state.settings().telemetry, state.shutdown.clone());
+
+    // Rehidratar cachés desde disco para no golpear a Mojang tras un reinicio
+    let manifest_restored = cache::rehydrate_manifest().await;
+    let versions_restored = manifest::rehydrate_version_cache().await;
+    info!(
+        "Disk cache restored (manifest: {}, versions: {})",
+        manifest_restored, versions_restored
+    );
+
+    // Temporales de descargas que cortó el último apagado, y por si
+    // proxy.max_cache_bytes bajó desde el último arranque
+    let spool_max_age = Duration::from_secs(state.settings().proxy.download_timeout_secs);
+    tokio::spawn(proxy::store::sweep_spools(spool_max_age));
+    proxy::store::collect_soon(state.settings().proxy.max_cache_bytes, spool_max_age);
+
+    // Webhooks para las versiones nuevas que detecte el refresher
+    let notifier = notify::spawn(state.clone());
+
+    // Refresco periódico del manifest y precarga de versiones populares
+    let refresher = refresher::spawn(state.clone());
+
+    // SIGTERM/Ctrl+C: dejar de aceptar conexiones y terminar las que hay
+    let token = state.shutdown.clone();
+    shutdown::spawn(token.clone());
+
+    // API gRPC en su propio puerto, si está configurada
+    let grpc_addr = state.settings().server.grpc_listen_addr;
+    #[cfg(feature = "grpc")]
+    let grpc = match grpc_addr {
+        Some(addr) => Some(manifestor::grpc::spawn(state.clone(), addr).await?),
+        None => None,
+    };
+    #[cfg(not(feature = "grpc"))]
+    if grpc_addr.is_some() {
+        warn!("grpc_listen_addr is set but this build has no `grpc` feature; ignoring it");
+    }
+
+    // HTTP en listen_addr y listen_addrs; HTTPS en tls.listen_addrs
+    let server_settings = state.settings().server.clone();
+    let app = api::create_router(state);
+    let server = listener::serve(&server_settings, app, token.clone())?;
+
+    let deadline = async {
+        token.cancelled().await;
+        tokio::time::sleep(drain_timeout).await;
+    };
+    tokio::select! {
+        result = server => result?,
+        _ = deadline => warn!("Connections still open after {:?}, shutting down anyway", drain_timeout),
+    }
+
+    // El refresher termina el refresco en curso, el notifier los envíos a
+    // webhooks que estén en marcha y el exportador OTLP hace un último envío.
+    if tokio::time::timeout(drain_timeout, async {
+        let exporter = async {
+            if let Some(exporter) = exporter {
+                let _ = exporter.await;
+            }
+        };
+        let grpc = async {
+            #[cfg(feature = "grpc")]
+            if let Some(grpc) = grpc {
+                let _ = grpc.await;
+            }
+        };
+        let _ = tokio::join!(refresher, notifier, exporter, grpc);
+    })
+    .await
+    .is_err()
+    {
+        warn!("Background tasks did not stop within {:?}", drain_timeout);
+    }
+
+    cache::flush().await;
+    info!("Shutdown complete");
     Ok(())
 }
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+    Json,
+};
+use manifestor_core::{arguments, Platform};
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use super::bundle::lookup_bundle;
+use super::lookup_version;
+use crate::api::with_freshness;
+use crate::cache::Freshness;
+use crate::state::AppState;
+use crate::types::NormalizedArguments;
+
+#[derive(Debug, Deserialize)]
+pub struct TemplateQuery {
+    /// Con plataforma se usan los argumentos del bundle, con las reglas ya
+    /// aplicadas; sin ella, todos los de la versión.
+    pub platform: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExpandRequest {
+    #[serde(default)]
+    pub values: HashMap<String, String>,
+}
+
+/// `GET /version/{id}/arguments/template`: argumentos separados en literales
+/// y placeholders, con la descripción de cada placeholder.
+pub async fn arguments_template(
+    State(state): State<AppState>,
+    Path(version_id): Path<String>,
+    Query(query): Query<TemplateQuery>,
+) -> Response {
+    match version_arguments(&state, &version_id, query.platform.as_deref()).await {
+        Ok((args, _, freshness)) => with_freshness(Json(arguments::template(&args)).into_response(), freshness),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// `POST /version/{id}/arguments/template`: sustituye los valores enviados y
+/// devuelve la línea de comandos. Los secretos (tokens de sesión) no se
+/// aceptan; quedan como placeholder para que el launcher los ponga en local.
+pub async fn expand_arguments(
+    State(state): State<AppState>,
+    Path(version_id): Path<String>,
+    Query(query): Query<TemplateQuery>,
+    Json(request): Json<ExpandRequest>,
+) -> Response {
+    let (args, main_class, freshness) = match version_arguments(&state, &version_id, query.platform.as_deref()).await {
+        Ok(found) => found,
+        Err(err) => return err.into_response(),
+    };
+
+    match arguments::expand(&args, main_class.as_deref(), &request.values) {
+        Ok(expanded) => with_freshness(Json(expanded).into_response(), freshness),
+        Err(secret) => (
+            StatusCode::BAD_REQUEST,
+            format!("'{}' es un secreto: sustitúyelo en el launcher, no lo envíes", secret),
+        )
+            .into_response(),
+    }
+}
+
+async fn version_arguments(
+    state: &AppState,
+    version_id: &str,
+    platform: Option<&str>,
+) -> Result<(NormalizedArguments, Option<String>, Freshness), (StatusCode, String)> {
+    let (version, freshness) = match platform {
+        Some(platform) => {
+            let platform = Platform::parse(platform).map_err(|msg| (StatusCode::BAD_REQUEST, msg.to_string()))?;
+            let cached = lookup_bundle(state, version_id, &platform).await?;
+            (cached.data.version, cached.freshness)
+        }
+        None => {
+            let cached = lookup_version(state, version_id).await?;
+            (cached.data, cached.freshness)
+        }
+    };
+    Ok((version.arguments, version.main_class, freshness))
+}
@@ -0,0 +1,188 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    time::Duration,
+};
+
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+    Json,
+};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+
+use super::lookup_version;
+use crate::api::with_freshness;
+use crate::cache::{self, Freshness};
+use crate::metrics;
+use crate::mirror::{self, MirrorChoice};
+use crate::state::AppState;
+use crate::types::{AssetIndex, NormalizedVersion};
+
+const RESOURCES_URL: &str = "https://resources.download.minecraft.net";
+// Los índices se direccionan por SHA1 y no cambian.
+const INDEX_TTL: Duration = Duration::from_secs(30 * 24 * 3600);
+
+/// Nombre → objeto, tal como viene en `objects` del índice de Mojang.
+type ObjectIndex = BTreeMap<String, IndexedObject>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedObject {
+    hash: String,
+    size: u64,
+}
+
+/// Lo que hay que descargar para pasar de los assets de `from` a los de `to`.
+#[derive(Debug, Serialize)]
+pub struct AssetDiff {
+    pub from: String,
+    pub to: String,
+    /// Ids de los asset index de cada versión.
+    pub from_index: String,
+    pub to_index: String,
+    /// Objetos de `to` cuyo hash no está en ningún objeto de `from`.
+    pub objects: Vec<AssetObject>,
+    /// Nombres de `from` que `to` ya no tiene.
+    pub removed: Vec<String>,
+    /// Hashes distintos en `objects`: el mismo archivo con varios nombres se
+    /// descarga una vez.
+    pub unique_objects: usize,
+    pub unique_size: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AssetObject {
+    pub name: String,
+    pub hash: String,
+    pub size: u64,
+    pub url: String,
+}
+
+/// `GET /assets/{id}/diff/{other_id}`: objetos del asset index de `other_id`
+/// que no están ya en el de `id`, para que un launcher que cambia entre
+/// versiones cercanas descargue solo lo que le falta.
+pub async fn diff_assets(
+    State(state): State<AppState>,
+    Path((from_id, to_id)): Path<(String, String)>,
+    mirror: MirrorChoice,
+) -> Response {
+    let (from, to) = tokio::join!(lookup_version(&state, &from_id), lookup_version(&state, &to_id));
+    let (from, to) = match (from, to) {
+        (Ok(from), Ok(to)) => (from, to),
+        (Err(err), _) | (_, Err(err)) => return err.into_response(),
+    };
+    let (from_index, to_index) = match (asset_index(&from.data), asset_index(&to.data)) {
+        (Ok(from), Ok(to)) => (from, to),
+        (Err(err), _) | (_, Err(err)) => return err.into_response(),
+    };
+
+    // Mismo índice: nada que descargar y no hace falta pedirlo.
+    let diff = if from_index.sha1 == to_index.sha1 {
+        let empty = ObjectIndex::new();
+        diff(&from.data, &to.data, &empty, &empty, &mirror)
+    } else {
+        let (from_objects, to_objects) = tokio::join!(objects(&state, &from_index), objects(&state, &to_index));
+        let (from_objects, to_objects) = match (from_objects, to_objects) {
+            (Ok(from), Ok(to)) => (from, to),
+            (Err(err), _) | (_, Err(err)) => return err.into_response(),
+        };
+        diff(&from.data, &to.data, &from_objects, &to_objects, &mirror)
+    };
+
+    let freshness = [from.freshness, to.freshness]
+        .into_iter()
+        .find(|f| *f != Freshness::Fresh)
+        .unwrap_or(Freshness::Fresh);
+    with_freshness(mirror::vary(&state, Json(diff).into_response()), freshness)
+}
+
+fn asset_index(version: &NormalizedVersion) -> Result<AssetIndex, (StatusCode, String)> {
+    version
+        .asset_index
+        .clone()
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("La versión '{}' no tiene asset index", version.id)))
+}
+
+fn diff(
+    from: &NormalizedVersion,
+    to: &NormalizedVersion,
+    from_objects: &ObjectIndex,
+    to_objects: &ObjectIndex,
+    mirror: &MirrorChoice,
+) -> AssetDiff {
+    let present: HashSet<&str> = from_objects.values().map(|o| o.hash.as_str()).collect();
+    let mut unique = HashSet::new();
+    let mut unique_size = 0;
+    let mut objects = vec![];
+    for (name, object) in to_objects.iter().filter(|(_, o)| !present.contains(o.hash.as_str())) {
+        if unique.insert(object.hash.as_str()) {
+            unique_size += object.size;
+        }
+        let mut object = asset_object(name, object);
+        if let Some(url) = mirror.0.as_ref().and_then(|m| m.rewrite(&object.url)) {
+            object.url = url;
+        }
+        objects.push(object);
+    }
+
+    AssetDiff {
+        from: from.id.clone(),
+        to: to.id.clone(),
+        from_index: from.asset_index.as_ref().map(|a| a.id.clone()).unwrap_or_default(),
+        to_index: to.asset_index.as_ref().map(|a| a.id.clone()).unwrap_or_default(),
+        unique_objects: unique.len(),
+        unique_size,
+        removed: from_objects.keys().filter(|name| !to_objects.contains_key(*name)).cloned().collect(),
+        objects,
+    }
+}
+
+fn asset_object(name: &str, object: &IndexedObject) -> AssetObject {
+    AssetObject {
+        name: name.to_string(),
+        hash: object.hash.clone(),
+        size: object.size,
+        url: format!("{}/{}/{}", RESOURCES_URL, &object.hash[..2], object.hash),
+    }
+}
+
+/// Todos los objetos de un asset index, por nombre y con la URL de Mojang.
+pub(crate) async fn index_objects(state: &AppState, index: &AssetIndex) -> Result<Vec<AssetObject>, (StatusCode, String)> {
+    let objects = objects(state, index).await?;
+    Ok(objects.iter().map(|(name, object)| asset_object(name, object)).collect())
+}
+
+async fn objects(state: &AppState, index: &AssetIndex) -> Result<ObjectIndex, (StatusCode, String)> {
+    let key = format!("assets:{}", index.sha1);
+    if let Some((objects, _, _)) = cache::get_json::<ObjectIndex>(&key).await {
+        metrics::cache_lookup("assets", true);
+        return Ok(objects);
+    }
+    metrics::cache_lookup("assets", false);
+
+    metrics::increment_counter(metrics::UPSTREAM_REQUESTS, &[("target", "asset_index")]);
+    let fetched = state.source.asset_index(&index.url).await.map_err(|e| e.to_string()).and_then(parse_objects);
+    match fetched {
+        Ok(objects) => {
+            cache::set_json(&key, &objects, INDEX_TTL).await;
+            Ok(objects)
+        }
+        Err(e) => {
+            metrics::increment_counter(metrics::UPSTREAM_ERRORS, &[("target", "asset_index")]);
+            warn!("Asset index {} fetch failed: {}", index.id, e);
+            Err((StatusCode::BAD_GATEWAY, format!("Error obteniendo el asset index '{}'", index.id)))
+        }
+    }
+}
+
+// `{"objects": {"minecraft/sounds/ambient/cave/cave1.ogg": {"hash": "...", "size": 12345}}}`.
+fn parse_objects(raw: Value) -> Result<ObjectIndex, String> {
+    let objects = raw.get("objects").cloned().unwrap_or_default();
+    let objects: ObjectIndex = serde_json::from_value(objects).map_err(|e| format!("Asset index inválido: {}", e))?;
+    match objects.values().find(|o| o.hash.len() < 2 || !o.hash.bytes().all(|b| b.is_ascii_hexdigit())) {
+        Some(object) => Err(format!("Asset index inválido: hash '{}'", object.hash)),
+        None => Ok(objects),
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+
+use axum::{
+    extract::State,
+    response::{IntoResponse, Response},
+    Json,
+};
+use futures_util::{stream, StreamExt};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::lookup_version;
+use crate::api::with_freshness;
+use crate::cache::Freshness;
+use crate::mirror::{self, MirrorChoice};
+use crate::schema::Schema;
+use crate::state::AppState;
+
+// Ids por petición y cuántos se resuelven a la vez.
+const MAX_IDS: usize = 64;
+const PARALLELISM: usize = 8;
+
+#[derive(Debug, Deserialize)]
+pub struct BatchRequest {
+    pub ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum BatchEntry {
+    /// La versión en el esquema pedido.
+    Version(Box<Value>),
+    Error { error: BatchError },
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchError {
+    pub status: u16,
+    pub message: String,
+}
+
+/// `POST /versions/batch`: varias versiones normalizadas de una vez. Un id
+/// que falla no hace fallar al resto; su entrada lleva el error.
+pub async fn batch_versions(
+    State(state): State<AppState>,
+    mirror: MirrorChoice,
+    schema: Schema,
+    Json(request): Json<BatchRequest>,
+) -> Response {
+    let mut ids = request.ids;
+    ids.sort();
+    ids.dedup();
+    if ids.is_empty() {
+        return (StatusCode::BAD_REQUEST, "La lista 'ids' no puede estar vacía").into_response();
+    }
+    if ids.len() > MAX_IDS {
+        return (StatusCode::BAD_REQUEST, format!("Como máximo {} versiones por petición", MAX_IDS)).into_response();
+    }
+
+    let results: Vec<_> = stream::iter(ids)
+        .map(|id| {
+            let state = state.clone();
+            async move {
+                let result = lookup_version(&state, &id).await;
+                (id, result)
+            }
+        })
+        .buffer_unordered(PARALLELISM)
+        .collect()
+        .await;
+
+    let mut freshness = Freshness::Fresh;
+    let versions: BTreeMap<String, BatchEntry> = results
+        .into_iter()
+        .map(|(id, result)| {
+            let entry = match result {
+                Ok(mut cached) => {
+                    mirror.apply(&mut cached.data);
+                    if cached.freshness != Freshness::Fresh {
+                        freshness = cached.freshness;
+                    }
+                    BatchEntry::Version(Box::new(schema.to_value(&cached.data)))
+                }
+                Err((status, message)) => BatchEntry::Error {
+                    error: BatchError {
+                        status: status.as_u16(),
+                        message,
+                    },
+                },
+            };
+            (id, entry)
+        })
+        .collect();
+
+    with_freshness(mirror::vary(&state, schema.mark(Json(versions).into_response())), freshness)
+}
@@ -0,0 +1,176 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use tracing::{info, warn};
+
+use crate::metrics;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl BreakerState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BreakerState::Closed => "closed",
+            BreakerState::Open => "open",
+            BreakerState::HalfOpen => "half_open",
+        }
+    }
+
+    fn gauge_value(self) -> f64 {
+        match self {
+            BreakerState::Closed => 0.0,
+            BreakerState::Open => 1.0,
+            BreakerState::HalfOpen => 2.0,
+        }
+    }
+}
+
+struct Inner {
+    state: BreakerState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    probe_in_flight: bool,
+}
+
+/// Circuit breaker para las peticiones a Mojang (o a otro upstream, con
+/// [`CircuitBreaker::for_target`]).
+///
+/// Tras `failure_threshold` fallos seguidos se abre y rechaza peticiones
+/// durante `open_duration`; después deja pasar una sola petición de prueba
+/// (semiabierto) que decide si vuelve a cerrarse o a abrirse.
+pub struct CircuitBreaker {
+    /// Etiqueta `target` de las métricas y los logs.
+    target: &'static str,
+    failure_threshold: u32,
+    open_duration: Duration,
+    inner: Mutex<Inner>,
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, open_duration: Duration) -> Self {
+        Self::for_target("mojang", failure_threshold, open_duration)
+    }
+
+    /// Breaker independiente para otro upstream, p. ej. la API de Bedrock.
+    pub fn for_target(target: &'static str, failure_threshold: u32, open_duration: Duration) -> Self {
+        metrics::set_gauge(metrics::UPSTREAM_CIRCUIT_STATE, &[("target", target)], BreakerState::Closed.gauge_value());
+        Self {
+            target,
+            failure_threshold: failure_threshold.max(1),
+            open_duration,
+            inner: Mutex::new(Inner {
+                state: BreakerState::Closed,
+                consecutive_failures: 0,
+                opened_at: None,
+                probe_in_flight: false,
+            }),
+        }
+    }
+
+    pub fn state(&self) -> BreakerState {
+        self.inner.lock().map(|inner| inner.state).unwrap_or(BreakerState::Closed)
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.state() != BreakerState::Closed
+    }
+
+    /// Permiso para que una petición salga hacia upstream, o `None` si el
+    /// breaker la rechaza. El resultado se apunta con [`Attempt::record`].
+    pub fn allow(&self) -> Option<Attempt<'_>> {
+        let Ok(mut inner) = self.inner.lock() else {
+            return Some(Attempt { breaker: self, probe: false });
+        };
+
+        let probe = match inner.state {
+            BreakerState::Closed => false,
+            BreakerState::Open => {
+                if inner.opened_at.is_none_or(|at| at.elapsed() < self.open_duration) {
+                    return None;
+                }
+                transition(self.target, &mut inner, BreakerState::HalfOpen);
+                true
+            }
+            BreakerState::HalfOpen if !inner.probe_in_flight => true,
+            BreakerState::HalfOpen => return None,
+        };
+        inner.probe_in_flight |= probe;
+        Some(Attempt { breaker: self, probe })
+    }
+
+    fn record_success(&self) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.consecutive_failures = 0;
+            inner.probe_in_flight = false;
+            if inner.state != BreakerState::Closed {
+                transition(self.target, &mut inner, BreakerState::Closed);
+                inner.opened_at = None;
+            }
+        }
+    }
+
+    fn record_failure(&self) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.consecutive_failures += 1;
+            inner.probe_in_flight = false;
+
+            let trip = match inner.state {
+                BreakerState::HalfOpen => true,
+                BreakerState::Closed => inner.consecutive_failures >= self.failure_threshold,
+                BreakerState::Open => false,
+            };
+            if trip {
+                transition(self.target, &mut inner, BreakerState::Open);
+                inner.opened_at = Some(Instant::now());
+            }
+        }
+    }
+}
+
+/// Petición admitida por [`CircuitBreaker::allow`]. Si es la prueba del
+/// estado semiabierto y se suelta sin [`record`](Attempt::record) (p. ej.
+/// porque el cliente se fue y se canceló el future), cuenta como fallo: si no,
+/// la prueba quedaría en vuelo para siempre y el breaker no volvería a dejar
+/// pasar nada.
+#[must_use]
+pub struct Attempt<'a> {
+    breaker: &'a CircuitBreaker,
+    probe: bool,
+}
+
+impl Attempt<'_> {
+    pub fn record(mut self, success: bool) {
+        self.probe = false;
+        if success {
+            self.breaker.record_success();
+        } else {
+            self.breaker.record_failure();
+        }
+    }
+}
+
+impl Drop for Attempt<'_> {
+    fn drop(&mut self) {
+        if self.probe {
+            warn!("Upstream circuit breaker ({}) probe was cancelled", self.breaker.target);
+            self.breaker.record_failure();
+        }
+    }
+}
+
+fn transition(target: &'static str, inner: &mut Inner, to: BreakerState) {
+    match to {
+        BreakerState::Open => warn!("Upstream circuit breaker ({}) opened after {} failures", target, inner.consecutive_failures),
+        _ => info!("Upstream circuit breaker ({}) is now {}", target, to.as_str()),
+    }
+    inner.state = to;
+    metrics::set_gauge(metrics::UPSTREAM_CIRCUIT_STATE, &[("target", target)], to.gauge_value());
+    metrics::increment_counter(metrics::UPSTREAM_CIRCUIT_TRANSITIONS, &[("target", target), ("to", to.as_str())]);
+}
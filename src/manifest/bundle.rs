@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+};
+use manifestor_core::{bundle_version_json, Platform};
+use reqwest::StatusCode;
+
+use super::{fetch_raw_version, fetch_version_manifest};
+use crate::api::with_cache_headers;
+use crate::cache::{self, bundle_key, get_cached_manifest, Cached, Freshness};
+use crate::maven;
+use crate::metrics;
+use crate::mirror::{self, MirrorChoice};
+use crate::schema::Schema;
+use crate::state::AppState;
+use crate::types::PlatformBundle;
+
+/// `GET /version/{id}/bundle/{platform}`: la versión ya resuelta para una
+/// plataforma, con las reglas aplicadas y los natives elegidos.
+///
+/// Las reglas solo están en el JSON original, así que el bundle se calcula
+/// desde ahí y se cachea aparte de la versión normalizada.
+pub async fn version_bundle(
+    State(state): State<AppState>,
+    Path((version_id, platform)): Path<(String, String)>,
+    mirror: MirrorChoice,
+    schema: Schema,
+) -> Response {
+    let platform = match Platform::parse(&platform) {
+        Ok(platform) => platform,
+        Err(msg) => return (StatusCode::BAD_REQUEST, msg).into_response(),
+    };
+
+    match lookup_bundle(&state, &version_id, &platform).await {
+        Ok(cached) => bundle_response(&state, &mirror, &schema, cached.data, cached.etag, cached.age, cached.freshness),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Bundle desde caché o recién calculado; si upstream falla, la última copia.
+pub(crate) async fn lookup_bundle(
+    state: &AppState,
+    version_id: &str,
+    platform: &Platform,
+) -> Result<Cached<PlatformBundle>, (StatusCode, String)> {
+    let key = bundle_key(version_id, platform);
+    let ttl = cache::settings().version_ttl();
+    let cached = cache::get_json::<PlatformBundle>(&key).await;
+    if let Some((data, etag, age)) = &cached
+        && *age < ttl
+    {
+        metrics::cache_lookup("bundle", true);
+        return Ok(Cached { data: data.clone(), etag: etag.clone(), age: *age, freshness: Freshness::Fresh });
+    }
+    metrics::cache_lookup("bundle", false);
+
+    match build(state, version_id, platform).await {
+        Ok(data) => {
+            let etag = cache::set_json(&key, &data, ttl + cache::stale_grace()).await;
+            Ok(Cached { data, etag, age: Duration::ZERO, freshness: Freshness::Fresh })
+        }
+        Err((status, msg)) => match cached {
+            Some((data, etag, age)) if status == StatusCode::BAD_GATEWAY => {
+                Ok(Cached { data, etag, age, freshness: Freshness::Fallback })
+            }
+            _ => Err((status, msg)),
+        },
+    }
+}
+
+async fn build(state: &AppState, version_id: &str, platform: &Platform) -> Result<PlatformBundle, (StatusCode, String)> {
+    let fetch_state = state.clone();
+    let manifest = get_cached_manifest(move || async move { fetch_version_manifest(&fetch_state).await }).await;
+    if manifest.freshness == Freshness::Unavailable {
+        return Err((StatusCode::BAD_GATEWAY, "Error obteniendo manifest".to_string()));
+    }
+
+    let raw = fetch_raw_version(state, &manifest.data, version_id).await?;
+    let mut bundle = bundle_version_json(&raw, platform)
+        .map_err(|msg| (StatusCode::BAD_GATEWAY, format!("No se pudo normalizar la versión: {}", msg)))?;
+    maven::fill_checksums(state, &mut bundle.version).await;
+    Ok(bundle)
+}
+
+fn bundle_response(
+    state: &AppState,
+    mirror: &MirrorChoice,
+    schema: &Schema,
+    mut bundle: PlatformBundle,
+    etag: String,
+    age: Duration,
+    freshness: Freshness,
+) -> Response {
+    let etag = match &mirror.0 {
+        Some(m) => {
+            m.apply(&mut bundle.version);
+            cache::etag_for_json(&bundle)
+        }
+        None => etag,
+    };
+    let response = mirror::vary(state, schema.respond(&bundle, Some(etag)));
+    with_cache_headers(response, age, cache::settings().version_ttl(), freshness)
+}
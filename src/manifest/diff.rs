@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+use super::lookup_version;
+use crate::api::with_freshness;
+use crate::cache::Freshness;
+use crate::mirror::{self, MirrorChoice};
+use crate::state::AppState;
+use crate::types::{AssetIndex, Checksums, Downloadable, HashAlgorithm, Library, NativeLibrary, NormalizedVersion};
+
+/// Lo que cambia al pasar de la versión `from` a `to`.
+#[derive(Debug, Serialize)]
+pub struct VersionDiff {
+    pub from: String,
+    pub to: String,
+    pub libraries: ListDiff<Library>,
+    pub natives: ListDiff<NativeLibrary>,
+    /// `null` si no cambia.
+    pub asset_index: Option<Change<Option<AssetIndex>>>,
+    /// `null` si no cambia.
+    pub client_jar: Option<Change<Option<Downloadable>>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListDiff<T> {
+    pub added: Vec<T>,
+    pub removed: Vec<T>,
+    /// Mismo nombre con algún checksum distinto (`sha1` o `checksums`).
+    pub changed: Vec<Change<T>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Change<T> {
+    pub from: T,
+    pub to: T,
+}
+
+/// `GET /version/{id}/diff/{other}`: diferencias entre dos versiones
+/// normalizadas, para actualizar solo lo que cambió al cambiar de versión.
+pub async fn diff_versions(
+    State(state): State<AppState>,
+    Path((from_id, to_id)): Path<(String, String)>,
+    mirror: MirrorChoice,
+) -> Response {
+    let (from, to) = tokio::join!(lookup_version(&state, &from_id), lookup_version(&state, &to_id));
+    let (mut from, mut to) = match (from, to) {
+        (Ok(from), Ok(to)) => (from, to),
+        (Err(err), _) | (_, Err(err)) => return err.into_response(),
+    };
+    mirror.apply(&mut from.data);
+    mirror.apply(&mut to.data);
+
+    // La respuesta es tan actual como la menos actual de las dos.
+    let freshness = [from.freshness, to.freshness]
+        .into_iter()
+        .find(|f| *f != Freshness::Fresh)
+        .unwrap_or(Freshness::Fresh);
+    let diff = diff(from.data, to.data);
+    with_freshness(mirror::vary(&state, Json(diff).into_response()), freshness)
+}
+
+pub fn diff(from: NormalizedVersion, to: NormalizedVersion) -> VersionDiff {
+    VersionDiff {
+        libraries: diff_list(from.libraries, to.libraries, |l| l.name.clone(), |l| checksums(l.sha1.as_deref(), &l.checksums)),
+        natives: diff_list(
+            from.natives,
+            to.natives,
+            |n| format!("{}:{}", n.name, n.classifier),
+            |n| checksums(Some(&n.sha1), &n.checksums),
+        ),
+        asset_index: changed(from.asset_index, to.asset_index, |a| {
+            checksums(a.as_ref().map(|a| a.sha1.as_str()), &Checksums::new())
+        }),
+        client_jar: changed(from.client_jar, to.client_jar, |j| {
+            j.as_ref().map(|j| checksums(Some(&j.sha1), &j.checksums)).unwrap_or_default()
+        }),
+        from: from.id,
+        to: to.id,
+    }
+}
+
+fn diff_list<T>(
+    from: Vec<T>,
+    to: Vec<T>,
+    key: impl Fn(&T) -> String,
+    checksums: impl Fn(&T) -> Checksums,
+) -> ListDiff<T> {
+    let mut previous: HashMap<String, T> = from.into_iter().map(|item| (key(&item), item)).collect();
+    let mut added = vec![];
+    let mut changed = vec![];
+
+    // Se conserva el orden de `to`, que es el del classpath.
+    for item in to {
+        match previous.remove(&key(&item)) {
+            None => added.push(item),
+            Some(old) if differ(&checksums(&old), &checksums(&item)) => changed.push(Change { from: old, to: item }),
+            Some(_) => {}
+        }
+    }
+
+    let mut removed: Vec<T> = previous.into_values().collect();
+    removed.sort_by_key(&key);
+    ListDiff { added, removed, changed }
+}
+
+fn changed<T>(from: T, to: T, checksums: impl Fn(&T) -> Checksums) -> Option<Change<T>> {
+    differ(&checksums(&from), &checksums(&to)).then_some(Change { from, to })
+}
+
+// `sha1` junto con el resto de checksums.
+fn checksums(sha1: Option<&str>, others: &Checksums) -> Checksums {
+    let mut all = others.clone();
+    if let Some(sha1) = sha1 {
+        all.insert(HashAlgorithm::Sha1, sha1.to_ascii_lowercase());
+    }
+    all
+}
+
+// Basta un algoritmo en común con distinto hash. Sin ninguno en común (p. ej.
+// solo sha1 frente a solo sha256) no hay forma de saber que es el mismo
+// artefacto, así que cuenta como cambio salvo que ninguno tenga checksums.
+fn differ(from: &Checksums, to: &Checksums) -> bool {
+    let mut shared = from.iter().filter_map(|(alg, hash)| to.get(alg).map(|other| hash != other)).peekable();
+    match shared.peek() {
+        Some(_) => shared.any(|differs| differs),
+        None => !from.is_empty() || !to.is_empty(),
+    }
+}
@@ -0,0 +1,220 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use axum::{
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+    Json,
+};
+use manifestor_core::Platform;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+
+use super::bundle::lookup_bundle;
+use crate::api::with_freshness;
+use crate::cache::{self, Freshness};
+use crate::metrics;
+use crate::state::AppState;
+
+const INDEX_KEY: &str = "java:runtimes";
+// Cuánto se guarda el último índice para servirlo si Mojang falla.
+const RETENTION: Duration = Duration::from_secs(7 * 24 * 3600);
+// Los manifests de archivos se direccionan por SHA1 y no cambian.
+const FILES_TTL: Duration = Duration::from_secs(30 * 24 * 3600);
+
+#[derive(Debug, Deserialize)]
+pub struct JavaQuery {
+    /// `<os>-<arch>`, como en los bundles.
+    pub platform: Option<String>,
+    /// Incluir la lista de archivos del runtime.
+    #[serde(default)]
+    pub files: bool,
+}
+
+/// Plataforma → componente → builds publicadas, tal como las da Mojang.
+type RuntimeIndex = BTreeMap<String, BTreeMap<String, Vec<RuntimeEntry>>>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RuntimeEntry {
+    manifest: RuntimeManifestRef,
+    version: RuntimeVersion,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeManifestRef {
+    pub sha1: String,
+    pub size: u64,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeVersion {
+    /// Versión exacta del JRE, p. ej. `17.0.8`.
+    pub name: String,
+    pub released: String,
+}
+
+/// Runtime de Java que instala el launcher oficial para una versión.
+#[derive(Debug, Serialize)]
+pub struct JavaRecommendation {
+    pub version_id: String,
+    pub component: String,
+    pub major_version: u8,
+    /// Clave de la plataforma en el índice de runtimes (`windows-x64`, `mac-os-arm64`...).
+    pub platform: String,
+    pub runtime: RuntimeVersion,
+    /// Manifest con los archivos del runtime.
+    pub manifest: RuntimeManifestRef,
+    /// Solo con `?files=true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files: Option<Vec<RuntimeFile>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RuntimeFile {
+    pub path: String,
+    /// `file`, `directory` o `link`.
+    #[serde(rename = "type")]
+    pub file_type: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub executable: bool,
+    /// Destino de un `link`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Descarga sin comprimir; la versión `lzma` se omite.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download: Option<RuntimeManifestRef>,
+}
+
+/// `GET /version/{id}/java?platform=windows-x64`: el componente de Java que
+/// pide la versión, la build que publica Mojang para la plataforma y su
+/// manifest de archivos.
+pub async fn version_java(
+    State(state): State<AppState>,
+    Path(version_id): Path<String>,
+    Query(query): Query<JavaQuery>,
+) -> Response {
+    let Some(platform) = query.platform.as_deref() else {
+        return (StatusCode::BAD_REQUEST, "Falta el parámetro platform (<os>-<arch>)").into_response();
+    };
+    let platform = match Platform::parse(platform) {
+        Ok(platform) => platform,
+        Err(msg) => return (StatusCode::BAD_REQUEST, msg).into_response(),
+    };
+
+    match recommend(&state, &version_id, &platform, query.files).await {
+        Ok((recommendation, freshness)) => with_freshness(Json(recommendation).into_response(), freshness),
+        Err(err) => err.into_response(),
+    }
+}
+
+async fn recommend(
+    state: &AppState,
+    version_id: &str,
+    platform: &Platform,
+    with_files: bool,
+) -> Result<(JavaRecommendation, Freshness), (StatusCode, String)> {
+    let bundle = lookup_bundle(state, version_id, platform).await?;
+    let java = bundle.data.java_runtime;
+    let (index, index_freshness) = runtime_index(state).await?;
+
+    let entry = index
+        .get(&java.platform)
+        .and_then(|components| components.get(&java.component))
+        .and_then(|builds| builds.first())
+        .ok_or_else(|| {
+            let msg = format!("Mojang no publica el runtime '{}' para {}", java.component, java.platform);
+            (StatusCode::NOT_FOUND, msg)
+        })?;
+
+    let files = if with_files { Some(runtime_files(state, &entry.manifest).await?) } else { None };
+    let freshness = if index_freshness == Freshness::Fallback { index_freshness } else { bundle.freshness };
+    let recommendation = JavaRecommendation {
+        version_id: version_id.to_string(),
+        component: java.component,
+        major_version: java.major_version,
+        platform: java.platform,
+        runtime: entry.version.clone(),
+        manifest: entry.manifest.clone(),
+        files,
+    };
+    Ok((recommendation, freshness))
+}
+
+// Índice de runtimes desde caché o Mojang; si Mojang falla, la última copia.
+async fn runtime_index(state: &AppState) -> Result<(RuntimeIndex, Freshness), (StatusCode, String)> {
+    let ttl = cache::settings().manifest_ttl();
+    let cached = cache::get_json::<RuntimeIndex>(INDEX_KEY).await;
+    if let Some((index, _, age)) = &cached
+        && *age < ttl
+    {
+        metrics::cache_lookup("java", true);
+        return Ok((index.clone(), Freshness::Fresh));
+    }
+    metrics::cache_lookup("java", false);
+
+    let url = state.settings().upstream.java_runtimes_url.clone();
+    let fetched = fetch(state, &url).await.and_then(|raw| {
+        serde_json::from_value::<RuntimeIndex>(raw).map_err(|e| format!("Índice de runtimes inválido: {}", e))
+    });
+    match fetched {
+        Ok(index) => {
+            cache::set_json(INDEX_KEY, &index, RETENTION).await;
+            Ok((index, Freshness::Fresh))
+        }
+        Err(e) => {
+            warn!("Java runtime index fetch failed: {}", e);
+            match cached {
+                Some((index, _, _)) => Ok((index, Freshness::Fallback)),
+                None => Err((StatusCode::BAD_GATEWAY, "Error obteniendo el índice de runtimes de Java".to_string())),
+            }
+        }
+    }
+}
+
+async fn runtime_files(state: &AppState, manifest: &RuntimeManifestRef) -> Result<Vec<RuntimeFile>, (StatusCode, String)> {
+    let key = format!("java:files:{}", manifest.sha1);
+    let raw = match cache::get_json::<Value>(&key).await {
+        Some((raw, _, _)) => raw,
+        None => {
+            let raw = fetch(state, &manifest.url).await.map_err(|e| {
+                warn!("Java runtime manifest fetch failed: {}", e);
+                (StatusCode::BAD_GATEWAY, "Error obteniendo el manifest del runtime de Java".to_string())
+            })?;
+            cache::set_json(&key, &raw, FILES_TTL).await;
+            raw
+        }
+    };
+    Ok(parse_files(&raw))
+}
+
+async fn fetch(state: &AppState, url: &str) -> Result<Value, String> {
+    metrics::increment_counter(metrics::UPSTREAM_REQUESTS, &[("target", "java_runtime")]);
+    state.source.java_runtime(url).await.map_err(|e| {
+        metrics::increment_counter(metrics::UPSTREAM_ERRORS, &[("target", "java_runtime")]);
+        e.to_string()
+    })
+}
+
+// `{"files": {"bin/java": {"type": "file", "executable": true, "downloads": {"raw": {...}, "lzma": {...}}}}}`.
+fn parse_files(raw: &Value) -> Vec<RuntimeFile> {
+    let Some(files) = raw.get("files").and_then(Value::as_object) else {
+        return vec![];
+    };
+    let mut parsed: Vec<RuntimeFile> = files
+        .iter()
+        .map(|(path, file)| RuntimeFile {
+            path: path.clone(),
+            file_type: file.get("type").and_then(Value::as_str).unwrap_or("file").to_string(),
+            executable: file.get("executable").and_then(Value::as_bool).unwrap_or(false),
+            target: file.get("target").and_then(Value::as_str).map(String::from),
+            download: file
+                .get("downloads")
+                .and_then(|d| d.get("raw"))
+                .and_then(|raw| serde_json::from_value(raw.clone()).ok()),
+        })
+        .collect();
+    parsed.sort_by(|a, b| a.path.cmp(&b.path));
+    parsed
+}
@@ -1,253 +1,324 @@
-use std::{collections::HashMap, time::Duration};
-
-use axum::{extract::Path, response::IntoResponse, Json};
-use once_cell::sync::Lazy;
-use reqwest::{Client, StatusCode};
-use serde_json::Value;
-use tokio::{sync::RwLock, time::Instant};
-
-use crate::types::{
-    AssetIndex, Downloadable, ExtractionHint, Library, MinecraftVersion,
-    NativeLibrary, NormalizedArguments, NormalizedVersion, VersionManifest, MOJANG_URL,
-};
-
-static VERSION_CACHE: Lazy<RwLock<HashMap<String, (NormalizedVersion, Instant)>>> =
-    Lazy::new(|| RwLock::new(HashMap::new()));
-const VERSION_TTL: Duration = Duration::from_secs(60 * 30); // 30 minutos
-
-pub async fn fetch_version_manifest() -> Result<VersionManifest, Box<dyn std::error::Error>> {
-    use serde::Deserialize;
-
-    #[derive(Debug, Deserialize)]
-    struct MojangVersion {
-        id: String,
-        url: String,
-        #[serde(rename = "sha1")]
-        hash: String,
-        #[serde(rename = "releaseTime")]
-        release_time: String,
-        #[serde(rename = "type")]
-        version_type: String,
-    }
-
-    #[derive(Debug, Deserialize)]
-    struct MojangManifest {
-        latest: HashMap<String, String>,
-        versions: Vec<MojangVersion>,
-    }
-
-    let resp = Client::new()
-        .get(MOJANG_URL)
-        .send()
-        .await?
-        .error_for_status()?
-        .json::<MojangManifest>()
-        .await?;
-
-    Ok(VersionManifest {
-        latest_release: resp.latest.get("release").cloned().unwrap_or_default(),
-        latest_snapshot: resp.latest.get("snapshot").cloned().unwrap_or_default(),
-        versions: resp
-            .versions
-            .into_iter()
-            .map(|v| MinecraftVersion {
-                id: v.id,
-                hash: v.hash,
-                url: v.url,
-                release_time: v.release_time,
-                version_type: v.version_type,
-            })
-            .collect(),
-    })
-}
-
-pub async fn get_version_by_id(Path(version_id): Path<String>) -> impl IntoResponse {
-    // Revisar caché
-    {
-        let cache = VERSION_CACHE.read().await;
-        if let Some((cached, timestamp)) = cache.get(&version_id) {
-            if timestamp.elapsed() < VERSION_TTL {
-                return Json(cached.clone()).into_response();
-            }
-        }
-    }
-
-    let manifest = match fetch_version_manifest().await {
-        Ok(m) => m,
-        Err(_) => return (StatusCode::BAD_GATEWAY, "Error obteniendo manifest").into_response(),
-    };
-
-    let version_url = manifest
-        .versions
-        .iter()
-        .find(|v| v.id == version_id)
-        .map(|v| v.url.clone());
-
-    let Some(version_url) = version_url else {
-        return (StatusCode::NOT_FOUND, format!("Versión '{}' no encontrada", version_id)).into_response();
-    };
-
-    let version_json = match Client::new().get(&version_url).send().await {
-        Ok(resp) => match resp.error_for_status().unwrap().json::<Value>().await {
-            Ok(json) => json,
-            Err(_) => return (StatusCode::BAD_GATEWAY, "Error parseando JSON de la versión").into_response(),
-        },
-        Err(_) => return (StatusCode::BAD_GATEWAY, "Error descargando JSON de la versión").into_response(),
-    };
-
-    let result = match parse_version_json(&version_json) {
-        Ok(v) => v,
-        Err(msg) => return (StatusCode::BAD_GATEWAY, msg).into_response(),
-    };
-
-    // Guardar en caché
-    {
-        let mut cache = VERSION_CACHE.write().await;
-        cache.insert(version_id, (result.clone(), Instant::now()));
-    }
-
-    Json(result).into_response()
-}
-
-fn parse_version_json(version_json: &Value) -> Result<NormalizedVersion, &'static str> {
-    let id = version_json.get("id").and_then(Value::as_str).unwrap_or_default().to_string();
-    let release_time = version_json
-        .get("releaseTime")
-        .and_then(Value::as_str)
-        .map(|s| s.to_string());
-
-    let java_version = version_json
-        .get("javaVersion")
-        .and_then(|v| v.get("majorVersion"))
-        .and_then(Value::as_u64)
-        .map(|v| v as u8);
-
-    let extract_downloadable = |v: &Value| -> Option<Downloadable> {
-        Some(Downloadable {
-            url: v.get("url")?.as_str()?.to_string(),
-            sha1: v.get("sha1")?.as_str()?.to_string(),
-            size: v.get("size")?.as_u64()?,
-        })
-    };
-
-    let client_jar = version_json
-        .get("downloads")
-        .and_then(|d| d.get("client"))
-        .and_then(extract_downloadable);
-
-    let server_jar = version_json
-        .get("downloads")
-        .and_then(|d| d.get("server"))
-        .and_then(extract_downloadable);
-
-    let asset_index = version_json.get("assetIndex").map(|a| AssetIndex {
-        id: a.get("id").and_then(Value::as_str).unwrap_or_default().to_string(),
-        url: a.get("url").and_then(Value::as_str).unwrap_or_default().to_string(),
-        sha1: a.get("sha1").and_then(Value::as_str).unwrap_or_default().to_string(),
-        size: a.get("size").and_then(Value::as_u64).unwrap_or(0),
-    });
-
-    let mut libraries = vec![];
-    let mut natives = vec![];
-    let mut requires_extraction = vec![];
-
-    if let Some(Value::Array(libs)) = version_json.get("libraries") {
-        for lib in libs {
-            let name = lib.get("name").and_then(Value::as_str).unwrap_or_default().to_string();
-
-            if let Some(natives_map) = lib.get("natives").and_then(Value::as_object) {
-                for (_os, classifier_val) in natives_map {
-                    if let Some(classifier_str) = classifier_val.as_str() {
-                        if let Some(downloads) = lib.get("downloads").and_then(|d| d.get("classifiers")) {
-                            if let Some(native) = downloads.get(classifier_str) {
-                                if let (Some(url), Some(sha1), Some(size), Some(path)) = (
-                                    native.get("url").and_then(Value::as_str),
-                                    native.get("sha1").and_then(Value::as_str),
-                                    native.get("size").and_then(Value::as_u64),
-                                    native.get("path").and_then(Value::as_str),
-                                ) {
-                                    natives.push(NativeLibrary {
-                                        name: name.clone(),
-                                        classifier: classifier_str.to_string(),
-                                        url: url.to_string(),
-                                        sha1: sha1.to_string(),
-                                        size,
-                                        path: path.to_string(),
-                                    });
-
-                                    let extract = lib
-                                        .get("extract")
-                                        .and_then(|e| e.get("exclude"))
-                                        .is_some();
-
-                                    requires_extraction.push(ExtractionHint {
-                                        path: path.to_string(),
-                                        requires_extraction: extract,
-                                    });
-                                }
-                            }
-                        }
-                    }
-                }
-            } else if let Some(artifact) = lib.get("downloads").and_then(|d| d.get("artifact")) {
-                libraries.push(Library {
-                    name,
-                    url: artifact.get("url").and_then(Value::as_str).map(String::from),
-                    sha1: artifact.get("sha1").and_then(Value::as_str).map(String::from),
-                    size: artifact.get("size").and_then(Value::as_u64),
-                    path: artifact.get("path").and_then(Value::as_str).map(String::from),
-                });
-            }
-        }
-    }
-
-    let arguments = if let Some(args) = version_json.get("arguments") {
-        let game = extract_args(args.get("game"));
-        let jvm = extract_args(args.get("jvm"));
-        NormalizedArguments { game, jvm }
-    } else if let Some(args) = version_json.get("minecraftArguments").and_then(Value::as_str) {
-        let game = args.split_whitespace().map(String::from).collect();
-        NormalizedArguments { game, jvm: vec![] }
-    } else {
-        NormalizedArguments { game: vec![], jvm: vec![] }
-    };
-
-    Ok(NormalizedVersion {
-        id,
-        release_time,
-        java_version,
-        client_jar,
-        server_jar,
-        asset_index,
-        libraries,
-        natives,
-        arguments,
-        requires_extraction,
-    })
-}
-
-fn extract_args(value: Option<&Value>) -> Vec<String> {
-    let mut result = vec![];
-
-    if let Some(Value::Array(entries)) = value {
-        for entry in entries {
-            match entry {
-                Value::String(s) => result.push(s.clone()),
-                Value::Object(obj) => {
-                    if let Some(Value::String(val)) = obj.get("value") {
-                        result.push(val.clone());
-                    } else if let Some(Value::Array(arr)) = obj.get("value") {
-                        for item in arr {
-                            if let Some(s) = item.as_str() {
-                                result.push(s.to_string());
-                            }
-                        }
-                    }
-                }
-                _ => {}
-            }
-        }
-    }
-
-    result
-}
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+    Json,
+};
+use manifestor_core::parse_version_json;
+use once_cell::sync::Lazy;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+
+use crate::api::with_cache_headers;
+use crate::cache::{self, disk, negative_version_key, singleflight::SingleFlight, version_key, Cached, Freshness};
+use crate::experimental;
+use crate::history;
+use crate::maven;
+use crate::metrics;
+use crate::mirror::{self, MirrorChoice};
+use crate::schema::Schema;
+use crate::state::AppState;
+use crate::types::{NormalizedVersion, VersionManifest};
+
+pub mod arguments;
+pub mod assets;
+pub mod batch;
+pub mod breaker;
+pub mod bundle;
+pub mod diff;
+pub mod java;
+pub mod resolve;
+
+use breaker::Attempt;
+
+type VersionResult = Result<(NormalizedVersion, String), (StatusCode, String)>;
+
+/// Error recordado para un id inexistente o que no se pudo normalizar.
+#[derive(Debug, Serialize, Deserialize)]
+struct NegativeEntry {
+    status: u16,
+    message: String,
+}
+
+// Peticiones a upstream en vuelo, compartidas entre clientes concurrentes.
+static MANIFEST_FLIGHT: Lazy<SingleFlight<Result<VersionManifest, String>>> = Lazy::new(SingleFlight::new);
+static VERSION_FLIGHT: Lazy<SingleFlight<VersionResult>> = Lazy::new(SingleFlight::new);
+
+pub async fn fetch_version_manifest(state: &AppState) -> Result<VersionManifest, Box<dyn std::error::Error + Send + Sync>> {
+    MANIFEST_FLIGHT
+        .run("manifest", || async {
+            let Some(attempt) = state.breaker.allow() else {
+                return Err(CIRCUIT_OPEN.to_string());
+            };
+
+            metrics::increment_counter(metrics::UPSTREAM_REQUESTS, &[("target", "manifest")]);
+            let url = &state.settings().upstream.manifest_url;
+            let result = state.source.manifest(url).await;
+            record_upstream(attempt, "manifest", result.is_ok());
+            if let Ok(manifest) = &result {
+                history::record_manifest(state, manifest).await;
+            }
+            result.map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(Into::into)
+}
+
+const CIRCUIT_OPEN: &str = "Circuit breaker abierto: upstream no disponible temporalmente";
+
+fn record_upstream(attempt: Attempt<'_>, target: &str, success: bool) {
+    attempt.record(success);
+    if !success {
+        metrics::increment_counter(metrics::UPSTREAM_ERRORS, &[("target", target)]);
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct VersionQuery {
+    /// La versión tal como se servía en esa fecha, desde el historial.
+    pub as_of: Option<String>,
+}
+
+pub async fn get_version_by_id(
+    State(state): State<AppState>,
+    Path(version_id): Path<String>,
+    Query(query): Query<VersionQuery>,
+    mirror: MirrorChoice,
+    schema: Schema,
+) -> impl IntoResponse {
+    if let Some(as_of) = &query.as_of {
+        let snapshot = match history::parse_timestamp(as_of) {
+            Ok(at) => history::version_as_of(&state, &version_id, at).await,
+            Err(msg) => Err((StatusCode::BAD_REQUEST, msg)),
+        };
+        return match snapshot {
+            Ok(snapshot) => version_response(&state, &mirror, &schema, snapshot.data, snapshot.etag),
+            Err(err) => err.into_response(),
+        };
+    }
+
+    match lookup_version(&state, &version_id).await {
+        Ok(cached) => {
+            let ttl = cache::settings().version_ttl();
+            let response = version_response(&state, &mirror, &schema, cached.data, cached.etag);
+            with_cache_headers(response, cached.age, ttl, cached.freshness)
+        }
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Versión normalizada desde caché, upstream o, si upstream falla, disco.
+pub(crate) async fn lookup_version(state: &AppState, version_id: &str) -> Result<Cached<NormalizedVersion>, (StatusCode, String)> {
+    // Revisar caché
+    let key = version_key(version_id);
+    let ttl = cache::settings().version_ttl();
+    if let Some((data, etag, age)) = cache::get_json::<NormalizedVersion>(&key).await {
+        if age < ttl {
+            metrics::cache_lookup("version", true);
+            return Ok(Cached { data, etag, age, freshness: Freshness::Fresh });
+        }
+
+        // Vencida pero dentro de la ventana de gracia: servir y refrescar aparte.
+        if age < ttl + cache::stale_grace() {
+            metrics::cache_lookup("version", true);
+            metrics::increment_counter(metrics::CACHE_STALE_HITS, &[("cache", "version")]);
+            if let Some(guard) = cache::try_begin_refresh(&key) {
+                let version_id = version_id.to_string();
+                let state = state.clone();
+                tokio::spawn(async move {
+                    let _guard = guard;
+                    if let Err((_, msg)) = refresh_version(&state, &version_id).await {
+                        warn!("Background refresh of version {} failed: {}", version_id, msg);
+                    }
+                });
+            }
+            let freshness = if state.breaker.is_open() { Freshness::Fallback } else { Freshness::Stale };
+            return Ok(Cached { data, etag, age, freshness });
+        }
+    }
+
+    // Ids que hace poco no existían o no se pudieron normalizar.
+    if let Some((negative, _, _)) = cache::get_json::<NegativeEntry>(&negative_version_key(version_id)).await {
+        metrics::cache_lookup("version_negative", true);
+        let status = StatusCode::from_u16(negative.status).unwrap_or(StatusCode::NOT_FOUND);
+        return Err((status, negative.message));
+    }
+
+    metrics::cache_lookup("version", false);
+    match refresh_version(state, version_id).await {
+        Ok((data, etag)) => Ok(Cached { data, etag, age: Duration::ZERO, freshness: Freshness::Fresh }),
+        Err((status, msg)) => {
+            // Si el fallo es de upstream, servir la última copia conocida en disco.
+            if status == StatusCode::BAD_GATEWAY
+                && let Some(restored) = disk::load_version(version_id).await
+            {
+                return Ok(Cached {
+                    data: restored.data,
+                    etag: restored.etag,
+                    age: restored.age,
+                    freshness: Freshness::Fallback,
+                });
+            }
+            Err((status, msg))
+        }
+    }
+}
+
+fn version_response(
+    state: &AppState,
+    mirror: &MirrorChoice,
+    schema: &Schema,
+    mut version: NormalizedVersion,
+    etag: String,
+) -> Response {
+    let etag = match &mirror.0 {
+        Some(m) => {
+            m.apply(&mut version);
+            cache::etag_for_json(&version)
+        }
+        None => etag,
+    };
+    mirror::vary(state, schema.respond(&version, Some(etag)))
+}
+
+/// Normaliza un JSON de versión enviado por el cliente, sin tocar upstream.
+pub async fn normalize_version(
+    State(state): State<AppState>,
+    mirror: MirrorChoice,
+    schema: Schema,
+    Json(raw): Json<Value>,
+) -> Response {
+    if !raw.is_object() {
+        return (StatusCode::BAD_REQUEST, "Se esperaba un objeto JSON de versión").into_response();
+    }
+
+    match parse_version_json(&raw) {
+        Ok(mut version) => {
+            maven::fill_checksums(&state, &mut version).await;
+            mirror.apply(&mut version);
+            mirror::vary(&state, schema.respond(&version, None))
+        }
+        Err(msg) => (StatusCode::UNPROCESSABLE_ENTITY, msg).into_response(),
+    }
+}
+
+async fn refresh_version(state: &AppState, version_id: &str) -> VersionResult {
+    VERSION_FLIGHT
+        .run(version_id, || fetch_and_store_version(state, version_id))
+        .await
+}
+
+/// Precarga una versión si falta en caché o vencería antes de `horizon`.
+/// Devuelve `true` si se descargó de nuevo.
+pub async fn warm_version(
+    state: &AppState,
+    manifest: &VersionManifest,
+    version_id: &str,
+    horizon: Duration,
+) -> Result<bool, String> {
+    if let Some(entry) = cache::store().get(&version_key(version_id)).await
+        && entry.age() + horizon < cache::settings().version_ttl()
+    {
+        return Ok(false);
+    }
+
+    VERSION_FLIGHT
+        .run(version_id, || store_version_from_manifest(state, manifest, version_id))
+        .await
+        .map(|_| true)
+        .map_err(|(_, msg)| msg)
+}
+
+// Descarga, normaliza y guarda en caché una versión.
+async fn fetch_and_store_version(state: &AppState, version_id: &str) -> VersionResult {
+    let manifest = fetch_version_manifest(state)
+        .await
+        .map_err(|_| (StatusCode::BAD_GATEWAY, "Error obteniendo manifest".to_string()))?;
+
+    store_version_from_manifest(state, &manifest, version_id).await
+}
+
+async fn store_version_from_manifest(state: &AppState, manifest: &VersionManifest, version_id: &str) -> VersionResult {
+    let version_json = match fetch_raw_version(state, manifest, version_id).await {
+        Ok(json) => json,
+        Err(err) if err.0 == StatusCode::NOT_FOUND => return Err(remember_failure(version_id, err).await),
+        Err(err) => return Err(err),
+    };
+    let mut result = match parse_version_json(&version_json) {
+        Ok(result) => result,
+        Err(msg) => {
+            let err = (StatusCode::BAD_GATEWAY, format!("No se pudo normalizar la versión: {}", msg));
+            return Err(remember_failure(version_id, err).await);
+        }
+    };
+
+    maven::fill_checksums(state, &mut result).await;
+
+    // Guardar en caché
+    let key = version_key(version_id);
+    let ttl = cache::settings().version_ttl() + cache::stale_grace();
+    let etag = cache::set_json(&key, &result, ttl).await;
+    disk::store_version(version_id, &result, &etag).await;
+    let sha1 = manifest.versions.iter().find(|v| v.id == version_id).map(|v| v.hash.as_str());
+    history::record_version(state, version_id, sha1, &result, &etag).await;
+    cache::store().invalidate(&negative_version_key(version_id)).await;
+
+    Ok((result, etag))
+}
+
+// Guarda el error en la caché negativa y lo devuelve tal cual.
+async fn remember_failure(version_id: &str, (status, message): (StatusCode, String)) -> (StatusCode, String) {
+    let entry = NegativeEntry {
+        status: status.as_u16(),
+        message,
+    };
+    cache::set_json(&negative_version_key(version_id), &entry, cache::settings().negative_ttl()).await;
+    (status, entry.message)
+}
+
+/// Descarga el JSON original de Mojang para una versión del manifest.
+pub(crate) async fn fetch_raw_version(
+    state: &AppState,
+    manifest: &VersionManifest,
+    version_id: &str,
+) -> Result<Value, (StatusCode, String)> {
+    let version_url = match manifest.versions.iter().find(|v| v.id == version_id) {
+        Some(v) => Some(v.url.clone()),
+        None => experimental::find(state, version_id).await.map(|v| v.url),
+    };
+
+    let Some(version_url) = version_url else {
+        return Err((StatusCode::NOT_FOUND, format!("Versión '{}' no encontrada", version_id)));
+    };
+
+    let Some(attempt) = state.breaker.allow() else {
+        return Err((StatusCode::BAD_GATEWAY, CIRCUIT_OPEN.to_string()));
+    };
+
+    metrics::increment_counter(metrics::UPSTREAM_REQUESTS, &[("target", "version")]);
+    let fetched = state.source.version(&version_url).await;
+    record_upstream(attempt, "version", fetched.is_ok());
+    match fetched {
+        Ok(json) => Ok(json),
+        Err(e) if e.is_decode() => Err((StatusCode::BAD_GATEWAY, "Error parseando JSON de la versión".to_string())),
+        Err(_) => Err((StatusCode::BAD_GATEWAY, "Error descargando JSON de la versión".to_string())),
+    }
+}
+
+/// Carga en la caché las versiones normalizadas guardadas en disco que sigan frescas.
+pub async fn rehydrate_version_cache() -> usize {
+    let mut restored = 0;
+
+    for entry in disk::load_versions().await {
+        let key = version_key(&entry.key);
+        let ttl = cache::settings().version_ttl() + cache::stale_grace();
+        if cache::restore_json(&key, &entry.data, entry.etag, entry.age, ttl).await {
+            restored += 1;
+        }
+    }
+
+    restored
+}
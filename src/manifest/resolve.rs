@@ -0,0 +1,90 @@
+use axum::{
+    extract::State,
+    response::{IntoResponse, Response},
+    Json,
+};
+use manifestor_core::{merge_inherited, parse_version_json};
+use reqwest::StatusCode;
+use serde_json::Value;
+
+use super::{fetch_raw_version, fetch_version_manifest};
+use crate::cache::{get_cached_manifest, Freshness};
+use crate::experimental;
+use crate::maven;
+use crate::mirror::{self, MirrorChoice};
+use crate::profiles;
+use crate::schema::Schema;
+use crate::state::AppState;
+use crate::types::VersionManifest;
+
+// Límite de niveles de `inheritsFrom`, para no seguir ciclos indefinidamente.
+const MAX_DEPTH: usize = 8;
+
+/// Aplana un perfil personalizado (Forge, Fabric, OptiFine...) con sus padres
+/// vanilla y devuelve la `NormalizedVersion` resultante.
+pub async fn resolve_version(
+    State(state): State<AppState>,
+    mirror: MirrorChoice,
+    schema: Schema,
+    Json(custom): Json<Value>,
+) -> Response {
+    if !custom.is_object() {
+        return (StatusCode::BAD_REQUEST, "Se esperaba un objeto JSON de versión").into_response();
+    }
+
+    let resolved = match flatten(&state, custom).await {
+        Ok(resolved) => resolved,
+        Err(err) => return err.into_response(),
+    };
+    match parse_version_json(&resolved) {
+        Ok(mut version) => {
+            maven::fill_checksums(&state, &mut version).await;
+            mirror.apply(&mut version);
+            mirror::vary(&state, schema.respond(&version, None))
+        }
+        Err(msg) => (StatusCode::UNPROCESSABLE_ENTITY, msg).into_response(),
+    }
+}
+
+/// Sigue `inheritsFrom` hasta un JSON sin padre. Los ids del manifest (y de
+/// los catálogos experimentales) van antes que los perfiles guardados, para
+/// que un perfil no pueda suplantar a una versión vanilla.
+pub(crate) async fn flatten(state: &AppState, custom: Value) -> Result<Value, (StatusCode, String)> {
+    let mut resolved = custom;
+    for _ in 0..MAX_DEPTH {
+        let Some(parent_id) = resolved.get("inheritsFrom").and_then(Value::as_str).map(String::from) else {
+            return Ok(resolved);
+        };
+
+        // Sin manifest (ni en caché ni en disco) no se sabe si el padre es una
+        // versión publicada, y un perfil no debe ocupar su lugar.
+        let Some(manifest) = manifest(state).await else {
+            let msg = "Sin manifest de Mojang no se puede resolver inheritsFrom".to_string();
+            return Err((StatusCode::SERVICE_UNAVAILABLE, msg));
+        };
+        let profile = if is_listed(state, &manifest, &parent_id).await {
+            None
+        } else {
+            profiles::raw_profile(state, &parent_id).await
+        };
+        let parent = match profile {
+            Some(profile) => profile,
+            None => fetch_raw_version(state, &manifest, &parent_id).await?,
+        };
+        resolved = merge_inherited(&parent, &resolved);
+    }
+
+    Err((StatusCode::BAD_REQUEST, "Cadena de inheritsFrom demasiado larga".to_string()))
+}
+
+/// Manifest de Mojang en caché, o `None` si no hay ninguno.
+pub(crate) async fn manifest(state: &AppState) -> Option<VersionManifest> {
+    let fetch_state = state.clone();
+    let cached = get_cached_manifest(move || async move { fetch_version_manifest(&fetch_state).await }).await;
+    (cached.freshness != Freshness::Unavailable).then_some(cached.data)
+}
+
+/// Si `id` es una versión de Mojang o de un catálogo experimental.
+pub(crate) async fn is_listed(state: &AppState, manifest: &VersionManifest, id: &str) -> bool {
+    manifest.versions.iter().any(|v| v.id == id) || experimental::find(state, id).await.is_some()
+}
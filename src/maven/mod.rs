@@ -0,0 +1,257 @@
+use axum::{
+    extract::{Query, State},
+    response::{IntoResponse, Response},
+    Json,
+};
+use futures_util::{stream, StreamExt};
+use manifestor_core::maven::Coordinate;
+use reqwest::{header::CONTENT_LENGTH, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::cache;
+use crate::metrics;
+use crate::state::AppState;
+use crate::types::{Checksums, HashAlgorithm, NormalizedVersion};
+
+// Descargas de checksums simultáneas al completar una versión.
+const PARALLELISM: usize = 8;
+
+#[derive(Debug, Deserialize)]
+pub struct ResolveQuery {
+    pub name: String,
+}
+
+/// Artefacto encontrado en alguno de los repositorios configurados.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedArtifact {
+    pub name: String,
+    pub path: String,
+    pub url: String,
+    pub repository: String,
+    /// Según el `.sha1` publicado junto al artefacto, si lo hay.
+    pub sha1: Option<String>,
+    pub size: Option<u64>,
+    /// `.sha256` y `.sha512` publicados, para repositorios que no dan sha1.
+    #[serde(default, skip_serializing_if = "Checksums::is_empty")]
+    pub checksums: Checksums,
+}
+
+/// `GET /maven/resolve?name=grupo:artefacto:versión[:clasificador]`.
+pub async fn resolve_handler(State(state): State<AppState>, Query(query): Query<ResolveQuery>) -> Response {
+    let coordinate = match Coordinate::parse(&query.name) {
+        Ok(coordinate) => coordinate,
+        Err(msg) => return (StatusCode::BAD_REQUEST, msg).into_response(),
+    };
+
+    match resolve(&state, &query.name, &coordinate).await {
+        Ok(Some(artifact)) => Json(artifact).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            format!("'{}' no está en ningún repositorio configurado", query.name),
+        )
+            .into_response(),
+        Err(msg) => (StatusCode::BAD_GATEWAY, msg).into_response(),
+    }
+}
+
+/// Busca `coordinate` en los repositorios, en orden. Los resultados (también
+/// "no encontrado") se cachean; los errores de red no.
+pub async fn resolve(state: &AppState, name: &str, coordinate: &Coordinate) -> Result<Option<ResolvedArtifact>, String> {
+    let key = format!("maven:{}", name.trim());
+    if let Some((cached, _, _)) = cache::get_json::<Option<ResolvedArtifact>>(&key).await {
+        metrics::cache_lookup("maven", true);
+        return Ok(cached);
+    }
+    metrics::cache_lookup("maven", false);
+
+    let settings = state.settings();
+    let mut last_error = None;
+    for repository in &settings.maven.repositories {
+        match probe(state, repository, coordinate).await {
+            Ok(Some((size, mut checksums))) => {
+                let artifact = ResolvedArtifact {
+                    name: name.trim().to_string(),
+                    path: coordinate.path(),
+                    url: coordinate.url(repository),
+                    repository: repository.clone(),
+                    sha1: checksums.remove(&HashAlgorithm::Sha1),
+                    size,
+                    checksums,
+                };
+                cache::set_json(&key, &Some(artifact.clone()), settings.maven.cache_ttl()).await;
+                return Ok(Some(artifact));
+            }
+            Ok(None) => {}
+            Err(e) => {
+                debug!("Maven probe of {} failed: {}", repository, e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    // Si algún repositorio no respondió, no se puede afirmar que no exista.
+    if let Some(e) = last_error {
+        return Err(format!("Error consultando los repositorios Maven: {}", e));
+    }
+    cache::set_json(&key, &None::<ResolvedArtifact>, cache::settings().negative_ttl()).await;
+    Ok(None)
+}
+
+// `HEAD` del artefacto para saber si existe y su tamaño; después, los
+// checksums publicados junto a él.
+async fn probe(
+    state: &AppState,
+    repository: &str,
+    coordinate: &Coordinate,
+) -> Result<Option<(Option<u64>, Checksums)>, reqwest::Error> {
+    let url = coordinate.url(repository);
+    let head = state
+        .upstream
+        .execute(&url, |client| {
+            let url = url.clone();
+            async move {
+                let response = client.head(&url).send().await?;
+                if response.status() == StatusCode::NOT_FOUND {
+                    return Ok(None);
+                }
+                let response = response.error_for_status()?;
+                let size = response
+                    .headers()
+                    .get(CONTENT_LENGTH)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.parse().ok());
+                Ok(Some(size))
+            }
+        })
+        .await?;
+    let Some(size) = head else {
+        return Ok(None);
+    };
+
+    let checksums = stream::iter(HashAlgorithm::ALL)
+        .map(|alg| {
+            let url = &url;
+            async move { fetch_checksum(state, url, alg).await.map(|hash| (alg, hash)) }
+        })
+        .buffer_unordered(HashAlgorithm::ALL.len())
+        .filter_map(|found| async move { found })
+        .collect()
+        .await;
+    Ok(Some((size, checksums)))
+}
+
+/// Si `url` cuelga de alguno de los repositorios: mismo esquema, host y
+/// puerto, y la ruta del repositorio como prefijo completo. Así
+/// `https://libraries.minecraft.net.example.com/` no pasa por
+/// `https://libraries.minecraft.net/`.
+pub fn in_repositories(url: &str, repositories: &[String]) -> bool {
+    let Ok(url) = Url::parse(url) else {
+        return false;
+    };
+    repositories.iter().filter_map(|repository| Url::parse(repository).ok()).any(|repository| {
+        let prefix = format!("{}/", repository.path().trim_end_matches('/'));
+        url.scheme() == repository.scheme()
+            && url.host_str().is_some()
+            && url.host_str() == repository.host_str()
+            && url.port_or_known_default() == repository.port_or_known_default()
+            && url.path().starts_with(&prefix)
+    })
+}
+
+/// Completa el checksum de las librerías que no traen ninguno si
+/// `maven.fill_checksums` está activo: el `.sha1` o, si el repositorio no lo
+/// publica, el `.sha512` o el `.sha256`. Solo se consultan URLs de los
+/// repositorios configurados, para no hacer peticiones a direcciones
+/// arbitrarias de un JSON enviado por el cliente, y como mucho
+/// `maven.max_checksum_lookups` librerías, para que un JSON con miles no
+/// se convierta en miles de peticiones.
+pub async fn fill_checksums(state: &AppState, version: &mut NormalizedVersion) {
+    let settings = state.settings();
+    if !settings.maven.fill_checksums {
+        return;
+    }
+
+    let missing: Vec<(usize, String)> = version
+        .libraries
+        .iter()
+        .enumerate()
+        .filter(|(_, library)| library.sha1.is_none() && library.checksums.is_empty())
+        .filter_map(|(i, library)| library.url.clone().map(|url| (i, url)))
+        .filter(|(_, url)| in_repositories(url, &settings.maven.repositories))
+        .collect();
+    if missing.len() > settings.maven.max_checksum_lookups {
+        debug!(
+            "{} libraries of {} lack a checksum; looking up only {}",
+            missing.len(),
+            version.id,
+            settings.maven.max_checksum_lookups
+        );
+    }
+
+    let found: Vec<(usize, Option<(HashAlgorithm, String)>)> = stream::iter(missing)
+        .take(settings.maven.max_checksum_lookups)
+        .map(|(i, url)| async move { (i, fetch_any_checksum(state, &url).await) })
+        .buffer_unordered(PARALLELISM)
+        .collect()
+        .await;
+    for (i, checksum) in found {
+        let library = &mut version.libraries[i];
+        match checksum {
+            Some((HashAlgorithm::Sha1, hash)) => library.sha1 = Some(hash),
+            Some((alg, hash)) => {
+                library.checksums.insert(alg, hash);
+            }
+            None => {}
+        }
+    }
+}
+
+const FALLBACK_ORDER: [HashAlgorithm; 3] = [HashAlgorithm::Sha1, HashAlgorithm::Sha512, HashAlgorithm::Sha256];
+
+async fn fetch_any_checksum(state: &AppState, url: &str) -> Option<(HashAlgorithm, String)> {
+    for alg in FALLBACK_ORDER {
+        if let Some(hash) = fetch_checksum(state, url, alg).await {
+            return Some((alg, hash));
+        }
+    }
+    None
+}
+
+// `.sha1`, `.sha256` o `.sha512` publicado junto a un artefacto, cacheado por URL.
+async fn fetch_checksum(state: &AppState, url: &str, alg: HashAlgorithm) -> Option<String> {
+    let key = format!("maven:{}:{}", alg.as_str(), url);
+    if let Some((cached, _, _)) = cache::get_json::<Option<String>>(&key).await {
+        return cached;
+    }
+
+    let checksum_url = format!("{}.{}", url, alg.as_str());
+    let fetched = state
+        .upstream
+        .execute(&checksum_url, |client| {
+            let url = checksum_url.clone();
+            async move {
+                let response = client.get(&url).send().await?;
+                if !response.status().is_success() {
+                    return Ok(None);
+                }
+                Ok(Some(response.text().await?))
+            }
+        })
+        .await;
+
+    // Un error de red no se recuerda: puede ir bien a la próxima.
+    let hash = fetched.ok()?.and_then(|text| parse_checksum(&text, alg));
+    let ttl = match hash {
+        Some(_) => state.settings().maven.cache_ttl(),
+        None => cache::settings().negative_ttl(),
+    };
+    cache::set_json(&key, &hash, ttl).await;
+    hash
+}
+
+// Algunos repositorios añaden el nombre del archivo tras el hash.
+fn parse_checksum(text: &str, alg: HashAlgorithm) -> Option<String> {
+    let hash = text.split_whitespace().next()?.to_ascii_lowercase();
+    (HashAlgorithm::for_hex(&hash) == Some(alg)).then_some(hash)
+}
@@ -0,0 +1,239 @@
+use std::{collections::BTreeMap, fmt::Write, sync::Mutex};
+
+use axum::http::header::CONTENT_TYPE;
+use axum::response::IntoResponse;
+use once_cell::sync::Lazy;
+
+pub const HTTP_REQUESTS: &str = "manifestor_http_requests_total";
+pub const HTTP_DURATION: &str = "manifestor_http_request_duration_seconds";
+pub const CACHE_HITS: &str = "manifestor_cache_hits_total";
+pub const CACHE_STALE_HITS: &str = "manifestor_cache_stale_hits_total";
+pub const CACHE_MISSES: &str = "manifestor_cache_misses_total";
+pub const CACHE_EVICTIONS: &str = "manifestor_cache_evictions_total";
+pub const UPSTREAM_REQUESTS: &str = "manifestor_upstream_requests_total";
+pub const UPSTREAM_ERRORS: &str = "manifestor_upstream_errors_total";
+pub const UPSTREAM_CIRCUIT_STATE: &str = "manifestor_upstream_circuit_state";
+pub const UPSTREAM_CIRCUIT_TRANSITIONS: &str = "manifestor_upstream_circuit_transitions_total";
+pub const PROXY_DOWNLOADS: &str = "manifestor_proxy_downloads_total";
+pub const ARTIFACT_EVICTIONS: &str = "manifestor_artifact_evictions_total";
+pub const RATE_LIMITED: &str = "manifestor_rate_limited_total";
+pub const LOAD_SHED: &str = "manifestor_load_shed_total";
+pub const WEBHOOK_DELIVERIES: &str = "manifestor_webhook_deliveries_total";
+
+// (nombre, tipo, ayuda) para las líneas # HELP / # TYPE.
+const DESCRIPTIONS: &[(&str, &str, &str)] = &[
+    (HTTP_REQUESTS, "counter", "HTTP requests by route, method and status."),
+    (HTTP_DURATION, "histogram", "HTTP request latency by route."),
+    (CACHE_HITS, "counter", "Cache lookups served from cache (fresh or stale)."),
+    (CACHE_STALE_HITS, "counter", "Cache lookups served stale while revalidating."),
+    (CACHE_MISSES, "counter", "Cache lookups that required an upstream fetch."),
+    (CACHE_EVICTIONS, "counter", "In-memory cache entries evicted to stay within size bounds."),
+    (UPSTREAM_REQUESTS, "counter", "Requests made to Mojang upstream."),
+    (UPSTREAM_ERRORS, "counter", "Failed requests to Mojang upstream."),
+    (UPSTREAM_CIRCUIT_STATE, "gauge", "Upstream circuit breaker state (0 closed, 1 open, 2 half-open)."),
+    (UPSTREAM_CIRCUIT_TRANSITIONS, "counter", "Upstream circuit breaker state transitions."),
+    (PROXY_DOWNLOADS, "counter", "Proxied artifact downloads by source and verification result."),
+    (ARTIFACT_EVICTIONS, "counter", "Cached proxy artifacts removed to stay within proxy.max_cache_bytes."),
+    (RATE_LIMITED, "counter", "Requests rejected by the per-IP rate limiter, by route group."),
+    (LOAD_SHED, "counter", "Requests rejected because their route group was at its concurrency limit."),
+    (WEBHOOK_DELIVERIES, "counter", "Webhook notifications by format and outcome."),
+];
+
+const BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+static REGISTRY: Lazy<Mutex<Registry>> = Lazy::new(|| Mutex::new(Registry::default()));
+
+type Labels = Vec<(String, String)>;
+type SeriesKey = (&'static str, Labels);
+
+#[derive(Default)]
+struct Registry {
+    counters: BTreeMap<SeriesKey, u64>,
+    gauges: BTreeMap<SeriesKey, f64>,
+    histograms: BTreeMap<SeriesKey, Histogram>,
+}
+
+struct Histogram {
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+pub fn increment_counter(name: &'static str, labels: &[(&str, &str)]) {
+    if let Ok(mut registry) = REGISTRY.lock() {
+        *registry.counters.entry((name, format_labels(labels))).or_insert(0) += 1;
+    }
+}
+
+pub fn set_gauge(name: &'static str, labels: &[(&str, &str)], value: f64) {
+    if let Ok(mut registry) = REGISTRY.lock() {
+        registry.gauges.insert((name, format_labels(labels)), value);
+    }
+}
+
+pub fn observe_histogram(name: &'static str, labels: &[(&str, &str)], value: f64) {
+    if let Ok(mut registry) = REGISTRY.lock() {
+        let histogram = registry
+            .histograms
+            .entry((name, format_labels(labels)))
+            .or_insert_with(|| Histogram {
+                buckets: vec![0; BUCKETS.len()],
+                sum: 0.0,
+                count: 0,
+            });
+
+        for (bucket, bound) in histogram.buckets.iter_mut().zip(BUCKETS) {
+            if value <= *bound {
+                *bucket += 1;
+            }
+        }
+        histogram.sum += value;
+        histogram.count += 1;
+    }
+}
+
+pub fn cache_lookup(cache: &str, hit: bool) {
+    increment_counter(if hit { CACHE_HITS } else { CACHE_MISSES }, &[("cache", cache)]);
+}
+
+/// Copia de una serie, para exportarla en otros formatos (OTLP).
+#[derive(Debug, Clone)]
+pub struct Series<T> {
+    pub name: &'static str,
+    pub labels: Labels,
+    pub value: T,
+}
+
+#[derive(Debug, Clone)]
+pub struct HistogramSnapshot {
+    /// Límites superiores de cada bucket, sin el `+Inf` final.
+    pub bounds: &'static [f64],
+    /// Observaciones por bucket, acumulativas como en Prometheus.
+    pub cumulative: Vec<u64>,
+    pub sum: f64,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    pub counters: Vec<Series<u64>>,
+    pub gauges: Vec<Series<f64>>,
+    pub histograms: Vec<Series<HistogramSnapshot>>,
+}
+
+pub fn snapshot() -> Snapshot {
+    let Ok(registry) = REGISTRY.lock() else {
+        return Snapshot::default();
+    };
+    let series = |(name, labels): &SeriesKey| (*name, labels.clone());
+
+    Snapshot {
+        counters: registry
+            .counters
+            .iter()
+            .map(|(key, value)| {
+                let (name, labels) = series(key);
+                Series { name, labels, value: *value }
+            })
+            .collect(),
+        gauges: registry
+            .gauges
+            .iter()
+            .map(|(key, value)| {
+                let (name, labels) = series(key);
+                Series { name, labels, value: *value }
+            })
+            .collect(),
+        histograms: registry
+            .histograms
+            .iter()
+            .map(|(key, histogram)| {
+                let (name, labels) = series(key);
+                let value = HistogramSnapshot {
+                    bounds: BUCKETS,
+                    cumulative: histogram.buckets.clone(),
+                    sum: histogram.sum,
+                    count: histogram.count,
+                };
+                Series { name, labels, value }
+            })
+            .collect(),
+    }
+}
+
+/// Descripción de una métrica registrada en `DESCRIPTIONS`.
+pub fn help(name: &str) -> Option<&'static str> {
+    DESCRIPTIONS.iter().find(|(n, _, _)| *n == name).map(|(_, _, help)| *help)
+}
+
+/// Texto en formato de exposición de Prometheus.
+pub fn render() -> String {
+    let Ok(registry) = REGISTRY.lock() else {
+        return String::new();
+    };
+    let mut out = String::new();
+
+    for (name, kind, help) in DESCRIPTIONS {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} {}", name, kind);
+
+        for ((_, labels), value) in registry.counters.iter().filter(|((n, _), _)| n == name) {
+            let _ = writeln!(out, "{}{} {}", name, wrap_labels(&label_text(labels)), value);
+        }
+
+        for ((_, labels), value) in registry.gauges.iter().filter(|((n, _), _)| n == name) {
+            let _ = writeln!(out, "{}{} {}", name, wrap_labels(&label_text(labels)), value);
+        }
+
+        for ((_, labels), histogram) in registry.histograms.iter().filter(|((n, _), _)| n == name) {
+            let labels = &label_text(labels);
+            // Los buckets de Prometheus son acumulativos; `observe` ya los cuenta así.
+            for (bound, count) in BUCKETS.iter().zip(&histogram.buckets) {
+                let le = join_labels(labels, &format!("le=\"{}\"", bound));
+                let _ = writeln!(out, "{}_bucket{{{}}} {}", name, le, count);
+            }
+            let inf = join_labels(labels, "le=\"+Inf\"");
+            let _ = writeln!(out, "{}_bucket{{{}}} {}", name, inf, histogram.count);
+            let _ = writeln!(out, "{}_sum{} {}", name, wrap_labels(labels), histogram.sum);
+            let _ = writeln!(out, "{}_count{} {}", name, wrap_labels(labels), histogram.count);
+        }
+    }
+
+    out
+}
+
+pub async fn metrics_handler() -> impl IntoResponse {
+    ([(CONTENT_TYPE, "text/plain; version=0.0.4")], render())
+}
+
+fn format_labels(labels: &[(&str, &str)]) -> Labels {
+    labels.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+fn label_text(labels: &Labels) -> String {
+    labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, escape(v)))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn wrap_labels(labels: &str) -> String {
+    if labels.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", labels)
+    }
+}
+
+fn join_labels(labels: &str, extra: &str) -> String {
+    if labels.is_empty() {
+        extra.to_string()
+    } else {
+        format!("{},{}", labels, extra)
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
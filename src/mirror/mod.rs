@@ -0,0 +1,111 @@
+use std::{convert::Infallible, sync::Arc};
+
+use axum::{
+    extract::FromRequestParts,
+    http::{header::VARY, request::Parts, HeaderValue},
+    response::Response,
+};
+use manifestor_core::rewrite_urls;
+
+use crate::config::MirrorSettings;
+use crate::state::AppState;
+use crate::types::NormalizedVersion;
+
+pub const MIRROR_HEADER: &str = "x-manifestor-mirror";
+
+/// Reescritura de URLs de Mojang hacia un mirror propio.
+pub struct Mirror {
+    // (host original, base que lo reemplaza)
+    rules: Vec<(String, String)>,
+    enabled_by_default: bool,
+}
+
+impl Mirror {
+    pub fn from_settings(settings: &MirrorSettings) -> Option<Self> {
+        let base = settings.base_url.as_deref()?.trim_end_matches('/');
+        let rules = settings
+            .hosts
+            .iter()
+            .map(|entry| {
+                let (host, prefix) = entry.split_once('=').unwrap_or((entry, ""));
+                let prefix = prefix.trim().trim_matches('/');
+                let target = if prefix.is_empty() { base.to_string() } else { format!("{}/{}", base, prefix) };
+                (host.trim().to_ascii_lowercase(), target)
+            })
+            .collect();
+
+        Some(Self {
+            rules,
+            enabled_by_default: settings.enabled_by_default,
+        })
+    }
+
+    /// URL equivalente en el mirror, o `None` si el host no se reescribe.
+    pub fn rewrite(&self, url: &str) -> Option<String> {
+        let rest = url.strip_prefix("https://").or_else(|| url.strip_prefix("http://"))?;
+        let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let (_, target) = self.rules.iter().find(|(h, _)| h.eq_ignore_ascii_case(host))?;
+        Some(format!("{}/{}", target, path))
+    }
+
+    pub fn apply(&self, version: &mut NormalizedVersion) {
+        rewrite_urls(version, |url| self.rewrite(url));
+    }
+}
+
+/// Mirror a aplicar en esta petición: `?mirror=` tiene prioridad sobre la
+/// cabecera `X-Manifestor-Mirror`, y ambas sobre `enabled_by_default`.
+pub struct MirrorChoice(pub Option<Arc<Mirror>>);
+
+impl FromRequestParts<AppState> for MirrorChoice {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let Some(mirror) = state.mirror() else {
+            return Ok(Self(None));
+        };
+
+        let from_query = parts
+            .uri
+            .query()
+            .into_iter()
+            .flat_map(|q| q.split('&'))
+            .find_map(|pair| pair.strip_prefix("mirror="))
+            .and_then(parse_flag);
+        let from_header = || {
+            parts
+                .headers
+                .get(MIRROR_HEADER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(parse_flag)
+        };
+
+        let enabled = from_query.or_else(from_header).unwrap_or(mirror.enabled_by_default);
+        Ok(Self(enabled.then_some(mirror)))
+    }
+}
+
+impl MirrorChoice {
+    pub fn apply(&self, version: &mut NormalizedVersion) {
+        if let Some(mirror) = &self.0 {
+            mirror.apply(version);
+        }
+    }
+}
+
+/// Marca la respuesta como dependiente de la cabecera del mirror, para que
+/// las cachés intermedias no mezclen ambas variantes.
+pub fn vary(state: &AppState, mut response: Response) -> Response {
+    if state.mirror().is_some() {
+        response.headers_mut().append(VARY, HeaderValue::from_static(MIRROR_HEADER));
+    }
+    response
+}
+
+fn parse_flag(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "on" | "yes" => Some(true),
+        "0" | "false" | "off" | "no" => Some(false),
+        _ => None,
+    }
+}
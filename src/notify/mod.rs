@@ -0,0 +1,210 @@
+use std::time::Duration;
+
+use reqwest::{header::RETRY_AFTER, Client, StatusCode, Url};
+use ring::hmac;
+use serde_json::{json, Value};
+use tokio::{sync::broadcast::error::RecvError, task::JoinHandle};
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+use tracing::{info, warn};
+
+use crate::cache::disk::unix_now;
+use crate::config::NotifySettings;
+use crate::events::VersionEvent;
+use crate::metrics;
+use crate::state::AppState;
+
+pub const SIGNATURE_HEADER: &str = "X-Manifestor-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Manifestor-Timestamp";
+const EVENT_HEADER: &str = "X-Manifestor-Event";
+// Tope para `Retry-After`, que Discord envía al superar su rate limit.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Json,
+    Discord,
+}
+
+impl Format {
+    fn as_str(self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Discord => "discord",
+        }
+    }
+}
+
+/// Un destino de `notify.webhooks`: `discord=URL` o solo `URL`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Webhook {
+    format: Format,
+    url: String,
+}
+
+impl Webhook {
+    // Las URLs de Discord llevan el token en la ruta: en los logs, solo el host.
+    fn host(&self) -> String {
+        Url::parse(&self.url)
+            .ok()
+            .and_then(|u| u.host_str().map(String::from))
+            .unwrap_or_else(|| "URL inválida".to_string())
+    }
+
+    fn parse(raw: &str) -> Self {
+        match raw.split_once('=') {
+            Some(("discord", url)) => Self { format: Format::Discord, url: url.trim().to_string() },
+            Some(("json", url)) => Self { format: Format::Json, url: url.trim().to_string() },
+            _ => Self { format: Format::Json, url: raw.trim().to_string() },
+        }
+    }
+}
+
+/// Lanza la tarea que reenvía a los webhooks las versiones nuevas que publica
+/// el refresher en el bus de eventos. La configuración se lee en cada evento,
+/// así que `/admin/reload` la cambia sin reiniciar.
+///
+/// Al apagar, la tarea espera a que terminen los envíos en curso; los que
+/// estaban esperando para reintentar se abandonan.
+pub fn spawn(state: AppState) -> JoinHandle<()> {
+    let mut receiver = state.events.subscribe();
+    let deliveries = TaskTracker::new();
+    tokio::spawn(async move {
+        loop {
+            let received = tokio::select! {
+                received = receiver.recv() => received,
+                _ = state.shutdown.cancelled() => {
+                    deliveries.close();
+                    deliveries.wait().await;
+                    return;
+                }
+            };
+            let event = match received {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Webhook notifier lagged, {} events dropped", skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => return,
+            };
+
+            let VersionEvent::VersionAdded { version_type, .. } = &event else {
+                continue;
+            };
+            let settings = state.settings();
+            let config = &settings.notify;
+            if config.webhooks.is_empty() || !config.types.contains(version_type) {
+                continue;
+            }
+
+            // Cada destino por separado: uno caído no retrasa a los demás.
+            for webhook in config.webhooks.iter().map(|raw| Webhook::parse(raw)) {
+                let client = state.upstream.client().clone();
+                let config = config.clone();
+                let event = event.clone();
+                let shutdown = state.shutdown.clone();
+                deliveries.spawn(async move { deliver(&client, &config, &webhook, &event, &shutdown).await });
+            }
+        }
+    })
+}
+
+async fn deliver(client: &Client, config: &NotifySettings, webhook: &Webhook, event: &VersionEvent, shutdown: &CancellationToken) {
+    let body = match webhook.format {
+        Format::Json => serde_json::to_vec(event),
+        Format::Discord => serde_json::to_vec(&discord_message(event)),
+    };
+    let Ok(body) = body else {
+        return;
+    };
+
+    let mut attempt = 0;
+    loop {
+        // Cada intento lleva su hora, para que un reintento no parezca viejo.
+        let timestamp = unix_now();
+        let mut request = client
+            .post(&webhook.url)
+            .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+            .header("content-type", "application/json")
+            .header(EVENT_HEADER, "version_added")
+            .header(TIMESTAMP_HEADER, timestamp)
+            .body(body.clone());
+        if let Some(secret) = config.secret.as_deref() {
+            request = request.header(SIGNATURE_HEADER, sign(secret, timestamp, &body));
+        }
+
+        let (retry, delay, outcome) = match request.send().await {
+            Ok(response) if response.status().is_success() => {
+                record(webhook, "delivered");
+                info!("Webhook {} ({}) notified", webhook.host(), webhook.format.as_str());
+                return;
+            }
+            Ok(response) => {
+                let status = response.status();
+                let retry = status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS;
+                let retry_after = response
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.trim().parse::<f64>().ok())
+                    .map(|secs| Duration::from_secs_f64(secs.max(0.0)).min(MAX_RETRY_AFTER));
+                (retry, retry_after, format!("HTTP {}", status))
+            }
+            Err(e) => (true, None, e.to_string()),
+        };
+
+        if !retry || attempt >= config.max_retries {
+            record(webhook, "failed");
+            warn!("Webhook {} failed after {} attempts: {}", webhook.host(), attempt + 1, outcome);
+            return;
+        }
+
+        let delay = delay.unwrap_or_else(|| Duration::from_secs(1 << attempt.min(6)));
+        warn!("Webhook {} failed ({}), retrying in {:?}", webhook.host(), outcome, delay);
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = shutdown.cancelled() => {
+                record(webhook, "failed");
+                warn!("Webhook {} abandoned on shutdown after {} attempts", webhook.host(), attempt + 1);
+                return;
+            }
+        }
+        attempt += 1;
+    }
+}
+
+fn record(webhook: &Webhook, outcome: &str) {
+    metrics::increment_counter(
+        metrics::WEBHOOK_DELIVERIES,
+        &[("format", webhook.format.as_str()), ("outcome", outcome)],
+    );
+}
+
+/// Lo que cubre la firma: la hora de `X-Manifestor-Timestamp`, un `.` y el
+/// cuerpo. El receptor puede rechazar horas viejas para evitar reenvíos.
+pub fn signed_payload(timestamp: u64, body: &[u8]) -> Vec<u8> {
+    let mut payload = format!("{}.", timestamp).into_bytes();
+    payload.extend_from_slice(body);
+    payload
+}
+
+/// `sha256=<hex>` del HMAC-SHA256 de [`signed_payload`].
+fn sign(secret: &str, timestamp: u64, body: &[u8]) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let tag = hmac::sign(&key, &signed_payload(timestamp, body));
+    let hex: String = tag.as_ref().iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", hex)
+}
+
+fn discord_message(event: &VersionEvent) -> Value {
+    let content = match event {
+        VersionEvent::VersionAdded { id, version_type, .. } => match version_type.as_str() {
+            "release" => format!("Nueva release de Minecraft: **{}**", id),
+            "snapshot" => format!("Nueva snapshot de Minecraft: **{}**", id),
+            other => format!("Nueva versión de Minecraft ({}): **{}**", other, id),
+        },
+        VersionEvent::LatestChanged { release, snapshot } => {
+            format!("Última release: **{}**, última snapshot: **{}**", release, snapshot)
+        }
+    };
+    json!({ "username": "manifestor", "content": content })
+}
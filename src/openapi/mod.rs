@@ -0,0 +1,886 @@
+use axum::{
+    http::header::CONTENT_TYPE,
+    response::{Html, IntoResponse},
+    Json,
+};
+use once_cell::sync::Lazy;
+use serde_json::{json, Map, Value};
+
+// No hay `utoipa` disponible, así que el documento se escribe a mano: al
+// cambiar un handler o un tipo serializado hay que actualizarlo aquí.
+static DOCUMENT: Lazy<Value> = Lazy::new(build_document);
+
+const SWAGGER_UI: &str = r##"<!DOCTYPE html>
+<html lang="es">
+<head>
+  <meta charset="utf-8">
+  <title>manifestor API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+  <script>
+    window.ui = SwaggerUIBundle({ url: "/openapi.json", dom_id: "#swagger-ui" });
+  </script>
+</body>
+</html>
+"##;
+
+/// Documento OpenAPI 3 de la API pública y de administración.
+pub async fn openapi_json() -> impl IntoResponse {
+    Json(DOCUMENT.clone())
+}
+
+/// Swagger UI contra `/openapi.json` (los recursos se cargan de unpkg).
+pub async fn docs() -> impl IntoResponse {
+    ([(CONTENT_TYPE, "text/html; charset=utf-8")], Html(SWAGGER_UI))
+}
+
+fn build_document() -> Value {
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "manifestor",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Manifest y versiones de Minecraft normalizadas para launchers. Las respuestas con \
+                versiones normalizadas siguen el esquema v1 salvo que se pida otro con \
+                `Accept: application/vnd.manifestor.v2+json` (en v2, `client_jar`/`server_jar` pasan a \
+                `downloads` y cada native lleva `extract`). Un esquema desconocido responde 406. Con \
+                `signing.key_file`, cada respuesta JSON lleva en `x-manifestor-signature` la firma Ed25519 \
+                (base64) de `manifestor-signature-v1\\n{METHOD}\\n{ruta y query}\\n{timestamp}\\n{cuerpo}`, \
+                con la hora (segundos Unix) en `x-manifestor-signature-timestamp`; se verifica con la clave de \
+                `/publickey`.",
+        },
+        "paths": paths(),
+        "components": {
+            "schemas": schemas(),
+            "securitySchemes": {
+                "bearer": { "type": "http", "scheme": "bearer" },
+                "apiKey": { "type": "apiKey", "in": "header", "name": "X-API-Key" },
+            },
+        },
+    })
+}
+
+fn paths() -> Value {
+    let admin = json!([{ "bearer": [] }, { "apiKey": [] }]);
+    let mirror = json!({
+        "name": "mirror", "in": "query", "required": false,
+        "description": "Forzar (true) o desactivar (false) la reescritura de URLs al mirror.",
+        "schema": { "type": "boolean" },
+    });
+
+    json!({
+        "/manifest": {
+            "get": {
+                "summary": "Lista de versiones, con filtro y paginación opcionales",
+                "parameters": [
+                    query("type", "Tipos separados por comas (release, snapshot, old_beta, old_alpha)", string()),
+                    query("since", "Fecha mínima de publicación (ISO 8601)", string()),
+                    query("limit", "Máximo de versiones a devolver", integer()),
+                    query("offset", "Versiones a saltar", integer()),
+                    query("sort", "Orden por fecha de publicación", json!({ "type": "string", "enum": ["asc", "desc"] })),
+                    query("as_of", "Servir la copia del historial vigente en esa fecha (RFC 3339 o segundos UNIX)", string()),
+                    query("include", "`experimental`: sumar las versiones de experimental.catalogs", string()),
+                    mirror,
+                ],
+                "responses": {
+                    "200": json_response("Página del manifest", reference("ManifestPage")),
+                    "400": text_response("Fecha inválida"),
+                    "404": text_response("Historial desactivado o sin copias anteriores a esa fecha"),
+                },
+            },
+        },
+        "/version/{id}": {
+            "get": {
+                "summary": "Versión normalizada",
+                "parameters": [
+                    path("id", "Id de la versión, p. ej. 1.20.1"),
+                    query("as_of", "Servir la copia del historial vigente en esa fecha (RFC 3339 o segundos UNIX)", string()),
+                    mirror,
+                ],
+                "responses": {
+                    "200": json_response("Versión normalizada", reference("NormalizedVersion")),
+                    "400": text_response("Fecha inválida"),
+                    "404": text_response("La versión no existe o no hay copia anterior a esa fecha"),
+                    "502": text_response("Error obteniendo la versión de Mojang"),
+                },
+            },
+        },
+        "/version/{id}/history": {
+            "get": {
+                "summary": "Copias guardadas de una versión, con su SHA1 y fecha de descarga (requiere history.enabled)",
+                "parameters": [path("id", "Id de la versión")],
+                "responses": {
+                    "200": json_response("Historial", reference("VersionHistory")),
+                    "404": text_response("Historial desactivado o sin copias de la versión"),
+                },
+            },
+        },
+        "/version/{id}/diff/{other}": {
+            "get": {
+                "summary": "Librerías, nativas, asset index y client jar que cambian de una versión a otra",
+                "parameters": [path("id", "Versión de origen"), path("other", "Versión de destino"), mirror],
+                "responses": {
+                    "200": json_response("Diferencias", reference("VersionDiff")),
+                    "404": text_response("Alguna de las versiones no existe"),
+                    "502": text_response("Error obteniendo una versión de Mojang"),
+                },
+            },
+        },
+        "/assets/{id}/diff/{other}": {
+            "get": {
+                "summary": "Objetos del asset index de otra versión que no están ya en el de esta, sin repetir hashes",
+                "parameters": [path("id", "Versión instalada"), path("other", "Versión de destino"), mirror],
+                "responses": {
+                    "200": json_response("Objetos que faltan", reference("AssetDiff")),
+                    "404": text_response("Alguna de las versiones no existe o no tiene asset index"),
+                    "502": text_response("Error obteniendo una versión o un asset index de Mojang"),
+                },
+            },
+        },
+        "/version/{id}/bundle/{platform}": {
+            "get": {
+                "summary": "Versión resuelta para una plataforma: reglas aplicadas, natives elegidos y runtime de Java",
+                "parameters": [
+                    path("id", "Id de la versión"),
+                    path("platform", "`<os>-<arch>`: windows, linux u osx con x64, x86 o arm64"),
+                    mirror,
+                ],
+                "responses": {
+                    "200": json_response("Bundle de la plataforma", reference("PlatformBundle")),
+                    "400": text_response("Plataforma desconocida"),
+                    "404": text_response("La versión no existe"),
+                    "502": text_response("Error obteniendo la versión de Mojang"),
+                },
+            },
+        },
+        "/version/{id}/java": {
+            "get": {
+                "summary": "Runtime de Java que instala el launcher oficial para la versión: componente, build y archivos",
+                "parameters": [
+                    path("id", "Id de la versión"),
+                    query("platform", "`<os>-<arch>`: windows, linux u osx con x64, x86 o arm64", string()),
+                    query("files", "Incluir la lista de archivos del runtime", json!({ "type": "boolean" })),
+                ],
+                "responses": {
+                    "200": json_response("Runtime recomendado", reference("JavaRecommendation")),
+                    "400": text_response("Falta la plataforma o es desconocida"),
+                    "404": text_response("La versión no existe o Mojang no publica su runtime para la plataforma"),
+                    "502": text_response("Error obteniendo la versión o el índice de runtimes de Mojang"),
+                },
+            },
+        },
+        "/version/{id}/arguments/template": {
+            "get": {
+                "summary": "Argumentos separados en literales y placeholders, con la descripción de cada placeholder",
+                "parameters": [
+                    path("id", "Id de la versión"),
+                    query("platform", "`<os>-<arch>`: usar los argumentos del bundle de esa plataforma", string()),
+                ],
+                "responses": {
+                    "200": json_response("Plantilla de argumentos", reference("ArgumentTemplate")),
+                    "400": text_response("Plataforma desconocida"),
+                    "404": text_response("La versión no existe"),
+                },
+            },
+            "post": {
+                "summary": "Sustituye los placeholders enviados y devuelve la línea de comandos",
+                "parameters": [
+                    path("id", "Id de la versión"),
+                    query("platform", "`<os>-<arch>`: usar los argumentos del bundle de esa plataforma", string()),
+                ],
+                "requestBody": {
+                    "required": true,
+                    "content": { "application/json": { "schema": object(&[], json!({
+                        "values": {
+                            "type": "object",
+                            "description": "Valor de cada placeholder; los secretos (auth_access_token, auth_session, auth_xuid) se rechazan",
+                            "additionalProperties": string(),
+                        },
+                    })) } },
+                },
+                "responses": {
+                    "200": json_response("Argumentos sustituidos", reference("ExpandedArguments")),
+                    "400": text_response("Plataforma desconocida o se envió un secreto"),
+                    "404": text_response("La versión no existe"),
+                },
+            },
+        },
+        "/version/resolve": {
+            "post": {
+                "summary": "Aplana un perfil con inheritsFrom (Forge, Fabric...) sobre su versión padre",
+                "parameters": [mirror],
+                "requestBody": raw_version_body(),
+                "responses": {
+                    "200": json_response("Versión resuelta y normalizada", reference("NormalizedVersion")),
+                    "400": text_response("Cuerpo inválido o cadena de herencia demasiado larga"),
+                    "404": text_response("La versión padre no existe"),
+                    "503": text_response("No hay manifest de Mojang, ni en caché ni en disco"),
+                },
+            },
+        },
+        "/normalize": {
+            "post": {
+                "summary": "Normaliza un JSON de versión en formato Mojang",
+                "parameters": [mirror],
+                "requestBody": raw_version_body(),
+                "responses": {
+                    "200": json_response("Versión normalizada", reference("NormalizedVersion")),
+                    "400": text_response("Cuerpo inválido"),
+                },
+            },
+        },
+        "/profiles": {
+            "get": {
+                "summary": "Perfiles del registro privado (requiere profiles.enabled)",
+                "responses": {
+                    "200": json_response("Perfiles", reference("ProfileList")),
+                    "404": text_response("Registro desactivado"),
+                },
+            },
+            "post": {
+                "summary": "Sube un perfil (OptiFine, modpack...): se resuelve inheritsFrom, se normaliza y se guarda",
+                "security": admin,
+                "parameters": [query("name", "Nombre en el registro; por defecto el id del JSON", string())],
+                "requestBody": raw_version_body(),
+                "responses": {
+                    "200": json_response("Perfil sustituido", reference("ProfileSummary")),
+                    "201": json_response("Perfil creado", reference("ProfileSummary")),
+                    "400": text_response("Cuerpo o nombre inválido"),
+                    "401": text_response("Falta profiles.token (o admin.token)"),
+                    "404": text_response("Registro desactivado o padre inexistente"),
+                    "409": text_response("El nombre es el de una versión de Mojang o de un catálogo experimental"),
+                    "422": text_response("El JSON resuelto no es una versión válida"),
+                    "503": text_response("No hay manifest de Mojang, ni en caché ni en disco"),
+                },
+            },
+        },
+        "/profiles/{name}": {
+            "get": {
+                "summary": "Versión normalizada de un perfil",
+                "parameters": [path("name", "Nombre del perfil"), mirror],
+                "responses": {
+                    "200": json_response("Perfil", reference("NormalizedVersion")),
+                    "404": text_response("Registro desactivado o perfil inexistente"),
+                },
+            },
+            "delete": {
+                "summary": "Borra un perfil",
+                "security": admin,
+                "parameters": [path("name", "Nombre del perfil")],
+                "responses": {
+                    "204": { "description": "Perfil borrado" },
+                    "404": text_response("Registro desactivado o perfil inexistente"),
+                },
+            },
+        },
+        "/versions/search": {
+            "get": {
+                "summary": "Búsqueda de versiones por id",
+                "parameters": [
+                    json!({ "name": "q", "in": "query", "required": true, "schema": string() }),
+                    query("type", "Tipos separados por comas", string()),
+                    query("limit", "Máximo de resultados (20 por defecto)", integer()),
+                ],
+                "responses": {
+                    "200": json_response("Resultados ordenados por relevancia", reference("SearchResponse")),
+                    "400": text_response("Consulta vacía"),
+                },
+            },
+        },
+        "/versions/batch": {
+            "post": {
+                "summary": "Varias versiones normalizadas en una petición (máximo 64 ids)",
+                "parameters": [mirror],
+                "requestBody": {
+                    "required": true,
+                    "content": { "application/json": { "schema": object(&["ids"], json!({ "ids": array(string()) })) } },
+                },
+                "responses": {
+                    "200": json_response(
+                        "Mapa id → versión normalizada, o `{error: {status, message}}` si falló",
+                        json!({
+                            "type": "object",
+                            "additionalProperties": {
+                                "oneOf": [reference("NormalizedVersion"), reference("BatchError")],
+                            },
+                        }),
+                    ),
+                    "400": text_response("Lista vacía o demasiado larga"),
+                },
+            },
+        },
+        "/bedrock/versions": {
+            "get": {
+                "summary": "Descargas del servidor dedicado de Bedrock (release y preview)",
+                "responses": {
+                    "200": json_response("Versiones por plataforma", reference("BedrockVersions")),
+                    "502": text_response("La fuente oficial no respondió y no hay copia en caché"),
+                },
+            },
+        },
+        "/graphql": {
+            "get": {
+                "summary": "Consulta GraphQL por query string (esquema en /graphql/schema)",
+                "parameters": [
+                    json!({ "name": "query", "in": "query", "required": true, "schema": string() }),
+                    query("variables", "Variables como objeto JSON", string()),
+                    query("operationName", "Operación a ejecutar si hay varias", string()),
+                ],
+                "responses": {
+                    "200": json_response("`data` y, si algún campo falló, `errors`", reference("GraphQLResponse")),
+                    "400": json_response("Consulta inválida", reference("GraphQLResponse")),
+                },
+            },
+            "post": {
+                "summary": "Consulta GraphQL: solo los campos pedidos de versiones, librerías, assets y perfiles",
+                "requestBody": {
+                    "required": true,
+                    "content": { "application/json": { "schema": reference("GraphQLRequest") } },
+                },
+                "responses": {
+                    "200": json_response("`data` y, si algún campo falló, `errors`", reference("GraphQLResponse")),
+                    "400": json_response("Consulta inválida", reference("GraphQLResponse")),
+                },
+            },
+        },
+        "/graphql/schema": {
+            "get": {
+                "summary": "Esquema GraphQL en SDL",
+                "responses": { "200": text_response("SDL") },
+            },
+        },
+        "/maven/resolve": {
+            "get": {
+                "summary": "Resuelve una coordenada Maven a su URL en los repositorios configurados",
+                "parameters": [json!({
+                    "name": "name", "in": "query", "required": true,
+                    "description": "grupo:artefacto:versión[:clasificador][@extensión]",
+                    "schema": string(),
+                })],
+                "responses": {
+                    "200": json_response("Artefacto encontrado", reference("ResolvedArtifact")),
+                    "400": text_response("Coordenada inválida"),
+                    "404": text_response("No está en ningún repositorio"),
+                    "502": text_response("Algún repositorio no respondió"),
+                },
+            },
+        },
+        "/proxy/{hash}": {
+            "get": {
+                "summary": "Descarga un artefacto verificando su checksum",
+                "parameters": [
+                    path("hash", "sha1, sha256 o sha512 esperado, en hexadecimal (el algoritmo se deduce de la longitud)"),
+                    json!({ "name": "url", "in": "query", "required": true, "schema": string() }),
+                ],
+                "responses": {
+                    "200": {
+                        "description": "Contenido del artefacto; la transferencia se corta si el SHA1 no coincide",
+                        "content": { "application/octet-stream": { "schema": { "type": "string", "format": "binary" } } },
+                    },
+                    "403": text_response("Host no permitido, o una redirección a uno que no lo está"),
+                    "502": text_response("Error descargando el artefacto"),
+                },
+            },
+        },
+        "/events": {
+            "get": {
+                "summary": "Flujo SSE de versiones nuevas (eventos version_added y latest_changed)",
+                "responses": {
+                    "200": {
+                        "description": "Un evento por versión añadida o cambio de latest",
+                        "content": { "text/event-stream": { "schema": reference("VersionEvent") } },
+                    },
+                },
+            },
+        },
+        "/publickey": {
+            "get": {
+                "summary": "Clave pública Ed25519 con la que verificar x-manifestor-signature",
+                "responses": {
+                    "200": json_response("Clave pública", reference("PublicKey")),
+                    "404": text_response("Firma desactivada"),
+                },
+            },
+        },
+        "/healthz": { "get": { "summary": "Liveness", "responses": { "200": text_response("ok") } } },
+        "/readyz": {
+            "get": {
+                "summary": "Readiness: caché poblada o Mojang accesible",
+                "responses": {
+                    "200": json_response("Listo", reference("Readiness")),
+                    "503": json_response("No listo", reference("Readiness")),
+                },
+            },
+        },
+        "/metrics": {
+            "get": { "summary": "Métricas en formato Prometheus", "responses": { "200": text_response("Métricas") } },
+        },
+        "/admin/stats": {
+            "get": {
+                "summary": "Estado de la caché y del circuit breaker",
+                "security": admin,
+                "responses": { "200": json_response("Estadísticas", reference("AdminStats")) },
+            },
+        },
+        "/cache/stats": {
+            "get": {
+                "summary": "Entradas, aciertos, edades y último refresco por tipo de caché",
+                "security": admin,
+                "responses": { "200": json_response("Estado de la caché", reference("CacheSnapshot")) },
+            },
+        },
+        "/cache/artifacts/stats": {
+            "get": {
+                "summary": "Artefactos del proxy en disco: tamaño frente al límite, aciertos, corruptos y borrados por el recolector",
+                "security": admin,
+                "responses": { "200": json_response("Estado de los artefactos", reference("ArtifactCacheStats")) },
+            },
+        },
+        "/admin/refresh": {
+            "post": {
+                "summary": "Refresca el manifest y las versiones precargadas",
+                "security": admin,
+                "responses": { "202": { "description": "Refresco lanzado" } },
+            },
+        },
+        "/admin/reload": {
+            "post": {
+                "summary": "Recarga la configuración",
+                "security": admin,
+                "responses": {
+                    "200": json_response("Configuración aplicada", reference("ReloadResult")),
+                    "400": text_response("Configuración inválida"),
+                },
+            },
+        },
+        "/cache": {
+            "delete": {
+                "summary": "Vacía la caché (memoria/Redis y disco)",
+                "security": admin,
+                "responses": { "204": { "description": "Caché vaciada" } },
+            },
+        },
+        "/cache/version/{id}": {
+            "delete": {
+                "summary": "Invalida una versión",
+                "security": admin,
+                "parameters": [path("id", "Id de la versión")],
+                "responses": { "204": { "description": "Versión invalidada" } },
+            },
+        },
+    })
+}
+
+// En dos `json!`: uno solo pasaría del límite de recursión de la macro.
+fn schemas() -> Value {
+    let mut schemas = version_schemas();
+    if let (Value::Object(all), Value::Object(service)) = (&mut schemas, service_schemas()) {
+        all.extend(service);
+    }
+    schemas
+}
+
+fn version_schemas() -> Value {
+    json!({
+        "MinecraftVersion": object(&["id", "sha1", "release_time", "url", "type"], json!({
+            "id": string(),
+            "sha1": string(),
+            "release_time": string(),
+            "url": string(),
+            "type": string(),
+        })),
+        "ManifestPage": object(&["latest_release", "latest_snapshot", "total", "offset", "count", "versions"], json!({
+            "latest_release": string(),
+            "latest_snapshot": string(),
+            "total": integer(),
+            "offset": integer(),
+            "count": integer(),
+            "versions": array(reference("MinecraftVersion")),
+        })),
+        "NormalizedVersion": object(&["id", "libraries", "natives", "arguments", "requires_extraction", "legacy"], json!({
+            "id": string(),
+            "release_time": nullable(string()),
+            "type": nullable(string()),
+            "main_class": nullable(string()),
+            "compliance_level": nullable(integer()),
+            "minimum_launcher_version": nullable(integer()),
+            "java_version": nullable(integer()),
+            "client_jar": nullable(reference("Downloadable")),
+            "server_jar": nullable(reference("Downloadable")),
+            "asset_index": nullable(reference("AssetIndex")),
+            "libraries": array(reference("Library")),
+            "natives": array(reference("NativeLibrary")),
+            "arguments": reference("NormalizedArguments"),
+            "requires_extraction": array(reference("ExtractionHint")),
+            "logging": nullable(reference("LoggingConfig")),
+            "legacy": { "type": "boolean" },
+            "legacy_assets": nullable(reference("LegacyAssets")),
+        })),
+        "LegacyAssets": object(&["index", "map_to_resources", "virtual", "directory"], json!({
+            "index": string(),
+            "map_to_resources": { "type": "boolean" },
+            "virtual": { "type": "boolean" },
+            "directory": string(),
+        })),
+        "VersionDiff": object(&["from", "to", "libraries", "natives"], json!({
+            "from": string(),
+            "to": string(),
+            "libraries": list_diff("Library"),
+            "natives": list_diff("NativeLibrary"),
+            "asset_index": nullable(change(nullable(reference("AssetIndex")))),
+            "client_jar": nullable(change(nullable(reference("Downloadable")))),
+        })),
+        "AssetDiff": object(
+            &["from", "to", "from_index", "to_index", "objects", "removed", "unique_objects", "unique_size"],
+            json!({
+                "from": string(),
+                "to": string(),
+                "from_index": string(),
+                "to_index": string(),
+                "objects": array(object(&["name", "hash", "size", "url"], json!({
+                    "name": string(),
+                    "hash": string(),
+                    "size": integer(),
+                    "url": string(),
+                }))),
+                "removed": array(string()),
+                "unique_objects": integer(),
+                "unique_size": { "type": "integer", "description": "Bytes a descargar: cada hash cuenta una vez" },
+            }),
+        ),
+        "VersionHistory": object(&["id", "records"], json!({
+            "id": string(),
+            "records": array(object(&["etag", "fetched_at", "fetched_at_unix"], json!({
+                "sha1": nullable(string()),
+                "etag": string(),
+                "fetched_at": string(),
+                "fetched_at_unix": integer(),
+            }))),
+        })),
+        "BatchError": object(&["error"], json!({
+            "error": object(&["status", "message"], json!({ "status": integer(), "message": string() })),
+        })),
+        "ArgumentTemplate": object(&["game", "jvm", "placeholders"], json!({
+            "game": array(reference("TemplateArgument")),
+            "jvm": array(reference("TemplateArgument")),
+            "placeholders": {
+                "type": "object",
+                "additionalProperties": object(&["secret"], json!({
+                    "description": nullable(string()),
+                    "secret": { "type": "boolean" },
+                })),
+            },
+        })),
+        "TemplateArgument": object(&["raw", "tokens"], json!({
+            "raw": string(),
+            "tokens": array(object(&["kind"], json!({
+                "kind": { "type": "string", "enum": ["literal", "placeholder"] },
+                "value": { "type": "string", "description": "Texto, si `kind` es `literal`" },
+                "name": { "type": "string", "description": "Placeholder, si `kind` es `placeholder`" },
+            }))),
+        })),
+        "ExpandedArguments": object(&["game", "jvm", "command_line", "unresolved"], json!({
+            "game": array(string()),
+            "jvm": array(string()),
+            "command_line": array(string()),
+            "unresolved": array(string()),
+        })),
+        "PlatformBundle": {
+            "allOf": [
+                reference("NormalizedVersion"),
+                object(&["platform", "java_runtime", "placeholders"], json!({
+                    "platform": string(),
+                    "java_runtime": object(&["component", "major_version", "platform"], json!({
+                        "component": string(),
+                        "major_version": integer(),
+                        "platform": string(),
+                    })),
+                    "placeholders": array(string()),
+                })),
+            ],
+        },
+        "JavaRecommendation": java_recommendation(),
+        "BedrockVersions": object(&["latest", "versions"], json!({
+            "latest": object(&[], json!({ "release": nullable(string()), "preview": nullable(string()) })),
+            "versions": array(object(&["version", "type", "platform", "edition", "url"], json!({
+                "version": string(),
+                "type": { "type": "string", "enum": ["release", "preview"] },
+                "platform": { "type": "string", "enum": ["windows", "linux"] },
+                "edition": string(),
+                "url": string(),
+            }))),
+        })),
+        "GraphQLRequest": object(&["query"], json!({
+            "query": string(),
+            "variables": nullable(json!({ "type": "object" })),
+            "operationName": nullable(string()),
+        })),
+        "GraphQLResponse": object(&[], json!({
+            "data": nullable(json!({ "type": "object" })),
+            "errors": array(object(&["message"], json!({
+                "message": string(),
+                "path": array(json!({ "oneOf": [string(), integer()] })),
+            }))),
+        })),
+        "ResolvedArtifact": object(&["name", "path", "url", "repository"], json!({
+            "name": string(),
+            "path": string(),
+            "url": string(),
+            "repository": string(),
+            "sha1": nullable(string()),
+            "size": nullable(integer()),
+            "checksums": reference("Checksums"),
+        })),
+        "Checksums": checksums(),
+        "Downloadable": object(&["url", "sha1", "size"], json!({
+            "url": string(),
+            "sha1": string(),
+            "size": integer(),
+            "checksums": reference("Checksums"),
+        })),
+        "AssetIndex": object(&["id", "url", "sha1", "size"], json!({
+            "id": string(),
+            "url": string(),
+            "sha1": string(),
+            "size": integer(),
+        })),
+        "Library": object(&["name"], json!({
+            "name": string(),
+            "url": nullable(string()),
+            "sha1": nullable(string()),
+            "size": nullable(integer()),
+            "path": nullable(string()),
+            "checksums": reference("Checksums"),
+        })),
+        "NativeLibrary": object(&["name", "classifier", "url", "sha1", "size", "path"], json!({
+            "name": string(),
+            "classifier": string(),
+            "url": string(),
+            "sha1": string(),
+            "size": integer(),
+            "path": string(),
+            "checksums": reference("Checksums"),
+        })),
+        "ExtractionHint": object(&["path", "requires_extraction"], json!({
+            "path": string(),
+            "requires_extraction": { "type": "boolean" },
+        })),
+        "NormalizedArguments": object(&["game", "jvm"], json!({
+            "game": array(string()),
+            "jvm": array(string()),
+        })),
+        "LoggingConfig": object(&["argument", "type", "file"], json!({
+            "argument": string(),
+            "type": string(),
+            "file": reference("LoggingFile"),
+        })),
+        "LoggingFile": object(&["id", "url", "sha1", "size"], json!({
+            "id": string(),
+            "url": string(),
+            "sha1": string(),
+            "size": integer(),
+        })),
+        "SearchResponse": object(&["query", "total", "results"], json!({
+            "query": string(),
+            "total": integer(),
+            "results": array(reference("SearchResult")),
+        })),
+        "SearchResult": object(&["id", "type", "release_time", "match"], json!({
+            "id": string(),
+            "type": string(),
+            "release_time": string(),
+            "match": { "type": "string", "enum": ["exact", "prefix", "segment", "contains"] },
+        })),
+        "VersionEvent": object(&["event"], json!({
+            "event": { "type": "string", "enum": ["version_added", "latest_changed"] },
+            "id": string(),
+            "type": string(),
+            "release_time": string(),
+            "release": string(),
+            "snapshot": string(),
+        })),
+    })
+}
+
+fn service_schemas() -> Value {
+    json!({
+        "Readiness": object(&["ready", "cache_populated", "upstream_reachable"], json!({
+            "ready": { "type": "boolean" },
+            "cache_populated": { "type": "boolean" },
+            "upstream_reachable": { "type": "boolean" },
+            "upstream_latency_ms": nullable(integer()),
+            "upstream_error": nullable(string()),
+            "last_manifest_refresh_unix": nullable(integer()),
+            "last_manifest_refresh_age_secs": nullable(integer()),
+        })),
+        "AdminStats": object(&["cache", "circuit_breaker"], json!({
+            "cache": reference("StoreStats"),
+            "circuit_breaker": { "type": "string", "enum": ["closed", "open", "half_open"] },
+            "last_manifest_refresh_age_secs": nullable(integer()),
+        })),
+        "StoreStats": object(&["backend"], json!({
+            "backend": string(),
+            "entries": nullable(integer()),
+            "bytes": nullable(integer()),
+            "max_entries": nullable(integer()),
+            "max_bytes": nullable(integer()),
+        })),
+        "CacheSnapshot": object(&["store", "caches", "artifacts"], json!({
+            "store": reference("StoreStats"),
+            "caches": {
+                "type": "object",
+                "description": "Por tipo: manifest, version, version_negative, bundle, maven, bedrock, java, assets, experimental",
+                "additionalProperties": reference("CacheClassStats"),
+            },
+            "artifacts": reference("CacheClassStats"),
+        })),
+        "CacheClassStats": object(&["hits", "misses"], json!({
+            "entries": nullable(integer()),
+            "bytes": nullable(integer()),
+            "oldest_age_secs": nullable(integer()),
+            "newest_age_secs": nullable(integer()),
+            "hits": integer(),
+            "misses": integer(),
+            "hit_ratio": nullable(json!({ "type": "number" })),
+            "last_refresh_unix": nullable(integer()),
+            "last_refresh_age_secs": nullable(integer()),
+        })),
+        "ArtifactCacheStats": object(&[
+            "files", "bytes", "verify_on_read", "hits", "misses", "errors", "corrupt", "evicted_files", "evicted_bytes",
+        ], json!({
+            "files": integer(),
+            "bytes": integer(),
+            "max_bytes": nullable(integer()),
+            "usage_ratio": nullable(json!({ "type": "number" })),
+            "verify_on_read": { "type": "boolean" },
+            "hits": integer(),
+            "misses": integer(),
+            "errors": integer(),
+            "corrupt": integer(),
+            "evicted_files": integer(),
+            "evicted_bytes": integer(),
+            "oldest_use_age_secs": nullable(integer()),
+            "newest_use_age_secs": nullable(integer()),
+            "last_gc_unix": nullable(integer()),
+        })),
+        "ProfileSummary": object(&["name", "id", "etag", "uploaded_at"], json!({
+            "name": string(),
+            "id": string(),
+            "inherits_from": nullable(string()),
+            "etag": string(),
+            "uploaded_at": string(),
+        })),
+        "ProfileList": object(&["profiles"], json!({ "profiles": array(reference("ProfileSummary")) })),
+        "PublicKey": object(&["algorithm", "key_id", "public_key", "pem", "signed_message"], json!({
+            "algorithm": { "type": "string", "enum": ["ed25519"] },
+            "key_id": string(),
+            "public_key": string(),
+            "pem": string(),
+            "signed_message": string(),
+        })),
+        "ReloadResult": object(&["restart_required"], json!({
+            "restart_required": array(string()),
+        })),
+    })
+}
+
+fn string() -> Value {
+    json!({ "type": "string" })
+}
+
+fn integer() -> Value {
+    json!({ "type": "integer" })
+}
+
+fn array(items: Value) -> Value {
+    json!({ "type": "array", "items": items })
+}
+
+fn reference(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+// En 3.0 `nullable` no se combina con `$ref`, de ahí el `allOf`.
+fn nullable(schema: Value) -> Value {
+    match schema {
+        Value::Object(mut map) if !map.contains_key("$ref") => {
+            map.insert("nullable".to_string(), Value::Bool(true));
+            Value::Object(map)
+        }
+        schema => json!({ "allOf": [schema], "nullable": true }),
+    }
+}
+
+fn java_recommendation() -> Value {
+    let download = object(&["sha1", "size", "url"], json!({ "sha1": string(), "size": integer(), "url": string() }));
+    object(&["version_id", "component", "major_version", "platform", "runtime", "manifest"], json!({
+        "version_id": string(),
+        "component": string(),
+        "major_version": integer(),
+        "platform": string(),
+        "runtime": object(&["name", "released"], json!({ "name": string(), "released": string() })),
+        "manifest": download.clone(),
+        "files": array(object(&["path", "type"], json!({
+            "path": string(),
+            "type": { "type": "string", "enum": ["file", "directory", "link"] },
+            "executable": { "type": "boolean" },
+            "target": string(),
+            "download": download,
+        }))),
+    }))
+}
+
+fn checksums() -> Value {
+    json!({
+        "type": "object",
+        "description": "Checksums además del sha1, por algoritmo; se omite si no hay ninguno",
+        "properties": { "sha256": string(), "sha512": string() },
+    })
+}
+
+fn object(required: &[&str], properties: Value) -> Value {
+    let mut map = Map::new();
+    map.insert("type".to_string(), json!("object"));
+    map.insert("required".to_string(), json!(required));
+    map.insert("properties".to_string(), properties);
+    Value::Object(map)
+}
+
+fn change(schema: Value) -> Value {
+    object(&["from", "to"], json!({ "from": schema.clone(), "to": schema }))
+}
+
+fn list_diff(item: &str) -> Value {
+    object(&["added", "removed", "changed"], json!({
+        "added": array(reference(item)),
+        "removed": array(reference(item)),
+        "changed": array(change(reference(item))),
+    }))
+}
+
+fn query(name: &str, description: &str, schema: Value) -> Value {
+    json!({ "name": name, "in": "query", "required": false, "description": description, "schema": schema })
+}
+
+fn path(name: &str, description: &str) -> Value {
+    json!({ "name": name, "in": "path", "required": true, "description": description, "schema": string() })
+}
+
+fn json_response(description: &str, schema: Value) -> Value {
+    json!({ "description": description, "content": { "application/json": { "schema": schema } } })
+}
+
+fn text_response(description: &str) -> Value {
+    json!({ "description": description, "content": { "text/plain": { "schema": string() } } })
+}
+
+fn raw_version_body() -> Value {
+    json!({
+        "required": true,
+        "description": "JSON de versión en formato Mojang",
+        "content": { "application/json": { "schema": { "type": "object" } } },
+    })
+}
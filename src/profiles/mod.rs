@@ -0,0 +1,286 @@
+//! Registro privado de perfiles de versión (OptiFine, modpacks...). Cada
+//! perfil es un archivo JSON bajo `profiles.dir` con el JSON subido y la
+//! versión ya resuelta y normalizada, que es lo que se sirve.
+
+use std::path::{Path, PathBuf};
+
+use axum::{
+    extract::{Path as UrlPath, Query, State},
+    http::header::LOCATION,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{delete, post},
+    Json, Router,
+};
+use manifestor_core::parse_version_json;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{info, warn};
+
+use crate::auth::require_publisher;
+use crate::cache::{self, disk::{self, unix_now}};
+use crate::history::format_timestamp;
+use crate::manifest::resolve::{flatten, is_listed, manifest};
+use crate::maven;
+use crate::mirror::{self, MirrorChoice};
+use crate::schema::Schema;
+use crate::state::AppState;
+use crate::types::NormalizedVersion;
+
+const MAX_NAME_LEN: usize = 64;
+
+/// Subida y borrado de perfiles, tras `require_publisher`. La lectura va con
+/// el resto de rutas de versiones.
+pub fn router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/profiles", post(upload_profile))
+        .route("/profiles/{name}", delete(delete_profile))
+        .route_layer(middleware::from_fn_with_state(state, require_publisher))
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredProfile {
+    name: String,
+    uploaded_at: u64, // segundos UNIX
+    /// JSON tal como se subió, para resolver perfiles que hereden de este.
+    raw: Value,
+    etag: String,
+    version: NormalizedVersion,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProfileSummary {
+    pub name: String,
+    /// `id` de la versión normalizada.
+    pub id: String,
+    pub inherits_from: Option<String>,
+    pub etag: String,
+    /// RFC 3339, en UTC.
+    pub uploaded_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProfileList {
+    pub profiles: Vec<ProfileSummary>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct UploadQuery {
+    /// Nombre en el registro; por defecto el `id` del JSON.
+    pub name: Option<String>,
+}
+
+fn root(state: &AppState) -> Option<PathBuf> {
+    let settings = &state.settings().profiles;
+    settings
+        .enabled
+        .then(|| settings.dir.clone().unwrap_or_else(|| cache::settings().dir.join("profiles")))
+}
+
+fn disabled() -> Response {
+    (StatusCode::NOT_FOUND, "El registro de perfiles no está activado (profiles.enabled)").into_response()
+}
+
+fn not_found(name: &str) -> Response {
+    (StatusCode::NOT_FOUND, format!("Perfil '{}' no encontrado", name)).into_response()
+}
+
+// Letras, dígitos, `.`, `-` y `_`; es también el nombre del archivo.
+fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && !name.starts_with('.')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_')
+}
+
+fn profile_path(root: &Path, name: &str) -> PathBuf {
+    root.join(format!("{}.json", name))
+}
+
+async fn read(path: &Path) -> Option<StoredProfile> {
+    let bytes = tokio::fs::read(path).await.ok()?;
+    match serde_json::from_slice(&bytes) {
+        Ok(profile) => Some(profile),
+        Err(e) => {
+            warn!("Invalid stored profile {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+async fn load(state: &AppState, name: &str) -> Option<StoredProfile> {
+    let root = root(state)?;
+    if !valid_name(name) {
+        return None;
+    }
+    read(&profile_path(&root, name)).await
+}
+
+/// JSON subido de un perfil, si el registro está activo y existe.
+pub(crate) async fn raw_profile(state: &AppState, name: &str) -> Option<Value> {
+    load(state, name).await.map(|profile| profile.raw)
+}
+
+// Todos los perfiles de `root`, por nombre.
+async fn read_all(root: &Path) -> Vec<StoredProfile> {
+    let mut profiles = vec![];
+    if let Ok(mut entries) = tokio::fs::read_dir(root).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json")
+                && let Some(profile) = read(&path).await
+            {
+                profiles.push(profile);
+            }
+        }
+    }
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+    profiles
+}
+
+/// Resumen y versión normalizada de cada perfil, para GraphQL; `None` si el
+/// registro no está activo.
+pub(crate) async fn stored_profiles(state: &AppState) -> Option<Vec<(ProfileSummary, NormalizedVersion)>> {
+    let root = root(state)?;
+    Some(read_all(&root).await.into_iter().map(|profile| (summary(&profile), profile.version)).collect())
+}
+
+/// Como [`stored_profiles`], pero de un solo perfil.
+pub(crate) async fn stored_profile(state: &AppState, name: &str) -> Option<(ProfileSummary, NormalizedVersion)> {
+    load(state, name).await.map(|profile| (summary(&profile), profile.version))
+}
+
+fn summary(profile: &StoredProfile) -> ProfileSummary {
+    ProfileSummary {
+        name: profile.name.clone(),
+        id: profile.version.id.clone(),
+        inherits_from: profile.raw.get("inheritsFrom").and_then(Value::as_str).map(String::from),
+        etag: profile.etag.clone(),
+        uploaded_at: format_timestamp(profile.uploaded_at),
+    }
+}
+
+/// `POST /profiles`: valida el JSON, resuelve `inheritsFrom` (con versiones
+/// de Mojang u otros perfiles), lo normaliza y lo guarda. Sustituye al
+/// perfil del mismo nombre, si lo hay; los que heredan de él se quedan como
+/// estaban hasta que se vuelvan a subir. No admite nombres de versiones de
+/// Mojang ni de los catálogos experimentales.
+pub async fn upload_profile(
+    State(state): State<AppState>,
+    Query(query): Query<UploadQuery>,
+    Json(raw): Json<Value>,
+) -> Response {
+    let Some(root) = root(&state) else {
+        return disabled();
+    };
+    if !raw.is_object() {
+        return (StatusCode::BAD_REQUEST, "Se esperaba un objeto JSON de versión").into_response();
+    }
+    let Some(name) = query.name.or_else(|| raw.get("id").and_then(Value::as_str).map(String::from)) else {
+        return (StatusCode::BAD_REQUEST, "Falta el nombre: usa ?name= o el campo id").into_response();
+    };
+    if !valid_name(&name) {
+        let msg = format!("Nombre de perfil inválido: hasta {} letras, dígitos, '.', '-' o '_'", MAX_NAME_LEN);
+        return (StatusCode::BAD_REQUEST, msg).into_response();
+    }
+    match manifest(&state).await {
+        Some(manifest) if is_listed(&state, &manifest, &name).await => {
+            let msg = format!("'{}' ya es una versión publicada: usa otro nombre con ?name=", name);
+            return (StatusCode::CONFLICT, msg).into_response();
+        }
+        Some(_) => {}
+        None => {
+            let msg = "Sin manifest de Mojang no se puede comprobar el nombre";
+            return (StatusCode::SERVICE_UNAVAILABLE, msg).into_response();
+        }
+    }
+
+    let resolved = match flatten(&state, raw.clone()).await {
+        Ok(resolved) => resolved,
+        Err(err) => return err.into_response(),
+    };
+    let mut version = match parse_version_json(&resolved) {
+        Ok(version) => version,
+        Err(msg) => return (StatusCode::UNPROCESSABLE_ENTITY, msg).into_response(),
+    };
+    maven::fill_checksums(&state, &mut version).await;
+
+    let path = profile_path(&root, &name);
+    let replaced = tokio::fs::try_exists(&path).await.unwrap_or(false);
+    let profile = StoredProfile {
+        name: name.clone(),
+        uploaded_at: unix_now(),
+        raw,
+        etag: cache::etag_for_json(&version),
+        version,
+    };
+    let written = match serde_json::to_vec(&profile) {
+        Ok(bytes) => disk::write_atomic(&path, &bytes).await,
+        Err(e) => Err(std::io::Error::other(e)),
+    };
+    if let Err(e) = written {
+        warn!("Could not store profile {}: {}", name, e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "No se pudo guardar el perfil").into_response();
+    }
+
+    info!("Stored profile {}", name);
+    let status = if replaced { StatusCode::OK } else { StatusCode::CREATED };
+    (status, [(LOCATION, format!("/profiles/{}", name))], Json(summary(&profile))).into_response()
+}
+
+/// `GET /profiles`.
+pub async fn list_profiles(State(state): State<AppState>) -> Response {
+    let Some(root) = root(&state) else {
+        return disabled();
+    };
+
+    let profiles = read_all(&root).await.iter().map(summary).collect();
+    Json(ProfileList { profiles }).into_response()
+}
+
+/// `GET /profiles/{name}`: la versión normalizada del perfil.
+pub async fn get_profile(
+    State(state): State<AppState>,
+    UrlPath(name): UrlPath<String>,
+    mirror: MirrorChoice,
+    schema: Schema,
+) -> Response {
+    if root(&state).is_none() {
+        return disabled();
+    }
+    let Some(profile) = load(&state, &name).await else {
+        return not_found(&name);
+    };
+
+    let mut version = profile.version;
+    let etag = match &mirror.0 {
+        Some(m) => {
+            m.apply(&mut version);
+            cache::etag_for_json(&version)
+        }
+        None => profile.etag,
+    };
+    mirror::vary(&state, schema.respond(&version, Some(etag)))
+}
+
+/// `DELETE /profiles/{name}`.
+pub async fn delete_profile(State(state): State<AppState>, UrlPath(name): UrlPath<String>) -> Response {
+    let Some(root) = root(&state) else {
+        return disabled();
+    };
+    if !valid_name(&name) {
+        return not_found(&name);
+    }
+    match tokio::fs::remove_file(profile_path(&root, &name)).await {
+        Ok(()) => {
+            info!("Deleted profile {}", name);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => not_found(&name),
+        Err(e) => {
+            warn!("Could not delete profile {}: {}", name, e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "No se pudo borrar el perfil").into_response()
+        }
+    }
+}
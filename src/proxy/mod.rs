@@ -0,0 +1,365 @@
+use std::{
+    io,
+    path::PathBuf,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, Query, State},
+    http::header::{CACHE_CONTROL, CONTENT_LENGTH, CONTENT_TYPE, ETAG, LOCATION},
+    response::{IntoResponse, Response},
+};
+use futures_util::stream;
+use reqwest::{StatusCode, Url};
+use ring::digest::{Context, SHA1_FOR_LEGACY_USE_ONLY, SHA256, SHA512};
+use serde::Deserialize;
+use tokio::{
+    fs::{self, File},
+    io::{AsyncReadExt, AsyncWriteExt},
+};
+use tracing::warn;
+
+use crate::cache;
+use crate::metrics;
+use crate::state::AppState;
+use crate::types::HashAlgorithm;
+
+pub mod store;
+
+// El contenido queda fijado por el checksum, así que puede cachearse para siempre.
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+const READ_CHUNK: usize = 64 * 1024;
+// Las mismas que sigue reqwest por defecto.
+const MAX_REDIRECTS: usize = 10;
+
+static TMP_SEQ: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Deserialize)]
+pub struct ProxyQuery {
+    pub url: String,
+}
+
+/// Descarga `url` verificando al vuelo que su checksum sea `hash` (sha1,
+/// sha256 o sha512, según la longitud). Si no coincide,
+/// la transferencia se corta con error en lugar de terminar normalmente.
+pub async fn proxy_artifact(
+    State(state): State<AppState>,
+    Path(hash): Path<String>,
+    Query(query): Query<ProxyQuery>,
+) -> Response {
+    let hash = hash.to_ascii_lowercase();
+    let Some(algorithm) = HashAlgorithm::for_hex(&hash) else {
+        return (StatusCode::BAD_REQUEST, "Checksum inválido: se esperaba un sha1, sha256 o sha512 en hexadecimal")
+            .into_response();
+    };
+
+    let settings = state.settings();
+    let settings = &settings.proxy;
+    let url = match Url::parse(&query.url) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => url,
+        _ => return (StatusCode::BAD_REQUEST, "URL inválida").into_response(),
+    };
+    if !host_allowed(&url, &settings.allowed_hosts) {
+        return (StatusCode::FORBIDDEN, "Host no permitido").into_response();
+    }
+
+    let cached = store::path(&hash);
+    if settings.cache_artifacts
+        && let Ok(file) = File::open(&cached).await
+    {
+        let len = file.metadata().await.ok().map(|m| m.len());
+        store::touch(cached).await;
+        if !settings.verify_on_read {
+            metrics::increment_counter(metrics::PROXY_DOWNLOADS, &[("source", "disk"), ("result", "ok")]);
+            return artifact_response(&hash, len, Body::from_stream(read_file(file)));
+        }
+        let check = DiskCheck {
+            file,
+            expected_len: len,
+            received: 0,
+            digest: Context::new(digest_algorithm(algorithm)),
+            expected: hash.clone(),
+        };
+        return artifact_response(&hash, len, Body::from_stream(read_verified(check)));
+    }
+
+    let timeout = Duration::from_secs(settings.download_timeout_secs);
+    let (url, response) = match fetch(&state, url, timeout, &settings.allowed_hosts).await {
+        Ok(fetched) => fetched,
+        Err(err) => {
+            metrics::increment_counter(metrics::PROXY_DOWNLOADS, &[("source", "upstream"), ("result", "error")]);
+            return err.into_response();
+        }
+    };
+
+    let len = response.content_length();
+    let spool = if settings.cache_artifacts { Spool::create(cached, settings.max_cache_bytes, timeout).await } else { None };
+    let verifier = Verifier {
+        response,
+        expected_len: len,
+        received: 0,
+        digest: Context::new(digest_algorithm(algorithm)),
+        expected: hash.clone(),
+        url: url.to_string(),
+        spool,
+    };
+
+    artifact_response(&hash, len, Body::from_stream(verify(verifier)))
+}
+
+fn host_allowed(url: &Url, allowed_hosts: &[String]) -> bool {
+    matches!(url.scheme(), "http" | "https")
+        && url.host_str().is_some_and(|host| allowed_hosts.iter().any(|h| h.eq_ignore_ascii_case(host)))
+}
+
+/// Descarga `url` siguiendo las redirecciones a mano: cada salto tiene que ir
+/// a un host de `allowed_hosts`, o el proxy serviría para llegar a cualquiera.
+async fn fetch(
+    state: &AppState,
+    mut url: Url,
+    timeout: Duration,
+    allowed_hosts: &[String],
+) -> Result<(Url, reqwest::Response), (StatusCode, &'static str)> {
+    for _ in 0..=MAX_REDIRECTS {
+        let fetched = state
+            .upstream
+            .execute_without_redirects(url.as_str(), |client| {
+                let url = url.clone();
+                async move { client.get(url).timeout(timeout).send().await?.error_for_status() }
+            })
+            .await;
+        let response = match fetched {
+            Ok(response) => response,
+            Err(e) => {
+                warn!("Artifact download from {} failed: {}", url, e);
+                return Err((StatusCode::BAD_GATEWAY, "Error descargando el artefacto"));
+            }
+        };
+        if !response.status().is_redirection() {
+            return Ok((url, response));
+        }
+
+        let location = response.headers().get(LOCATION).and_then(|l| l.to_str().ok());
+        match location.and_then(|l| url.join(l).ok()) {
+            Some(next) if host_allowed(&next, allowed_hosts) => url = next,
+            Some(next) => {
+                warn!("Artifact download from {} redirected to disallowed {}", url, next);
+                return Err((StatusCode::FORBIDDEN, "Redirección a un host no permitido"));
+            }
+            None => {
+                warn!("Artifact download from {} redirected without a valid Location", url);
+                return Err((StatusCode::BAD_GATEWAY, "Error descargando el artefacto"));
+            }
+        }
+    }
+    warn!("Artifact download from {} exceeded {} redirects", url, MAX_REDIRECTS);
+    Err((StatusCode::BAD_GATEWAY, "Demasiadas redirecciones"))
+}
+
+fn digest_algorithm(algorithm: HashAlgorithm) -> &'static ring::digest::Algorithm {
+    match algorithm {
+        HashAlgorithm::Sha1 => &SHA1_FOR_LEGACY_USE_ONLY,
+        HashAlgorithm::Sha256 => &SHA256,
+        HashAlgorithm::Sha512 => &SHA512,
+    }
+}
+
+fn artifact_response(hash: &str, len: Option<u64>, body: Body) -> Response {
+    let mut response = (
+        [
+            (CONTENT_TYPE, "application/octet-stream".to_string()),
+            (ETAG, format!("\"{}\"", hash)),
+            (CACHE_CONTROL, IMMUTABLE.to_string()),
+        ],
+        body,
+    )
+        .into_response();
+
+    if let Some(len) = len {
+        response.headers_mut().insert(CONTENT_LENGTH, len.into());
+    }
+    response
+}
+
+struct Verifier {
+    response: reqwest::Response,
+    expected_len: Option<u64>,
+    received: u64,
+    digest: Context,
+    expected: String,
+    url: String,
+    spool: Option<Spool>,
+}
+
+fn verify(verifier: Verifier) -> impl futures_util::Stream<Item = io::Result<Bytes>> {
+    stream::unfold(Some(verifier), |verifier| async move {
+        let mut v = verifier?;
+
+        match v.response.chunk().await {
+            Ok(Some(chunk)) => {
+                v.digest.update(&chunk);
+                if let Some(spool) = &mut v.spool
+                    && let Err(e) = spool.file.write_all(&chunk).await
+                {
+                    warn!("Could not spool artifact {} to disk: {}", v.expected, e);
+                    v.spool = None;
+                }
+                v.received += chunk.len() as u64;
+
+                // Con Content-Length, hyper deja de leer el cuerpo al llegar a
+                // esa longitud: hay que verificar antes de entregar el último
+                // trozo, o el cliente recibiría la descarga completa sin más.
+                if v.expected_len.is_some_and(|len| v.received >= len) {
+                    return match v.finish().await {
+                        Ok(()) => Some((Ok(chunk), None)),
+                        Err(e) => Some((Err(e), None)),
+                    };
+                }
+                Some((Ok(chunk), Some(v)))
+            }
+            Ok(None) => v.finish().await.err().map(|e| (Err(e), None)),
+            Err(e) => {
+                metrics::increment_counter(metrics::PROXY_DOWNLOADS, &[("source", "upstream"), ("result", "error")]);
+                Some((Err(io::Error::other(e)), None))
+            }
+        }
+    })
+}
+
+fn hex_digest(digest: Context) -> String {
+    digest.finish().as_ref().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl Verifier {
+    async fn finish(mut self) -> io::Result<()> {
+        let actual = hex_digest(self.digest);
+        if actual == self.expected {
+            metrics::increment_counter(metrics::PROXY_DOWNLOADS, &[("source", "upstream"), ("result", "ok")]);
+            if let Some(spool) = self.spool.take() {
+                spool.persist().await;
+            }
+            return Ok(());
+        }
+
+        warn!("Checksum mismatch for {}: expected {}, got {}", self.url, self.expected, actual);
+        metrics::increment_counter(metrics::PROXY_DOWNLOADS, &[("source", "upstream"), ("result", "mismatch")]);
+        Err(io::Error::other("checksum mismatch"))
+    }
+}
+
+fn read_file(file: File) -> impl futures_util::Stream<Item = io::Result<Vec<u8>>> {
+    stream::unfold(Some(file), |file| async move {
+        let mut file = file?;
+        let mut buf = Vec::with_capacity(READ_CHUNK);
+        match file.read_buf(&mut buf).await {
+            Ok(0) => None,
+            Ok(_) => Some((Ok(buf), Some(file))),
+            Err(e) => Some((Err(e), None)),
+        }
+    })
+}
+
+/// Lectura desde disco que vuelve a calcular el checksum. Si el archivo se ha
+/// corrompido, se borra y la transferencia se corta antes del último trozo,
+/// igual que con una descarga de upstream; el siguiente intento la repite.
+struct DiskCheck {
+    file: File,
+    expected_len: Option<u64>,
+    received: u64,
+    digest: Context,
+    expected: String,
+}
+
+fn read_verified(check: DiskCheck) -> impl futures_util::Stream<Item = io::Result<Vec<u8>>> {
+    stream::unfold(Some(check), |check| async move {
+        let mut c = check?;
+        let mut buf = Vec::with_capacity(READ_CHUNK);
+        match c.file.read_buf(&mut buf).await {
+            Ok(0) => c.finish().await.err().map(|e| (Err(e), None)),
+            Ok(n) => {
+                c.digest.update(&buf);
+                c.received += n as u64;
+                if c.expected_len.is_some_and(|len| c.received >= len) {
+                    return Some((c.finish().await.map(|()| buf), None));
+                }
+                Some((Ok(buf), Some(c)))
+            }
+            Err(e) => Some((Err(e), None)),
+        }
+    })
+}
+
+impl DiskCheck {
+    async fn finish(self) -> io::Result<()> {
+        if hex_digest(self.digest) == self.expected {
+            metrics::increment_counter(metrics::PROXY_DOWNLOADS, &[("source", "disk"), ("result", "ok")]);
+            return Ok(());
+        }
+        metrics::increment_counter(metrics::PROXY_DOWNLOADS, &[("source", "disk"), ("result", "corrupt")]);
+        store::remove_corrupt(&self.expected).await;
+        Err(io::Error::other("checksum mismatch"))
+    }
+}
+
+/// Archivo temporal donde se va copiando la descarga; solo se mueve a su
+/// ruta definitiva cuando el checksum coincide. Si se suelta antes (checksum
+/// distinto, error o el cliente cortó la descarga), se borra.
+struct Spool {
+    file: File,
+    tmp: PathBuf,
+    dest: PathBuf,
+    max_bytes: Option<u64>,
+    spool_max_age: Duration,
+    persisted: bool,
+}
+
+impl Spool {
+    async fn create(dest: PathBuf, max_bytes: Option<u64>, spool_max_age: Duration) -> Option<Self> {
+        let seq = TMP_SEQ.fetch_add(1, Ordering::Relaxed);
+        let tmp = dest.with_extension(format!("{}.{}.tmp", std::process::id(), seq));
+        let result = async {
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            File::create(&tmp).await
+        }
+        .await;
+
+        match result {
+            Ok(file) => Some(Self { file, tmp, dest, max_bytes, spool_max_age, persisted: false }),
+            Err(e) => {
+                warn!("Could not create artifact spool {:?}: {}", tmp, e);
+                None
+            }
+        }
+    }
+
+    async fn persist(mut self) {
+        let result = async {
+            self.file.flush().await?;
+            fs::rename(&self.tmp, &self.dest).await
+        }
+        .await;
+        match result {
+            Ok(()) => {
+                self.persisted = true;
+                cache::mark_refreshed("artifact");
+                store::collect_soon(self.max_bytes, self.spool_max_age);
+            }
+            Err(e) => warn!("Could not store artifact {:?}: {}", self.dest, e),
+        }
+    }
+}
+
+impl Drop for Spool {
+    fn drop(&mut self) {
+        if !self.persisted
+            && let Err(e) = std::fs::remove_file(&self.tmp)
+            && e.kind() != io::ErrorKind::NotFound
+        {
+            warn!("Could not remove artifact spool {:?}: {}", self.tmp, e);
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Artefactos verificados en disco, direccionados por su checksum. La fecha de
+//! modificación hace de último uso: se actualiza al servir un artefacto y el
+//! recolector borra primero los más antiguos hasta quedar bajo
+//! `proxy.max_cache_bytes`.
+
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+    time::{Duration, SystemTime},
+};
+
+use axum::{extract::State, Json};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::cache::disk::{self, unix_now};
+use crate::metrics;
+use crate::state::AppState;
+
+static COLLECTING: AtomicBool = AtomicBool::new(false);
+static EVICTED_BYTES: AtomicU64 = AtomicU64::new(0);
+static LAST_GC: AtomicU64 = AtomicU64::new(0);
+
+pub fn path(hash: &str) -> PathBuf {
+    disk::artifact_path(hash)
+}
+
+/// Marca el artefacto como recién usado. Si falla, solo se pierde precisión
+/// en el orden de borrado.
+pub async fn touch(path: PathBuf) {
+    let result = tokio::task::spawn_blocking(move || {
+        std::fs::File::options().append(true).open(&path)?.set_modified(SystemTime::now())
+    })
+    .await;
+    if let Ok(Err(e)) = result {
+        warn!("Could not update artifact access time: {}", e);
+    }
+}
+
+/// Borra un artefacto cuyo contenido ya no coincide con su checksum.
+pub async fn remove_corrupt(hash: &str) {
+    warn!("Cached artifact {} failed verification, removing it", hash);
+    if let Err(e) = tokio::fs::remove_file(path(hash)).await
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        warn!("Could not remove corrupt artifact {}: {}", hash, e);
+    }
+}
+
+/// Lanza una recolección en segundo plano si hay límite y no hay otra en curso.
+pub fn collect_soon(max_bytes: Option<u64>, spool_max_age: Duration) {
+    let Some(max_bytes) = max_bytes else {
+        return;
+    };
+    if COLLECTING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    tokio::spawn(async move {
+        collect_garbage(max_bytes, spool_max_age).await;
+        COLLECTING.store(false, Ordering::Release);
+    });
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Collected {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// Borra los artefactos usados hace más tiempo hasta que el total no pase de
+/// `max_bytes`. Un artefacto más grande que el límite tampoco se conserva.
+/// Antes, barre los temporales abandonados (ver [`sweep_spools`]).
+pub async fn collect_garbage(max_bytes: u64, spool_max_age: Duration) -> Collected {
+    sweep_spools(spool_max_age).await;
+    let mut files = disk::artifact_files().await;
+    let mut total: u64 = files.iter().map(|f| f.bytes).sum();
+    let mut collected = Collected::default();
+    LAST_GC.store(unix_now(), Ordering::Relaxed);
+    if total <= max_bytes {
+        return collected;
+    }
+
+    files.sort_by_key(|f| f.modified);
+    for file in files {
+        if total <= max_bytes {
+            break;
+        }
+        match tokio::fs::remove_file(&file.path).await {
+            Ok(()) => {
+                total -= file.bytes;
+                collected.files += 1;
+                collected.bytes += file.bytes;
+                metrics::increment_counter(metrics::ARTIFACT_EVICTIONS, &[]);
+            }
+            // Otra recolección o una purga se adelantó.
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => total -= file.bytes,
+            Err(e) => warn!("Could not evict artifact {:?}: {}", file.path, e),
+        }
+    }
+
+    EVICTED_BYTES.fetch_add(collected.bytes, Ordering::Relaxed);
+    info!("Evicted {} cached artifacts ({} bytes)", collected.files, collected.bytes);
+    collected
+}
+
+/// Borra los temporales de descarga sin tocar desde hace más de `max_age`.
+/// Una descarga no dura más que `proxy.download_timeout_secs`, así que son
+/// restos de un proceso que murió a medias.
+pub async fn sweep_spools(max_age: Duration) -> Collected {
+    let mut swept = Collected::default();
+    for file in disk::artifact_spools().await {
+        if file.modified.elapsed().unwrap_or_default() <= max_age {
+            continue;
+        }
+        match tokio::fs::remove_file(&file.path).await {
+            Ok(()) => {
+                swept.files += 1;
+                swept.bytes += file.bytes;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Could not remove stale artifact spool {:?}: {}", file.path, e),
+        }
+    }
+    if swept.files > 0 {
+        info!("Removed {} stale artifact spools ({} bytes)", swept.files, swept.bytes);
+    }
+    swept
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArtifactCacheStats {
+    pub files: usize,
+    pub bytes: u64,
+    pub max_bytes: Option<u64>,
+    /// `bytes / max_bytes`; `null` sin límite.
+    pub usage_ratio: Option<f64>,
+    pub verify_on_read: bool,
+    /// Artefactos servidos desde disco y descargados de upstream.
+    pub hits: u64,
+    pub misses: u64,
+    /// Descargas de upstream que fallaron o no cuadraron con el checksum;
+    /// no cuentan como fallos de caché.
+    pub errors: u64,
+    /// Lecturas de disco que no pasaron la verificación del checksum.
+    pub corrupt: u64,
+    pub evicted_files: u64,
+    pub evicted_bytes: u64,
+    pub oldest_use_age_secs: Option<u64>,
+    pub newest_use_age_secs: Option<u64>,
+    pub last_gc_unix: Option<u64>,
+}
+
+/// `GET /cache/artifacts/stats`.
+pub async fn artifact_stats(State(state): State<AppState>) -> Json<ArtifactCacheStats> {
+    let settings = &state.settings().proxy;
+    let on_disk = disk::artifact_stats().await;
+
+    let counters = metrics::snapshot().counters;
+    let count = |name: &str, labels: &[(&str, &str)]| -> u64 {
+        counters
+            .iter()
+            .filter(|s| s.name == name && labels.iter().all(|(k, v)| s.labels.iter().any(|(lk, lv)| lk == k && lv == v)))
+            .map(|s| s.value)
+            .sum()
+    };
+
+    let last_gc = LAST_GC.load(Ordering::Relaxed);
+    Json(ArtifactCacheStats {
+        files: on_disk.files,
+        bytes: on_disk.bytes,
+        max_bytes: settings.max_cache_bytes,
+        usage_ratio: settings.max_cache_bytes.filter(|max| *max > 0).map(|max| on_disk.bytes as f64 / max as f64),
+        verify_on_read: settings.verify_on_read,
+        hits: count(metrics::PROXY_DOWNLOADS, &[("source", "disk"), ("result", "ok")]),
+        misses: count(metrics::PROXY_DOWNLOADS, &[("source", "upstream"), ("result", "ok")]),
+        errors: count(metrics::PROXY_DOWNLOADS, &[("source", "upstream"), ("result", "error")])
+            + count(metrics::PROXY_DOWNLOADS, &[("source", "upstream"), ("result", "mismatch")]),
+        corrupt: count(metrics::PROXY_DOWNLOADS, &[("source", "disk"), ("result", "corrupt")]),
+        evicted_files: count(metrics::ARTIFACT_EVICTIONS, &[]),
+        evicted_bytes: EVICTED_BYTES.load(Ordering::Relaxed),
+        oldest_use_age_secs: on_disk.oldest.map(|d| d.as_secs()),
+        newest_use_age_secs: on_disk.newest.map(|d| d.as_secs()),
+        last_gc_unix: (last_gc > 0).then_some(last_gc),
+    })
+}
@@ -0,0 +1,133 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header::RETRY_AFTER, HeaderMap},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use once_cell::sync::Lazy;
+use reqwest::StatusCode;
+
+use crate::config::{RateLimit, RateLimitSettings};
+use crate::metrics;
+use crate::state::AppState;
+
+// A partir de este número de buckets se descartan los que llevan un rato sin
+// usarse (a esas alturas ya se habrían rellenado por completo).
+const PRUNE_THRESHOLD: usize = 10_000;
+const IDLE_EXPIRY: Duration = Duration::from_secs(600);
+
+static BUCKETS: Lazy<Mutex<HashMap<(&'static str, IpAddr), Bucket>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Limita las peticiones por IP de cliente con un token bucket por grupo de
+/// rutas; al agotarse responde 429 con `Retry-After`.
+pub async fn rate_limit(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let settings = state.settings();
+    let config = &settings.rate_limit;
+    let Some(group) = route_group(request.uri().path()) else {
+        return next.run(request).await;
+    };
+    if !config.enabled {
+        return next.run(request).await;
+    }
+
+    // Sin IP conocida (p. ej. en tests con `oneshot`) no hay a quién limitar.
+    let Some(ip) = client_ip(&request, config) else {
+        return next.run(request).await;
+    };
+
+    match take((group, ip), limit_for(config, group)) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            metrics::increment_counter(metrics::RATE_LIMITED, &[("group", group)]);
+            let secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(RETRY_AFTER, secs.to_string())],
+                "Demasiadas peticiones, inténtalo más tarde",
+            )
+                .into_response()
+        }
+    }
+}
+
+fn route_group(path: &str) -> Option<&'static str> {
+    match path {
+        "/healthz" | "/readyz" => None,
+        p if p.starts_with("/admin") || p.starts_with("/cache") => Some("admin"),
+        p if p.starts_with("/proxy/") => Some("proxy"),
+        _ => Some("api"),
+    }
+}
+
+fn limit_for(config: &RateLimitSettings, group: &str) -> RateLimit {
+    match group {
+        "admin" => config.admin,
+        "proxy" => config.proxy,
+        _ => config.api,
+    }
+}
+
+fn client_ip(request: &Request, config: &RateLimitSettings) -> Option<IpAddr> {
+    if config.trust_forwarded_for
+        && let Some(ip) = forwarded_for(request.headers(), config.trusted_proxy_hops)
+    {
+        return Some(ip);
+    }
+
+    request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+}
+
+// Los proxies añaden al final de X-Forwarded-For, así que solo las `hops`
+// últimas entradas son de fiar; la de más a la izquierda de esas es el
+// cliente. Lo que haya antes lo envió el propio cliente.
+fn forwarded_for(headers: &HeaderMap, hops: usize) -> Option<IpAddr> {
+    let entries: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect();
+    let index = entries.len().checked_sub(hops.max(1))?;
+    entries[index].parse().ok()
+}
+
+/// Consume un token; si no hay, devuelve cuánto falta para el siguiente.
+fn take(key: (&'static str, IpAddr), limit: RateLimit) -> Result<(), Duration> {
+    let Ok(mut buckets) = BUCKETS.lock() else {
+        return Ok(());
+    };
+    let now = Instant::now();
+    let burst = f64::from(limit.burst.max(1));
+    let rate = limit.per_sec.max(f64::EPSILON);
+
+    if buckets.len() >= PRUNE_THRESHOLD {
+        buckets.retain(|_, b| now.duration_since(b.updated) < IDLE_EXPIRY);
+    }
+
+    let bucket = buckets.entry(key).or_insert(Bucket { tokens: burst, updated: now });
+    bucket.tokens = (bucket.tokens + now.duration_since(bucket.updated).as_secs_f64() * rate).min(burst);
+    bucket.updated = now;
+
+    if bucket.tokens >= 1.0 {
+        bucket.tokens -= 1.0;
+        Ok(())
+    } else {
+        Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+    }
+}
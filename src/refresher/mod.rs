@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+use tracing::{info, warn};
+
+use crate::cache::{get_json, store_manifest, MANIFEST_KEY};
+use crate::config::RefresherSettings;
+use crate::manifest::{fetch_version_manifest, warm_version};
+use crate::state::AppState;
+use crate::types::VersionManifest;
+
+/// Lanza la tarea que refresca el manifest y precarga versiones populares.
+pub fn spawn(state: AppState) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let interval = Duration::from_secs(state.settings().refresher.interval_secs.max(1));
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        // Un refresco en curso se deja terminar; solo se corta la espera.
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => refresh_once(&state, interval).await,
+                _ = state.shutdown.cancelled() => break,
+            }
+        }
+        info!("Refresher stopped");
+    })
+}
+
+pub async fn refresh_once(state: &AppState, interval: Duration) {
+    let settings = state.settings();
+    let config = &settings.refresher;
+    let manifest = match fetch_version_manifest(state).await {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            warn!("Manifest refresh failed: {}", e);
+            return;
+        }
+    };
+    // La copia anterior (rehidratada de disco al arrancar) sirve de referencia
+    // para no anunciar todo el manifest como nuevo en el primer refresco.
+    if let Some((previous, _, _)) = get_json::<VersionManifest>(MANIFEST_KEY).await {
+        state.events.seed(&previous);
+    }
+    store_manifest(&manifest).await;
+    let announced = state.events.observe(&manifest);
+
+    let mut warmed = 0;
+    for id in versions_to_warm(&manifest, config) {
+        match warm_version(state, &manifest, &id, interval).await {
+            Ok(true) => warmed += 1,
+            Ok(false) => {}
+            Err(e) => warn!("Warm-up of version {} failed: {}", id, e),
+        }
+    }
+
+    info!(
+        "Manifest refreshed ({} versions, {} events), {} versions warmed",
+        manifest.versions.len(),
+        announced,
+        warmed
+    );
+}
+
+fn versions_to_warm(manifest: &VersionManifest, config: &RefresherSettings) -> Vec<String> {
+    let mut ids: Vec<String> = vec![];
+
+    let aliased = config.warm_versions.iter().map(|id| match id.as_str() {
+        "latest-release" => manifest.latest_release.clone(),
+        "latest-snapshot" => manifest.latest_snapshot.clone(),
+        _ => id.clone(),
+    });
+
+    // El manifest de Mojang viene ordenado de más nueva a más antigua.
+    let recent_releases = manifest
+        .versions
+        .iter()
+        .filter(|v| v.version_type == "release")
+        .take(config.top_releases)
+        .map(|v| v.id.clone());
+
+    for id in aliased.chain(recent_releases) {
+        if !id.is_empty() && !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+
+    ids
+}
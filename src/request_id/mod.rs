@@ -0,0 +1,94 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Instant,
+};
+
+use axum::{
+    extract::{MatchedPath, Request},
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use once_cell::sync::Lazy;
+use ring::rand::{SecureRandom, SystemRandom};
+use tracing::{field, info, info_span, warn, Instrument, Span};
+
+pub static REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+static RNG: Lazy<SystemRandom> = Lazy::new(SystemRandom::new);
+static FALLBACK: AtomicU64 = AtomicU64::new(0);
+
+// Ids más largos que esto (o con caracteres raros) se sustituyen por uno propio.
+const MAX_LEN: usize = 128;
+
+/// Id de la petición en curso, disponible como extensión para los handlers.
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+/// Asigna un `x-request-id` (el del cliente si es válido), abre un span con
+/// él para todo lo que se registre durante la petición y lo devuelve en la
+/// respuesta. Al terminar registra método, ruta, estado y duración.
+pub async fn trace_request(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|id| is_valid(id))
+        .map(String::from)
+        .unwrap_or_else(generate);
+
+    let method = request.method().clone();
+    let uri = request.uri().path().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    request.extensions_mut().insert(RequestId(id.clone()));
+
+    let traceparent = request.headers().get("traceparent").and_then(|v| v.to_str().ok()).map(String::from);
+    let span = info_span!(
+        "request",
+        id = %id,
+        method = %method,
+        route = %route,
+        status = field::Empty,
+        traceparent = field::Empty,
+    );
+    if let Some(traceparent) = traceparent {
+        span.record("traceparent", traceparent.as_str());
+    }
+    async move {
+        let started = Instant::now();
+        let mut response = next.run(request).await;
+        let elapsed_ms = started.elapsed().as_secs_f64() * 1000.0;
+        let status = response.status().as_u16();
+        Span::current().record("status", status);
+
+        if response.status().is_server_error() {
+            warn!(status, elapsed_ms, path = %uri, "request failed");
+        } else {
+            info!(status, elapsed_ms, path = %uri, "request completed");
+        }
+
+        if let Ok(value) = HeaderValue::from_str(&id) {
+            response.headers_mut().insert(REQUEST_ID_HEADER.clone(), value);
+        }
+        response
+    }
+    .instrument(span)
+    .await
+}
+
+fn is_valid(id: &str) -> bool {
+    !id.is_empty() && id.len() <= MAX_LEN && id.bytes().all(|b| b.is_ascii_graphic())
+}
+
+fn generate() -> String {
+    let mut bytes = [0u8; 8];
+    if RNG.fill(&mut bytes).is_err() {
+        // Sin aleatoriedad, al menos que no se repita dentro del proceso.
+        bytes = FALLBACK.fetch_add(1, Ordering::Relaxed).to_be_bytes();
+    }
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,174 @@
+//! Versiones del esquema de las respuestas con versiones normalizadas, por
+//! tipo de medio: `Accept: application/vnd.manifestor.v2+json`. Sin tipo
+//! propio se sirve v1, el formato original, para no romper a los launchers
+//! que ya existen. Internamente siempre se trabaja con `NormalizedVersion`;
+//! cada versión del esquema es una transformación del JSON al responder.
+//!
+//! Cambios de v2 respecto a v1:
+//! - `client_jar` y `server_jar` pasan a `downloads.client` y `downloads.server`.
+//! - Cada native lleva `extract`; desaparece la lista `requires_extraction`.
+
+use axum::{
+    extract::FromRequestParts,
+    http::{
+        header::{ACCEPT, CONTENT_TYPE, ETAG, VARY},
+        request::Parts,
+        HeaderValue,
+    },
+    response::{IntoResponse, Response},
+    Json,
+};
+use reqwest::StatusCode;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::cache;
+
+const MEDIA_PREFIX: &str = "application/vnd.manifestor.v";
+const MEDIA_SUFFIX: &str = "+json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SchemaVersion {
+    V1 = 1,
+    V2 = 2,
+}
+
+impl SchemaVersion {
+    pub const LATEST: SchemaVersion = SchemaVersion::V2;
+
+    fn from_number(n: u32) -> Option<Self> {
+        match n {
+            1 => Some(SchemaVersion::V1),
+            2 => Some(SchemaVersion::V2),
+            _ => None,
+        }
+    }
+
+    pub fn media_type(self) -> &'static str {
+        match self {
+            SchemaVersion::V1 => "application/vnd.manifestor.v1+json",
+            SchemaVersion::V2 => "application/vnd.manifestor.v2+json",
+        }
+    }
+}
+
+/// Esquema pedido. `explicit` indica si el cliente lo pidió con el tipo de
+/// medio; si no, se responde `application/json` como siempre.
+#[derive(Debug, Clone, Copy)]
+pub struct Schema {
+    pub version: SchemaVersion,
+    explicit: bool,
+}
+
+impl Default for Schema {
+    fn default() -> Self {
+        Self { version: SchemaVersion::V1, explicit: false }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Schema {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let accept = parts.headers.get_all(ACCEPT).iter().filter_map(|v| v.to_str().ok());
+        negotiate(accept).map_err(IntoResponse::into_response)
+    }
+}
+
+/// Elige, de las versiones pedidas que se sepa servir, la de mayor `q` (y a
+/// igual `q`, la más alta). Las que llevan `q=0` no se aceptan; si solo se
+/// piden versiones desconocidas, 406.
+pub fn negotiate<'a>(accept: impl Iterator<Item = &'a str>) -> Result<Schema, (StatusCode, String)> {
+    let mut requested = vec![];
+    for range in accept.flat_map(|v| v.split(',')) {
+        let mut parts = range.split(';');
+        let media = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+        let Some(number) = media.strip_prefix(MEDIA_PREFIX).and_then(|m| m.strip_suffix(MEDIA_SUFFIX)) else {
+            continue;
+        };
+        let quality = parts
+            .filter_map(|param| param.split_once('='))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("q"))
+            .and_then(|(_, q)| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+        if quality > 0.0 {
+            requested.push((number.parse::<u32>().ok(), quality));
+        }
+    }
+    if requested.is_empty() {
+        return Ok(Schema::default());
+    }
+
+    requested
+        .into_iter()
+        .filter_map(|(number, quality)| Some((SchemaVersion::from_number(number?)?, quality)))
+        .max_by(|(a, qa), (b, qb)| qa.total_cmp(qb).then(a.cmp(b)))
+        .map(|(version, _)| Schema { version, explicit: true })
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_ACCEPTABLE,
+                format!("Versión de esquema no soportada; se admiten v1 a v{}", SchemaVersion::LATEST as u8),
+            )
+        })
+}
+
+impl Schema {
+    /// Una versión normalizada, o algo que la incluye aplanada (un bundle),
+    /// en el esquema pedido.
+    pub fn to_value<T: Serialize>(&self, data: &T) -> Value {
+        let mut value = serde_json::to_value(data).unwrap_or_default();
+        if self.version >= SchemaVersion::V2
+            && let Value::Object(version) = &mut value
+        {
+            to_v2(version);
+        }
+        value
+    }
+
+    /// Respuesta JSON con el tipo de medio del esquema. `etag` es el de la
+    /// representación v1 (el de la caché); en otros esquemas se recalcula.
+    pub fn respond<T: Serialize>(&self, data: &T, etag: Option<String>) -> Response {
+        let value = self.to_value(data);
+        let etag = match etag {
+            Some(etag) if self.version == SchemaVersion::V1 => Some(etag),
+            Some(_) => Some(cache::etag_for_json(&value)),
+            None => None,
+        };
+        let mut response = Json(value).into_response();
+        if let Some(etag) = etag.and_then(|e| HeaderValue::from_str(&e).ok()) {
+            response.headers_mut().insert(ETAG, etag);
+        }
+        self.mark(response)
+    }
+
+    /// Pone el tipo de medio y `Vary: Accept` en una respuesta ya construida.
+    pub fn mark(&self, mut response: Response) -> Response {
+        let headers = response.headers_mut();
+        if self.explicit {
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static(self.version.media_type()));
+        }
+        headers.append(VARY, HeaderValue::from_static("accept"));
+        response
+    }
+}
+
+fn to_v2(version: &mut Map<String, Value>) {
+    let mut downloads = Map::new();
+    for (old, new) in [("client_jar", "client"), ("server_jar", "server")] {
+        downloads.insert(new.to_string(), version.remove(old).unwrap_or(Value::Null));
+    }
+    version.insert("downloads".to_string(), Value::Object(downloads));
+
+    let hints = version.remove("requires_extraction").unwrap_or_default();
+    let extract = |path: &Value| {
+        hints.as_array().into_iter().flatten().any(|hint| {
+            hint.get("path") == Some(path) && hint.get("requires_extraction").and_then(Value::as_bool) == Some(true)
+        })
+    };
+    if let Some(Value::Array(natives)) = version.get_mut("natives") {
+        for native in natives.iter_mut().filter_map(Value::as_object_mut) {
+            let flag = native.get("path").is_some_and(extract);
+            native.insert("extract".to_string(), Value::Bool(flag));
+        }
+    }
+}
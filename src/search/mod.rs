@@ -0,0 +1,104 @@
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::cache::get_cached_manifest;
+use crate::manifest::fetch_version_manifest;
+use crate::state::AppState;
+use crate::types::MinecraftVersion;
+
+const DEFAULT_LIMIT: usize = 20;
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    #[serde(rename = "type")]
+    pub version_type: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchKind {
+    Exact,
+    Prefix,
+    Segment,
+    Contains,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResult {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub version_type: String,
+    pub release_time: String,
+    #[serde(rename = "match")]
+    pub match_kind: MatchKind,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResponse {
+    pub query: String,
+    pub total: usize,
+    pub results: Vec<SearchResult>,
+}
+
+pub async fn search_versions(State(state): State<AppState>, Query(query): Query<SearchQuery>) -> impl IntoResponse {
+    let needle = query.q.trim().to_lowercase();
+    if needle.is_empty() {
+        return (StatusCode::BAD_REQUEST, "El parámetro 'q' no puede estar vacío").into_response();
+    }
+
+    let manifest = get_cached_manifest(move || async move { fetch_version_manifest(&state).await }).await.data;
+    let types: Option<Vec<&str>> = query
+        .version_type
+        .as_deref()
+        .map(|t| t.split(',').map(str::trim).filter(|t| !t.is_empty()).collect());
+
+    let mut matches: Vec<(MatchKind, MinecraftVersion)> = manifest
+        .versions
+        .into_iter()
+        .filter(|v| types.as_ref().is_none_or(|types| types.contains(&v.version_type.as_str())))
+        .filter_map(|v| match_kind(&v.id.to_lowercase(), &needle).map(|kind| (kind, v)))
+        .collect();
+
+    // Mejor coincidencia primero; a igualdad, la versión más reciente.
+    matches.sort_by(|(ka, a), (kb, b)| ka.cmp(kb).then_with(|| b.release_time.cmp(&a.release_time)));
+
+    let total = matches.len();
+    let results = matches
+        .into_iter()
+        .take(query.limit.unwrap_or(DEFAULT_LIMIT))
+        .map(|(match_kind, v)| SearchResult {
+            id: v.id,
+            version_type: v.version_type,
+            release_time: v.release_time,
+            match_kind,
+        })
+        .collect();
+
+    Json(SearchResponse {
+        query: query.q,
+        total,
+        results,
+    })
+    .into_response()
+}
+
+fn match_kind(id: &str, needle: &str) -> Option<MatchKind> {
+    if id == needle {
+        return Some(MatchKind::Exact);
+    }
+    if id.starts_with(needle) {
+        return Some(MatchKind::Prefix);
+    }
+
+    let position = id.find(needle)?;
+    // Empieza tras un separador: "rc" en "1.20-rc1", "pre" en "1.14 pre-release".
+    let boundary = id[..position].ends_with(['.', '-', '_', ' ']);
+    Some(if boundary { MatchKind::Segment } else { MatchKind::Contains })
+}
@@ -0,0 +1,41 @@
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// Espera a Ctrl+C o, en Unix, a SIGTERM.
+pub async fn signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!("Could not listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                warn!("Could not listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+/// Cancela `token` al recibir la señal de apagado.
+pub fn spawn(token: CancellationToken) {
+    tokio::spawn(async move {
+        signal().await;
+        info!("Shutdown signal received, draining connections");
+        token.cancel();
+    });
+}
@@ -0,0 +1,95 @@
+use std::{fmt, future::Future, pin::Pin, sync::Arc};
+
+use manifestor_core::{fetch_version_json, fetch_version_manifest};
+use serde_json::Value;
+
+use crate::types::VersionManifest;
+use crate::upstream::UpstreamClient;
+
+pub type SourceFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+#[derive(Debug)]
+pub enum SourceError {
+    /// La respuesta llegó pero no es el JSON esperado.
+    Decode(String),
+    /// Error de red o estado HTTP de error.
+    Fetch(String),
+}
+
+impl SourceError {
+    pub fn is_decode(&self) -> bool {
+        matches!(self, SourceError::Decode(_))
+    }
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceError::Decode(e) | SourceError::Fetch(e) => f.write_str(e),
+        }
+    }
+}
+
+impl std::error::Error for SourceError {}
+
+impl From<reqwest::Error> for SourceError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_decode() { SourceError::Decode(e.to_string()) } else { SourceError::Fetch(e.to_string()) }
+    }
+}
+
+/// De dónde salen el manifest y los JSON de versión. En producción es
+/// Mojang por HTTP; los tests inyectan JSON de fixtures.
+///
+/// Las URLs son las de la configuración y el manifest; cada fuente decide
+/// cómo interpretarlas.
+pub trait ManifestSource: Send + Sync {
+    fn manifest<'a>(&'a self, url: &'a str) -> SourceFuture<'a, Result<VersionManifest, SourceError>>;
+    fn version<'a>(&'a self, url: &'a str) -> SourceFuture<'a, Result<Value, SourceError>>;
+    /// JSON del servicio de runtimes de Java: el índice de componentes o el
+    /// manifest de archivos de uno de ellos.
+    fn java_runtime<'a>(&'a self, url: &'a str) -> SourceFuture<'a, Result<Value, SourceError>>;
+    /// Asset index de una versión (`assetIndex.url`).
+    fn asset_index<'a>(&'a self, url: &'a str) -> SourceFuture<'a, Result<Value, SourceError>>;
+}
+
+/// Fuente por HTTP, con los reintentos y límites de `UpstreamClient`.
+pub struct HttpSource {
+    upstream: Arc<UpstreamClient>,
+}
+
+impl HttpSource {
+    pub fn new(upstream: Arc<UpstreamClient>) -> Self {
+        Self { upstream }
+    }
+}
+
+impl ManifestSource for HttpSource {
+    fn manifest<'a>(&'a self, url: &'a str) -> SourceFuture<'a, Result<VersionManifest, SourceError>> {
+        Box::pin(async move {
+            let result = self
+                .upstream
+                .execute(url, |client| async move { fetch_version_manifest(&client, url).await })
+                .await;
+            Ok(result?)
+        })
+    }
+
+    fn version<'a>(&'a self, url: &'a str) -> SourceFuture<'a, Result<Value, SourceError>> {
+        Box::pin(async move {
+            let result = self
+                .upstream
+                .execute(url, |client| async move { fetch_version_json(&client, url).await })
+                .await;
+            Ok(result?)
+        })
+    }
+
+    fn java_runtime<'a>(&'a self, url: &'a str) -> SourceFuture<'a, Result<Value, SourceError>> {
+        self.version(url)
+    }
+
+    fn asset_index<'a>(&'a self, url: &'a str) -> SourceFuture<'a, Result<Value, SourceError>> {
+        self.version(url)
+    }
+}
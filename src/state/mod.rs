@@ -0,0 +1,107 @@
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use tokio_util::sync::CancellationToken;
+
+use crate::config::Settings;
+use crate::events::EventBus;
+use crate::manifest::breaker::CircuitBreaker;
+use crate::mirror::Mirror;
+use crate::signing::Signer;
+use crate::source::{HttpSource, ManifestSource};
+use crate::upstream::UpstreamClient;
+
+/// Estado compartido por los handlers de axum y las tareas en segundo plano.
+#[derive(Clone)]
+pub struct AppState {
+    live: Arc<RwLock<Live>>,
+    pub upstream: Arc<UpstreamClient>,
+    /// Origen del manifest y de los JSON de versión.
+    pub source: Arc<dyn ManifestSource>,
+    pub breaker: Arc<CircuitBreaker>,
+    /// La API de descargas de Bedrock es otro servicio: su caída no corta Mojang.
+    pub bedrock_breaker: Arc<CircuitBreaker>,
+    pub events: Arc<EventBus>,
+    /// Solo con `signing.key_file`; se carga al arrancar.
+    pub signer: Option<Arc<Signer>>,
+    /// Se cancela al recibir la señal de apagado.
+    pub shutdown: CancellationToken,
+}
+
+// Lo que se puede recargar en caliente desde `/admin/reload`.
+struct Live {
+    settings: Arc<Settings>,
+    /// Solo presente si hay `mirror.base_url` configurado.
+    mirror: Option<Arc<Mirror>>,
+}
+
+impl Live {
+    fn new(settings: Settings) -> Self {
+        Self {
+            mirror: Mirror::from_settings(&settings.mirror).map(Arc::new),
+            settings: Arc::new(settings),
+        }
+    }
+}
+
+impl AppState {
+    pub fn new(settings: Settings) -> Result<Self, String> {
+        let upstream = Arc::new(UpstreamClient::new(&settings.upstream)?);
+        let source = Arc::new(HttpSource::new(upstream.clone()));
+        Self::build(settings, upstream, source)
+    }
+
+    /// Como `new`, pero con otra fuente para el manifest y las versiones
+    /// (p. ej. fixtures en los tests).
+    pub fn with_source(settings: Settings, source: Arc<dyn ManifestSource>) -> Result<Self, String> {
+        let upstream = Arc::new(UpstreamClient::new(&settings.upstream)?);
+        Self::build(settings, upstream, source)
+    }
+
+    fn build(settings: Settings, upstream: Arc<UpstreamClient>, source: Arc<dyn ManifestSource>) -> Result<Self, String> {
+        let breaker = CircuitBreaker::new(
+            settings.upstream.breaker_failure_threshold,
+            Duration::from_secs(settings.upstream.breaker_open_secs),
+        );
+        let bedrock_breaker = CircuitBreaker::for_target(
+            "bedrock",
+            settings.upstream.breaker_failure_threshold,
+            Duration::from_secs(settings.upstream.breaker_open_secs),
+        );
+        let signer = match &settings.signing.key_file {
+            Some(path) => Some(Arc::new(Signer::load(path)?)),
+            None => None,
+        };
+        Ok(Self {
+            live: Arc::new(RwLock::new(Live::new(settings))),
+            upstream,
+            source,
+            breaker: Arc::new(breaker),
+            bedrock_breaker: Arc::new(bedrock_breaker),
+            events: Arc::new(EventBus::new()),
+            signer,
+            shutdown: CancellationToken::new(),
+        })
+    }
+
+    /// Configuración vigente; una recarga no afecta a quien ya la tiene.
+    pub fn settings(&self) -> Arc<Settings> {
+        self.live.read().map(|live| live.settings.clone()).unwrap_or_else(|e| e.into_inner().settings.clone())
+    }
+
+    pub fn mirror(&self) -> Option<Arc<Mirror>> {
+        self.live.read().map(|live| live.mirror.clone()).unwrap_or_else(|e| e.into_inner().mirror.clone())
+    }
+
+    /// Sustituye la configuración. El cliente upstream, el circuit breaker,
+    /// la clave de firma y la caché se construyen al arrancar y no cambian hasta reiniciar.
+    pub fn replace_settings(&self, settings: Settings) {
+        let live = Live::new(settings);
+        match self.live.write() {
+            Ok(mut guard) => *guard = live,
+            Err(e) => *e.into_inner() = live,
+        }
+    }
+}
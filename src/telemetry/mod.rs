@@ -0,0 +1,40 @@
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::TelemetrySettings;
+
+#[cfg(feature = "otlp")]
+mod otlp;
+
+/// Inicializa los logs y, con la feature `otlp`, la captura de spans.
+pub fn init_tracing() {
+    #[cfg(feature = "otlp")]
+    {
+        use tracing_subscriber::{filter::LevelFilter, layer::SubscriberExt, util::SubscriberInitExt};
+
+        tracing_subscriber::registry()
+            .with(LevelFilter::INFO)
+            .with(tracing_subscriber::fmt::layer())
+            .with(otlp::SpanLayer)
+            .init();
+    }
+
+    #[cfg(not(feature = "otlp"))]
+    tracing_subscriber::fmt::init();
+}
+
+/// Lanza la exportación periódica a `telemetry.otlp_endpoint`, si lo hay.
+/// Al apagar se hace un último envío antes de terminar.
+pub fn spawn(settings: &TelemetrySettings, shutdown: CancellationToken) -> Option<JoinHandle<()>> {
+    settings.otlp_endpoint.as_ref()?;
+
+    #[cfg(feature = "otlp")]
+    return otlp::spawn(settings, shutdown);
+
+    #[cfg(not(feature = "otlp"))]
+    {
+        let _ = shutdown;
+        tracing::warn!("telemetry.otlp_endpoint is set but manifestor was built without the `otlp` feature");
+        None
+    }
+}
@@ -0,0 +1,382 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use once_cell::sync::Lazy;
+use reqwest::Client;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde_json::{json, Value};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    warn, Subscriber,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+
+use crate::config::TelemetrySettings;
+use crate::metrics::{self, Series};
+
+// Spans terminados a la espera del siguiente envío; si el collector no
+// responde se descartan los que no quepan.
+const MAX_PENDING: usize = 4096;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static PENDING: Lazy<Mutex<Vec<Value>>> = Lazy::new(|| Mutex::new(vec![]));
+static RNG: Lazy<SystemRandom> = Lazy::new(SystemRandom::new);
+static STARTED_AT: Lazy<u64> = Lazy::new(unix_nanos);
+
+// Campos de nuestros spans con su nombre en las convenciones de OpenTelemetry.
+const ATTRIBUTE_NAMES: &[(&str, &str)] = &[
+    ("method", "http.request.method"),
+    ("route", "http.route"),
+    ("status", "http.response.status_code"),
+    ("url", "url.full"),
+    ("id", "request.id"),
+];
+
+struct SpanData {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    parent_id: Option<[u8; 8]>,
+    start: u64,
+    attributes: Vec<Value>,
+    error: bool,
+}
+
+/// Capa de `tracing` que convierte los spans de manifestor en spans OTLP.
+pub struct SpanLayer;
+
+impl<S> Layer<S> for SpanLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if !ENABLED.load(Ordering::Relaxed) || !attrs.metadata().target().starts_with("manifestor") {
+            return;
+        }
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let parent = span
+            .parent()
+            .and_then(|parent| parent.extensions().get::<SpanData>().map(|p| (p.trace_id, p.span_id)));
+        let mut data = SpanData {
+            trace_id: parent.map(|(trace_id, _)| trace_id).unwrap_or_else(random_bytes),
+            span_id: random_bytes(),
+            parent_id: parent.map(|(_, span_id)| span_id),
+            start: unix_nanos(),
+            attributes: vec![],
+            error: false,
+        };
+        attrs.record(&mut Visitor(&mut data));
+        span.extensions_mut().insert(data);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id)
+            && let Some(data) = span.extensions_mut().get_mut::<SpanData>()
+        {
+            values.record(&mut Visitor(data));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(data) = span.extensions_mut().remove::<SpanData>() else {
+            return;
+        };
+
+        let kind = match span.name() {
+            "request" => 2, // SERVER
+            "upstream" => 3, // CLIENT
+            _ => 1,          // INTERNAL
+        };
+        let mut otlp = json!({
+            "traceId": hex(&data.trace_id),
+            "spanId": hex(&data.span_id),
+            "name": span.name(),
+            "kind": kind,
+            "startTimeUnixNano": data.start.to_string(),
+            "endTimeUnixNano": unix_nanos().to_string(),
+            "attributes": data.attributes,
+            "status": { "code": if data.error { 2 } else { 0 } },
+        });
+        if let Some(parent_id) = data.parent_id {
+            otlp["parentSpanId"] = json!(hex(&parent_id));
+        }
+
+        if let Ok(mut pending) = PENDING.lock()
+            && pending.len() < MAX_PENDING
+        {
+            pending.push(otlp);
+        }
+    }
+}
+
+struct Visitor<'a>(&'a mut SpanData);
+
+impl Visitor<'_> {
+    fn push(&mut self, field: &Field, value: Value) {
+        let key = ATTRIBUTE_NAMES
+            .iter()
+            .find(|(name, _)| *name == field.name())
+            .map(|(_, otel)| *otel)
+            .unwrap_or(field.name());
+        self.0.attributes.push(json!({ "key": key, "value": value }));
+    }
+}
+
+impl Visit for Visitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "traceparent" {
+            self.adopt_traceparent(value);
+            return;
+        }
+        self.push(field, json!({ "stringValue": value }));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.record_str(field, &format!("{:?}", value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.push(field, json!({ "intValue": value.to_string() }));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "status" && value >= 500 {
+            self.0.error = true;
+        }
+        self.push(field, json!({ "intValue": value.to_string() }));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.push(field, json!({ "doubleValue": value }));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        if field.name() == "ok" && !value {
+            self.0.error = true;
+        }
+        self.push(field, json!({ "boolValue": value }));
+    }
+}
+
+impl Visitor<'_> {
+    // W3C `traceparent` (`00-<trace id>-<span id>-<flags>`) del cliente: el
+    // span pasa a colgar de la traza de quien llama.
+    fn adopt_traceparent(&mut self, value: &str) {
+        let mut parts = value.trim().split('-');
+        let (Some("00"), Some(trace_id), Some(parent_id)) = (parts.next(), parts.next(), parts.next()) else {
+            return;
+        };
+        if let (Some(trace_id), Some(parent_id)) = (unhex::<16>(trace_id), unhex::<8>(parent_id)) {
+            self.0.trace_id = trace_id;
+            self.0.parent_id = Some(parent_id);
+        }
+    }
+}
+
+struct Exporter {
+    client: Client,
+    endpoint: String,
+    headers: Vec<(String, String)>,
+    resource: Value,
+    failing: bool,
+}
+
+pub fn spawn(settings: &TelemetrySettings, shutdown: CancellationToken) -> Option<JoinHandle<()>> {
+    let endpoint = settings.otlp_endpoint.as_deref()?.trim_end_matches('/').to_string();
+    let client = match Client::builder().timeout(Duration::from_secs(10)).build() {
+        Ok(client) => client,
+        Err(e) => {
+            warn!("OTLP exporter disabled: {}", e);
+            return None;
+        }
+    };
+
+    Lazy::force(&STARTED_AT);
+    ENABLED.store(true, Ordering::Relaxed);
+    let mut exporter = Exporter {
+        client,
+        endpoint,
+        headers: settings
+            .headers
+            .iter()
+            .filter_map(|h| h.split_once('='))
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .collect(),
+        resource: json!({
+            "attributes": [
+                { "key": "service.name", "value": { "stringValue": settings.service_name } },
+                { "key": "service.version", "value": { "stringValue": env!("CARGO_PKG_VERSION") } },
+            ],
+        }),
+        failing: false,
+    };
+    let interval = Duration::from_secs(settings.export_interval_secs.max(1));
+    tracing::info!("Exporting traces and metrics to {} every {:?}", exporter.endpoint, interval);
+
+    Some(tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.tick().await;
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => exporter.export().await,
+                _ = shutdown.cancelled() => break,
+            }
+        }
+        exporter.export().await;
+    }))
+}
+
+impl Exporter {
+    async fn export(&mut self) {
+        let spans = PENDING.lock().map(|mut pending| std::mem::take(&mut *pending)).unwrap_or_default();
+        let mut ok = true;
+
+        if !spans.is_empty() {
+            let body = json!({
+                "resourceSpans": [{
+                    "resource": self.resource,
+                    "scopeSpans": [{ "scope": scope(), "spans": spans }],
+                }],
+            });
+            ok &= self.post("/v1/traces", &body).await;
+        }
+
+        let body = json!({
+            "resourceMetrics": [{
+                "resource": self.resource,
+                "scopeMetrics": [{ "scope": scope(), "metrics": metrics_json() }],
+            }],
+        });
+        ok &= self.post("/v1/metrics", &body).await;
+
+        if ok && self.failing {
+            tracing::info!("OTLP export to {} recovered", self.endpoint);
+        }
+        self.failing = !ok;
+    }
+
+    async fn post(&self, path: &str, body: &Value) -> bool {
+        let mut request = self.client.post(format!("{}{}", self.endpoint, path)).json(body);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+
+        let error = match request.send().await {
+            Ok(response) if response.status().is_success() => return true,
+            Ok(response) => format!("HTTP {}", response.status()),
+            Err(e) => e.to_string(),
+        };
+        // Solo al empezar a fallar, no en cada intervalo.
+        if !self.failing {
+            warn!("OTLP export to {}{} failed: {}", self.endpoint, path, error);
+        }
+        false
+    }
+}
+
+fn scope() -> Value {
+    json!({ "name": "manifestor", "version": env!("CARGO_PKG_VERSION") })
+}
+
+// Las métricas del registro propio, como series acumulativas desde el arranque.
+fn metrics_json() -> Vec<Value> {
+    let snapshot = metrics::snapshot();
+    let start = STARTED_AT.to_string();
+    let now = unix_nanos().to_string();
+    let point = |labels: &[(String, String)]| {
+        json!({
+            "attributes": labels
+                .iter()
+                .map(|(k, v)| json!({ "key": k, "value": { "stringValue": v } }))
+                .collect::<Vec<_>>(),
+            "startTimeUnixNano": start,
+            "timeUnixNano": now,
+        })
+    };
+
+    let mut grouped: BTreeMap<&'static str, (&'static str, Vec<Value>)> = BTreeMap::new();
+    for Series { name, labels, value } in &snapshot.counters {
+        let mut p = point(labels);
+        p["asInt"] = json!(value.to_string());
+        grouped.entry(name).or_insert(("sum", vec![])).1.push(p);
+    }
+    for Series { name, labels, value } in &snapshot.gauges {
+        let mut p = point(labels);
+        p["asDouble"] = json!(value);
+        grouped.entry(name).or_insert(("gauge", vec![])).1.push(p);
+    }
+    for Series { name, labels, value } in &snapshot.histograms {
+        // OTLP cuenta por bucket, no acumulado, e incluye el de +Inf.
+        let mut counts: Vec<u64> = value
+            .cumulative
+            .iter()
+            .scan(0, |previous, &total| {
+                let count = total - *previous;
+                *previous = total;
+                Some(count)
+            })
+            .collect();
+        counts.push(value.count - value.cumulative.last().copied().unwrap_or(0));
+
+        let mut p = point(labels);
+        p["count"] = json!(value.count.to_string());
+        p["sum"] = json!(value.sum);
+        p["bucketCounts"] = json!(counts.iter().map(u64::to_string).collect::<Vec<_>>());
+        p["explicitBounds"] = json!(value.bounds);
+        grouped.entry(name).or_insert(("histogram", vec![])).1.push(p);
+    }
+
+    grouped
+        .into_iter()
+        .map(|(name, (kind, points))| {
+            let data = match kind {
+                "sum" => json!({ "aggregationTemporality": 2, "isMonotonic": true, "dataPoints": points }),
+                "histogram" => json!({ "aggregationTemporality": 2, "dataPoints": points }),
+                _ => json!({ "dataPoints": points }),
+            };
+            let mut metric = json!({ "name": name, "description": metrics::help(name).unwrap_or_default() });
+            metric[kind] = data;
+            metric
+        })
+        .collect()
+}
+
+fn unix_nanos() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos() as u64).unwrap_or(0)
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    let _ = RNG.fill(&mut bytes);
+    bytes
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn unhex<const N: usize>(text: &str) -> Option<[u8; N]> {
+    if text.len() != N * 2 {
+        return None;
+    }
+    let mut bytes = [0u8; N];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(text.get(i * 2..i * 2 + 2)?, 16).ok()?;
+    }
+    // Un id todo a cero no es válido en W3C Trace Context.
+    bytes.iter().any(|b| *b != 0).then_some(bytes)
+}
@@ -0,0 +1,230 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use reqwest::{redirect, tls, Certificate, Client, ClientBuilder, NoProxy, Proxy, StatusCode, Url};
+use ring::rand::{SecureRandom, SystemRandom};
+use tokio::sync::{Mutex, Semaphore};
+use tracing::{field, info, info_span, warn, Instrument, Span};
+
+use crate::config::{UpstreamProxySettings, UpstreamSettings, UpstreamTlsSettings};
+
+/// Cliente HTTP compartido para hablar con Mojang: timeouts, reintentos con
+/// backoff exponencial + jitter y un límite de peticiones simultáneas por host.
+pub struct UpstreamClient {
+    client: Client,
+    // Mismo cliente sin seguir redirecciones, para URLs que vienen del usuario.
+    no_redirects: Client,
+    max_retries: u32,
+    base_delay: Duration,
+    max_per_host: usize,
+    hosts: Mutex<HashMap<String, Arc<Semaphore>>>,
+    rng: SystemRandom,
+}
+
+impl UpstreamClient {
+    pub fn new(settings: &UpstreamSettings) -> Result<Self, String> {
+        let proxy = proxy(&settings.proxy)?;
+        let ca_certs = ca_certs(&settings.tls)?;
+        let build = |policy: redirect::Policy| {
+            let builder = Client::builder()
+                .user_agent(concat!("manifestor/", env!("CARGO_PKG_VERSION")))
+                .connect_timeout(Duration::from_secs(settings.connect_timeout_secs))
+                .read_timeout(Duration::from_secs(settings.read_timeout_secs))
+                .timeout(Duration::from_secs(settings.request_timeout_secs))
+                .redirect(policy);
+            let builder = match &proxy {
+                Some(proxy) => builder.proxy(proxy.clone()),
+                None => builder,
+            };
+            with_tls(builder, &settings.tls, &ca_certs)?
+                .build()
+                .map_err(|e| format!("No se pudo crear el cliente HTTP: {}", e))
+        };
+        let client = build(redirect::Policy::default())?;
+        let no_redirects = build(redirect::Policy::none())?;
+
+        if let Some(url) = &settings.proxy.url {
+            info!("Upstream requests go through proxy {}", Url::parse(url).map(|u| redact(&u)).unwrap_or_default());
+        }
+        if let Some(path) = &settings.tls.ca_file {
+            info!("Trusting {} extra CA certificate(s) from {}", ca_certs.len(), path.display());
+        }
+        if settings.tls.insecure_skip_verify {
+            warn!("upstream.tls.insecure_skip_verify is on: upstream certificates are NOT verified");
+        }
+
+        Ok(Self {
+            client,
+            no_redirects,
+            max_retries: settings.max_retries,
+            base_delay: Duration::from_millis(settings.retry_base_delay_ms),
+            max_per_host: settings.max_concurrency_per_host.max(1),
+            hosts: Mutex::new(HashMap::new()),
+            rng: SystemRandom::new(),
+        })
+    }
+
+    /// Cliente subyacente, para peticiones que no deben reintentarse.
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Ejecuta `op` contra `url`, reintentando errores transitorios.
+    pub async fn execute<T, F, Fut>(&self, url: &str, op: F) -> Result<T, reqwest::Error>
+    where
+        F: Fn(Client) -> Fut,
+        Fut: Future<Output = Result<T, reqwest::Error>>,
+    {
+        self.execute_on(&self.client, url, op).await
+    }
+
+    /// Como [`execute`](Self::execute), pero las redirecciones se devuelven
+    /// tal cual: quien llama decide si sigue el `Location`.
+    pub async fn execute_without_redirects<T, F, Fut>(&self, url: &str, op: F) -> Result<T, reqwest::Error>
+    where
+        F: Fn(Client) -> Fut,
+        Fut: Future<Output = Result<T, reqwest::Error>>,
+    {
+        self.execute_on(&self.no_redirects, url, op).await
+    }
+
+    async fn execute_on<T, F, Fut>(&self, client: &Client, url: &str, op: F) -> Result<T, reqwest::Error>
+    where
+        F: Fn(Client) -> Fut,
+        Fut: Future<Output = Result<T, reqwest::Error>>,
+    {
+        let semaphore = self.host_semaphore(url).await;
+        let mut attempt = 0;
+
+        loop {
+            let span = info_span!("upstream", url = %url, attempt, ok = field::Empty);
+            let result = async {
+                let _permit = semaphore.acquire().await.expect("semáforo nunca se cierra");
+                let started = Instant::now();
+                let result = op(client.clone()).await;
+                let elapsed_ms = started.elapsed().as_secs_f64() * 1000.0;
+                Span::current().record("ok", result.is_ok());
+                info!(elapsed_ms, "upstream request finished");
+                result
+            }
+            .instrument(span)
+            .await;
+
+            match result {
+                Err(e) if attempt < self.max_retries && is_retryable(&e) => {
+                    let delay = self.backoff(attempt);
+                    warn!("Upstream request to {} failed ({}), retrying in {:?}", url, e, delay);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn host_semaphore(&self, url: &str) -> Arc<Semaphore> {
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(String::from))
+            .unwrap_or_default();
+
+        let mut hosts = self.hosts.lock().await;
+        hosts
+            .entry(host)
+            .or_insert_with(|| Arc::new(Semaphore::new(self.max_per_host)))
+            .clone()
+    }
+
+    // base * 2^intento, más un jitter aleatorio de hasta el mismo valor.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self.base_delay.saturating_mul(2u32.saturating_pow(attempt));
+        let mut bytes = [0u8; 4];
+        let fraction = match self.rng.fill(&mut bytes) {
+            Ok(()) => u32::from_le_bytes(bytes) as f64 / u32::MAX as f64,
+            Err(_) => 0.5,
+        };
+        exp + exp.mul_f64(fraction)
+    }
+}
+
+// Sin `url` reqwest ya usa las variables HTTPS_PROXY/HTTP_PROXY/NO_PROXY.
+fn proxy(settings: &UpstreamProxySettings) -> Result<Option<Proxy>, String> {
+    let Some(url) = settings.url.as_deref() else {
+        return Ok(None);
+    };
+    let mut proxy = Proxy::all(url).map_err(|e| format!("upstream.proxy.url inválida: {}", e))?;
+    if let Some(username) = &settings.username {
+        proxy = proxy.basic_auth(username, settings.password.as_deref().unwrap_or_default());
+    }
+    let no_proxy = if settings.no_proxy.is_empty() {
+        NoProxy::from_env()
+    } else {
+        NoProxy::from_string(&settings.no_proxy.join(","))
+    };
+    Ok(Some(proxy.no_proxy(no_proxy)))
+}
+
+// Para los logs: sin credenciales.
+fn redact(url: &Url) -> String {
+    let mut url = url.clone();
+    let _ = url.set_username("");
+    let _ = url.set_password(None);
+    url.to_string()
+}
+
+fn ca_certs(settings: &UpstreamTlsSettings) -> Result<Vec<Certificate>, String> {
+    let Some(path) = &settings.ca_file else {
+        if !settings.system_roots {
+            return Err("upstream.tls.system_roots = false requiere upstream.tls.ca_file".to_string());
+        }
+        return Ok(vec![]);
+    };
+    let pem = std::fs::read(path).map_err(|e| format!("No se pudo leer {}: {}", path.display(), e))?;
+    let certs = Certificate::from_pem_bundle(&pem).map_err(|e| format!("{}: {}", path.display(), e))?;
+    if certs.is_empty() {
+        return Err(format!("{}: no hay ningún bloque CERTIFICATE", path.display()));
+    }
+    Ok(certs)
+}
+
+fn with_tls(
+    mut builder: ClientBuilder,
+    settings: &UpstreamTlsSettings,
+    ca_certs: &[Certificate],
+) -> Result<ClientBuilder, String> {
+    for cert in ca_certs {
+        builder = builder.add_root_certificate(cert.clone());
+    }
+    builder = builder.tls_built_in_root_certs(settings.system_roots);
+
+    if let Some(version) = &settings.min_version {
+        let version = match version.trim() {
+            "1.0" => tls::Version::TLS_1_0,
+            "1.1" => tls::Version::TLS_1_1,
+            "1.2" => tls::Version::TLS_1_2,
+            // native-tls no permite fijar 1.3 como mínimo: reqwest fallaría al
+            // crear el cliente con un error poco claro.
+            "1.3" => return Err("upstream.tls.min_version '1.3' no está soportado con native-tls; usa 1.2".to_string()),
+            other => return Err(format!("upstream.tls.min_version '{}': se esperaba 1.0, 1.1 o 1.2", other)),
+        };
+        builder = builder.min_tls_version(version);
+    }
+    if settings.insecure_skip_verify {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    Ok(builder)
+}
+
+fn is_retryable(error: &reqwest::Error) -> bool {
+    if error.is_timeout() || error.is_connect() || error.is_request() {
+        return true;
+    }
+    match error.status() {
+        Some(status) => status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS,
+        None => false,
+    }
+}
@@ -0,0 +1,246 @@
+mod common;
+
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request, StatusCode},
+};
+use common::{app, app_with, json, send, settings, ADMIN_TOKEN};
+use manifestor::{cache, proxy::store};
+use ring::digest::{digest, Algorithm, SHA1_FOR_LEGACY_USE_ONLY, SHA256};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+// Los artefactos se colocan a mano en el directorio de la caché, así que el
+// proxy los sirve sin salir a la red.
+fn hex(algorithm: &'static Algorithm, content: &[u8]) -> String {
+    digest(algorithm, content).as_ref().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn sha1(content: &[u8]) -> String {
+    hex(&SHA1_FOR_LEGACY_USE_ONLY, content)
+}
+
+fn plant(hash: &str, content: &[u8], last_used: SystemTime) -> PathBuf {
+    let path = cache::settings().dir.join("artifacts").join(&hash[..2]).join(hash);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, content).unwrap();
+    std::fs::File::options().append(true).open(&path).unwrap().set_modified(last_used).unwrap();
+    path
+}
+
+fn proxy_uri(hash: &str) -> String {
+    format!("/proxy/{}?url=https://piston-data.mojang.com/v1/objects/{}/client.jar", hash, hash)
+}
+
+async fn artifact_stats(app: &axum::Router) -> serde_json::Value {
+    let request = Request::get("/cache/artifacts/stats")
+        .header(header::AUTHORIZATION, format!("Bearer {}", ADMIN_TOKEN))
+        .body(Body::empty())
+        .unwrap();
+    json(send(app, request).await, StatusCode::OK).await
+}
+
+#[tokio::test]
+async fn cached_artifact_is_verified_and_touched() {
+    let (app, _) = app();
+    let content = b"client jar";
+    let sha1 = sha1(content);
+    let path = plant(&sha1, content, SystemTime::now() - Duration::from_secs(3600));
+
+    let response = send(&app, Request::get(proxy_uri(&sha1)).body(Body::empty()).unwrap()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::ETAG], format!("\"{}\"", sha1).as_str());
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&body[..], content);
+
+    let age = std::fs::metadata(&path).unwrap().modified().unwrap().elapsed().unwrap_or_default();
+    assert!(age < Duration::from_secs(60));
+    assert!(artifact_stats(&app).await["hits"].as_u64().unwrap() >= 1);
+}
+
+#[tokio::test]
+async fn artifacts_can_be_addressed_by_sha256() {
+    let (app, _) = app();
+    let content = b"maven artifact";
+    let sha256 = hex(&SHA256, content);
+    plant(&sha256, content, SystemTime::now());
+
+    let response = send(&app, Request::get(proxy_uri(&sha256)).body(Body::empty()).unwrap()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(&to_bytes(response.into_body(), usize::MAX).await.unwrap()[..], content);
+
+    let short = send(&app, Request::get(proxy_uri(&sha256[..50])).body(Body::empty()).unwrap()).await;
+    assert_eq!(short.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn corrupt_artifact_is_removed() {
+    let (app, _) = app();
+    let sha1 = sha1(b"asset index");
+    let path = plant(&sha1, b"bit rot", SystemTime::now());
+
+    let response = send(&app, Request::get(proxy_uri(&sha1)).body(Body::empty()).unwrap()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(to_bytes(response.into_body(), usize::MAX).await.is_err());
+
+    assert!(!path.exists());
+    assert!(artifact_stats(&app).await["corrupt"].as_u64().unwrap() >= 1);
+}
+
+#[tokio::test]
+async fn garbage_collection_evicts_least_recently_used() {
+    // Más antiguos que lo que colocan los demás tests de este binario.
+    let day = Duration::from_secs(86400);
+    let now = SystemTime::now();
+    let old = plant(&sha1(b"lru old"), &[0; 300], now - day * 3);
+    let recent = plant(&sha1(b"lru recent"), &[0; 300], now - day * 2);
+
+    let collected = store::collect_garbage(store_total() - 300, Duration::from_secs(600)).await;
+
+    assert!(collected.files >= 1);
+    assert!(!old.exists());
+    assert!(recent.exists());
+}
+
+#[tokio::test]
+async fn abandoned_spools_are_swept() {
+    let now = SystemTime::now();
+    let hash = sha1(b"spool");
+    let stale = plant(&format!("{}.1.0.tmp", hash), b"a medias", now - Duration::from_secs(3600));
+    let active = plant(&format!("{}.1.1.tmp", hash), b"en curso", now);
+
+    let swept = store::sweep_spools(Duration::from_secs(600)).await;
+
+    assert!(swept.files >= 1);
+    assert!(!stale.exists());
+    assert!(active.exists());
+}
+
+#[tokio::test]
+async fn spool_is_removed_when_the_client_disconnects() {
+    // Upstream que anuncia 1000 bytes, envía 10 y se queda esperando.
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let _ = stream.read(&mut [0; 1024]).await;
+        let head = "HTTP/1.1 200 OK\r\ncontent-length: 1000\r\n\r\n";
+        stream.write_all(head.as_bytes()).await.unwrap();
+        stream.write_all(&[0; 10]).await.unwrap();
+        std::future::pending::<()>().await;
+    });
+
+    let mut settings = settings();
+    settings.proxy.allowed_hosts = vec!["127.0.0.1".to_string()];
+    let (app, _) = app_with(settings);
+    let hash = sha1(b"cortado");
+    let uri = format!("/proxy/{}?url=http://{}/client.jar", hash, addr);
+    let response = send(&app, Request::get(uri).body(Body::empty()).unwrap()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let shard = cache::settings().dir.join("artifacts").join(&hash[..2]);
+    let spools = || {
+        std::fs::read_dir(&shard)
+            .map(|files| files.flatten().filter(|f| f.file_name().to_string_lossy().starts_with(&hash)).count())
+            .unwrap_or(0)
+    };
+    assert_eq!(spools(), 1);
+    drop(response);
+    assert_eq!(spools(), 0);
+}
+
+// Upstream que responde `response(path)` a cada conexión y la cierra.
+async fn upstream(response: fn(u16, &str) -> String) -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut head = vec![];
+            let mut buf = [0; 1024];
+            while !head.ends_with(b"\r\n\r\n") {
+                let n = stream.read(&mut buf).await.unwrap();
+                head.extend_from_slice(&buf[..n]);
+            }
+            let head = String::from_utf8_lossy(&head);
+            let path = head.split(' ').nth(1).unwrap_or_default();
+            stream.write_all(response(port, path).as_bytes()).await.unwrap();
+        }
+    });
+    port
+}
+
+fn redirect_to(location: &str) -> String {
+    format!("HTTP/1.1 302 Found\r\nlocation: {}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n", location)
+}
+
+#[tokio::test]
+async fn redirects_are_followed_only_to_allowed_hosts() {
+    let port = upstream(|port, path| match path {
+        "/jar" => redirect_to("/cdn/jar"),
+        "/cdn/jar" => "HTTP/1.1 200 OK\r\ncontent-length: 8\r\nconnection: close\r\n\r\nredirect".to_string(),
+        // `localhost` es el mismo servidor, pero no está en la lista.
+        "/fuera" => redirect_to(&format!("http://localhost:{}/cdn/jar", port)),
+        _ => redirect_to("/bucle"),
+    })
+    .await;
+    let mut settings = settings();
+    settings.proxy.allowed_hosts = vec!["127.0.0.1".to_string()];
+    let (app, _) = app_with(settings);
+    // Tras la primera descarga el artefacto ya está en disco: cada caso lleva
+    // su propio checksum.
+    let proxy = |hash: &str, path: &str| format!("/proxy/{}?url=http://127.0.0.1:{}{}", hash, port, path);
+
+    let response = send(&app, Request::get(proxy(&sha1(b"redirect"), "/jar")).body(Body::empty()).unwrap()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(&to_bytes(response.into_body(), usize::MAX).await.unwrap()[..], b"redirect");
+
+    let response = send(&app, Request::get(proxy(&sha1(b"fuera"), "/fuera")).body(Body::empty()).unwrap()).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = send(&app, Request::get(proxy(&sha1(b"bucle"), "/bucle")).body(Body::empty()).unwrap()).await;
+    assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+}
+
+#[tokio::test]
+async fn upstream_failures_are_errors_not_misses() {
+    let port = upstream(|_, path| match path {
+        "/roto" => "HTTP/1.1 500 Internal Server Error\r\ncontent-length: 0\r\nconnection: close\r\n\r\n".to_string(),
+        _ => "HTTP/1.1 200 OK\r\ncontent-length: 4\r\nconnection: close\r\n\r\notro".to_string(),
+    })
+    .await;
+    let mut settings = settings();
+    settings.proxy.allowed_hosts = vec!["127.0.0.1".to_string()];
+    let (app, _) = app_with(settings);
+    let proxy = |hash: &str, path: &str| format!("/proxy/{}?url=http://127.0.0.1:{}{}", hash, port, path);
+    let before = artifact_stats(&app).await;
+
+    let response = send(&app, Request::get(proxy(&sha1(b"roto"), "/roto")).body(Body::empty()).unwrap()).await;
+    assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+    let response = send(&app, Request::get(proxy(&sha1(b"distinto"), "/distinto")).body(Body::empty()).unwrap()).await;
+    assert!(to_bytes(response.into_body(), usize::MAX).await.is_err());
+
+    let after = artifact_stats(&app).await;
+    let delta = |field: &str| after[field].as_u64().unwrap() - before[field].as_u64().unwrap();
+    assert!(delta("errors") >= 2);
+    // Como mucho la descarga buena de `redirects_are_followed_only_to_allowed_hosts`.
+    assert!(delta("misses") <= 1);
+}
+
+fn store_total() -> u64 {
+    let root = cache::settings().dir.join("artifacts");
+    let mut total = 0;
+    for shard in std::fs::read_dir(root).unwrap().flatten() {
+        // Sin los temporales, igual que el recolector.
+        for file in std::fs::read_dir(shard.path()).unwrap().flatten().filter(|f| f.path().extension().is_none()) {
+            total += file.metadata().unwrap().len();
+        }
+    }
+    total
+}
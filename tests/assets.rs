@@ -0,0 +1,46 @@
+mod common;
+
+use std::sync::atomic::Ordering;
+
+use axum::http::StatusCode;
+use common::{app, get, json};
+
+#[tokio::test]
+async fn diff_lists_only_missing_hashes_once() {
+    let (app, source) = app();
+    let diff = json(get(&app, "/assets/1.20.1/diff/23w31a").await, StatusCode::OK).await;
+    assert_eq!(diff["from_index"], "5");
+    assert_eq!(diff["to_index"], "7");
+
+    // en_gb.json tiene el mismo hash que en_us.json, que ya está instalado.
+    let names: Vec<&str> = diff["objects"].as_array().unwrap().iter().map(|o| o["name"].as_str().unwrap()).collect();
+    assert_eq!(
+        names,
+        [
+            "icons/icon_16x16.png",
+            "minecraft/sounds/mob/sniffer/idle1.ogg",
+            "minecraft/sounds/mob/sniffer/idle2.ogg",
+        ]
+    );
+    assert_eq!(diff["objects"][0]["url"], format!("https://resources.download.minecraft.net/ee/{}", "e".repeat(40)));
+    assert_eq!(diff["removed"], serde_json::json!(["minecraft/sounds/records/old.ogg"]));
+    assert_eq!(diff["unique_objects"], 2);
+    assert_eq!(diff["unique_size"], 500);
+
+    // Los índices quedan en caché por SHA1.
+    let calls = source.asset_index_calls.load(Ordering::SeqCst);
+    json(get(&app, "/assets/23w31a/diff/1.20.1").await, StatusCode::OK).await;
+    assert_eq!(source.asset_index_calls.load(Ordering::SeqCst), calls);
+}
+
+#[tokio::test]
+async fn same_index_needs_nothing_and_unknown_versions_fail() {
+    let (app, source) = app();
+    let diff = json(get(&app, "/assets/1.20.1/diff/1.20.1").await, StatusCode::OK).await;
+    assert_eq!(diff["objects"], serde_json::json!([]));
+    assert_eq!(diff["unique_size"], 0);
+    assert_eq!(source.asset_index_calls.load(Ordering::SeqCst), 0);
+
+    let response = get(&app, "/assets/1.20.1/diff/no-existe").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use axum::http::{header, StatusCode};
+use common::{app_with, get, json, settings};
+use futures_util::future::join_all;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+const LINKS: &str = include_str!("fixtures/bedrock/links.json");
+
+// Sirve el fixture de enlaces tras un pequeño retardo y cuenta las peticiones.
+async fn links_api() -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let counter = counter.clone();
+            tokio::spawn(async move {
+                let mut buf = [0; 1024];
+                let _ = stream.read(&mut buf).await;
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    LINKS.len(),
+                    LINKS
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            });
+        }
+    });
+    (format!("http://{}/api/v1.0/download/links", addr), requests)
+}
+
+// La caché es del proceso y la lista de Bedrock tiene una sola clave: todo en un test.
+#[tokio::test]
+async fn server_downloads_are_listed_coalesced_and_revalidated_in_background() {
+    let (url, requests) = links_api().await;
+    let mut settings = settings();
+    settings.bedrock.links_url = url;
+    settings.bedrock.cache_ttl_secs = 0;
+    let (app, _) = app_with(settings);
+
+    // Peticiones simultáneas sin caché comparten una sola descarga.
+    let responses = join_all((0..5).map(|_| get(&app, "/bedrock/versions"))).await;
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+    let mut bodies = vec![];
+    for response in responses {
+        bodies.push(json(response, StatusCode::OK).await);
+    }
+    let versions = &bodies[0];
+    assert!(bodies.iter().all(|body| body == versions));
+
+    assert_eq!(versions["latest"]["release"], "1.21.50.07");
+    assert_eq!(versions["latest"]["preview"], "1.21.60.21");
+    // El jar de Java, la URL sin versión y la entrada sin URL se descartan.
+    let downloads = versions["versions"].as_array().unwrap();
+    assert_eq!(downloads.len(), 4);
+    let linux_preview = downloads.iter().find(|d| d["platform"] == "linux" && d["type"] == "preview").unwrap();
+    assert_eq!(linux_preview["version"], "1.21.60.21");
+    assert_eq!(linux_preview["edition"], "server");
+    assert!(linux_preview["url"].as_str().unwrap().ends_with("/bin-linux-preview/bedrock-server-1.21.60.21.zip"));
+
+    // Vencida (TTL 0) pero dentro de la gracia: se sirve al momento y se refresca aparte.
+    let stale = get(&app, "/bedrock/versions").await;
+    assert_eq!(stale.status(), StatusCode::OK);
+    assert!(stale.headers()[header::WARNING].to_str().unwrap().starts_with("110"));
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+}
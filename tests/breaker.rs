@@ -0,0 +1,116 @@
+mod common;
+
+use std::time::Duration;
+
+use axum::http::{header, StatusCode};
+use common::{get, init_cache, settings, state_with};
+use manifestor::{
+    api,
+    manifest::breaker::{BreakerState, CircuitBreaker},
+};
+
+// Sin TTL cada petición va a upstream, y con upstream caído se sirve la copia en disco.
+fn init() {
+    init_cache(|settings| {
+        settings.version_ttl_secs = 0;
+        settings.stale_grace_secs = 0;
+    });
+}
+
+#[tokio::test]
+async fn breaker_opens_serves_the_last_copy_and_closes_after_a_probe() {
+    init();
+    let mut settings = settings();
+    settings.upstream.breaker_failure_threshold = 2;
+    settings.upstream.breaker_open_secs = 1;
+    let (state, source) = state_with(settings);
+    let app = api::create_router(state.clone());
+
+    assert_eq!(get(&app, "/version/1.16.5").await.status(), StatusCode::OK);
+    assert!(get(&app, "/version/1.16.5").await.headers().get(header::WARNING).is_none());
+
+    // Dos fallos seguidos abren el breaker.
+    source.fail(true);
+    for _ in 0..2 {
+        assert_eq!(state.breaker.state(), BreakerState::Closed);
+        let response = get(&app, "/version/1.16.5").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers()[header::WARNING].to_str().unwrap().starts_with("111"));
+    }
+    assert_eq!(state.breaker.state(), BreakerState::Open);
+
+    // Abierto: se sirve la última copia sin tocar upstream.
+    let attempts = source.attempts();
+    let response = get(&app, "/version/1.16.5").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()[header::WARNING].to_str().unwrap().starts_with("111"));
+    assert_eq!(source.attempts(), attempts);
+
+    // Pasado `breaker_open_secs`, una petición de prueba que sale bien lo cierra.
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    source.fail(false);
+    let response = get(&app, "/version/1.16.5").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get(header::WARNING).is_none());
+    assert_eq!(state.breaker.state(), BreakerState::Closed);
+}
+
+#[tokio::test]
+async fn half_open_breaker_lets_a_single_probe_through() {
+    let breaker = CircuitBreaker::new(3, Duration::from_millis(50));
+
+    for _ in 0..2 {
+        breaker.allow().unwrap().record(false);
+    }
+    assert_eq!(breaker.state(), BreakerState::Closed);
+    // Un éxito reinicia la cuenta.
+    breaker.allow().unwrap().record(true);
+    for _ in 0..3 {
+        breaker.allow().unwrap().record(false);
+    }
+    assert_eq!(breaker.state(), BreakerState::Open);
+    assert!(breaker.allow().is_none());
+
+    // Semiabierto: una sola prueba; si falla, vuelve a abrirse.
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    let probe = breaker.allow().unwrap();
+    assert_eq!(breaker.state(), BreakerState::HalfOpen);
+    assert!(breaker.allow().is_none());
+    probe.record(false);
+    assert_eq!(breaker.state(), BreakerState::Open);
+    assert!(breaker.allow().is_none());
+
+    // Si sale bien, se cierra y deja pasar todo.
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    let probe = breaker.allow().unwrap();
+    assert!(breaker.allow().is_none());
+    probe.record(true);
+    assert_eq!(breaker.state(), BreakerState::Closed);
+    assert!(breaker.allow().is_some());
+    assert!(breaker.allow().is_some());
+}
+
+#[tokio::test]
+async fn cancelled_probe_does_not_wedge_the_breaker() {
+    let breaker = CircuitBreaker::new(1, Duration::from_millis(50));
+    breaker.allow().unwrap().record(false);
+    tokio::time::sleep(Duration::from_millis(60)).await;
+
+    // La prueba se queda esperando a upstream y el future se cancela.
+    let probe = async {
+        let _attempt = breaker.allow().unwrap();
+        std::future::pending::<()>().await;
+    };
+    assert!(tokio::time::timeout(Duration::from_millis(10), probe).await.is_err());
+
+    // Cuenta como fallo: vuelve a abrirse y, pasado el plazo, admite otra prueba.
+    assert_eq!(breaker.state(), BreakerState::Open);
+    assert!(breaker.allow().is_none());
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    breaker.allow().unwrap().record(true);
+    assert_eq!(breaker.state(), BreakerState::Closed);
+
+    // Una petición normal cancelada no cuenta para nada.
+    drop(breaker.allow().unwrap());
+    assert_eq!(breaker.state(), BreakerState::Closed);
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use common::{app, get, json, send, ADMIN_TOKEN};
+
+// Cada test usa versiones distintas: la caché es compartida por el proceso.
+
+#[tokio::test]
+async fn second_request_is_served_from_cache() {
+    let (app, source) = app();
+
+    let first = json(get(&app, "/version/1.12.2").await, StatusCode::OK).await;
+    let second = json(get(&app, "/version/1.12.2").await, StatusCode::OK).await;
+
+    assert_eq!(first, second);
+    assert_eq!(source.version_calls(), 1);
+}
+
+#[tokio::test]
+async fn matching_etag_returns_not_modified() {
+    let (app, _) = app();
+
+    let response = get(&app, "/version/1.7.10").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response.headers()[header::ETAG].clone();
+    assert!(response.headers()[header::CACHE_CONTROL].to_str().unwrap().contains("max-age="));
+
+    let request = Request::get("/version/1.7.10")
+        .header(header::IF_NONE_MATCH, etag.clone())
+        .body(Body::empty())
+        .unwrap();
+    let revalidated = send(&app, request).await;
+    assert_eq!(revalidated.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(revalidated.headers()[header::ETAG], etag);
+}
+
+#[tokio::test]
+async fn purge_forces_a_new_fetch() {
+    let (app, source) = app();
+
+    json(get(&app, "/version/1.16.5").await, StatusCode::OK).await;
+    let purge = Request::delete("/cache/version/1.16.5")
+        .header(header::AUTHORIZATION, format!("Bearer {}", ADMIN_TOKEN))
+        .body(Body::empty())
+        .unwrap();
+    assert_eq!(send(&app, purge).await.status(), StatusCode::NO_CONTENT);
+    json(get(&app, "/version/1.16.5").await, StatusCode::OK).await;
+
+    assert_eq!(source.version_calls(), 2);
+}
+
+#[tokio::test]
+async fn purge_requires_admin_token() {
+    let (app, _) = app();
+    let response = send(&app, Request::delete("/cache").body(Body::empty()).unwrap()).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn bundles_are_cached_per_platform() {
+    let (app, source) = app();
+
+    json(get(&app, "/version/23w31a/bundle/linux-x64").await, StatusCode::OK).await;
+    json(get(&app, "/version/23w31a/bundle/linux-x64").await, StatusCode::OK).await;
+    assert_eq!(source.version_calls(), 1);
+
+    json(get(&app, "/version/23w31a/bundle/osx-arm64").await, StatusCode::OK).await;
+    assert_eq!(source.version_calls(), 2);
+}
@@ -0,0 +1,234 @@
+// Compartido por los tests de integración: cada archivo de `tests/` es un
+// binario aparte y no usa todo.
+#![allow(dead_code)]
+
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Once,
+    },
+};
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    response::Response,
+    Router,
+};
+use manifestor::{
+    api,
+    cache,
+    config::{CacheSettings, Settings},
+    source::{ManifestSource, SourceError, SourceFuture},
+    state::AppState,
+    types::VersionManifest,
+};
+use serde_json::Value;
+use tower::ServiceExt;
+
+pub const MANIFEST_URL: &str = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+pub const ADMIN_TOKEN: &str = "test-token";
+
+fn fixtures() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+/// Fuente que responde con los JSON de `tests/fixtures` y cuenta las
+/// peticiones. Mientras `failing` está activo, todo falla como si Mojang no
+/// respondiera; `version_delay_ms` hace lentas las versiones. `attempts`
+/// cuenta todas las peticiones, también las que fallan. `padding` añade al
+/// manifest ese número de versiones `old_alpha` sin JSON.
+#[derive(Default)]
+pub struct FixtureSource {
+    pub manifest_calls: AtomicUsize,
+    pub version_calls: AtomicUsize,
+    pub asset_index_calls: AtomicUsize,
+    pub attempts: AtomicUsize,
+    pub failing: AtomicBool,
+    pub version_delay_ms: AtomicU64,
+    pub padding: AtomicUsize,
+}
+
+impl FixtureSource {
+    pub fn fail(&self, failing: bool) {
+        self.failing.store(failing, Ordering::SeqCst);
+    }
+
+    pub fn version_calls(&self) -> usize {
+        self.version_calls.load(Ordering::SeqCst)
+    }
+
+    pub fn attempts(&self) -> usize {
+        self.attempts.load(Ordering::SeqCst)
+    }
+
+    fn check(&self) -> Result<(), SourceError> {
+        self.attempts.fetch_add(1, Ordering::SeqCst);
+        if self.failing.load(Ordering::SeqCst) {
+            return Err(SourceError::Fetch("upstream caído (fixture)".to_string()));
+        }
+        Ok(())
+    }
+}
+
+impl ManifestSource for FixtureSource {
+    // Cualquier otra URL es un catálogo experimental de `fixtures/catalogs`.
+    fn manifest<'a>(&'a self, url: &'a str) -> SourceFuture<'a, Result<VersionManifest, SourceError>> {
+        Box::pin(async move {
+            self.check()?;
+            if url != MANIFEST_URL {
+                let file = url.rsplit('/').next().unwrap_or_default();
+                return Ok(manifest_from_mojang(&read_fixture(&format!("catalogs/{}", file))?));
+            }
+            self.manifest_calls.fetch_add(1, Ordering::SeqCst);
+            let mut manifest = manifest_from_mojang(&read_fixture("version_manifest_v2.json")?);
+            for i in 0..self.padding.load(Ordering::SeqCst) {
+                manifest.versions.push(manifestor::types::MinecraftVersion {
+                    id: format!("a0.0.{}", i),
+                    hash: String::new(),
+                    release_time: "2009-05-16T00:00:00+00:00".to_string(),
+                    url: format!("https://piston-meta.mojang.com/v1/packages/padding/a0.0.{}.json", i),
+                    version_type: "old_alpha".to_string(),
+                });
+            }
+            Ok(manifest)
+        })
+    }
+
+    fn version<'a>(&'a self, url: &'a str) -> SourceFuture<'a, Result<Value, SourceError>> {
+        Box::pin(async move {
+            self.version_calls.fetch_add(1, Ordering::SeqCst);
+            let delay = self.version_delay_ms.load(Ordering::SeqCst);
+            if delay > 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+            }
+            self.check()?;
+            let file = url.rsplit('/').next().unwrap_or_default();
+            read_fixture(&format!("versions/{}", file))
+        })
+    }
+
+    // El índice es `.../all.json`; cada manifest de archivos, `.../<sha1>/manifest.json`.
+    fn java_runtime<'a>(&'a self, url: &'a str) -> SourceFuture<'a, Result<Value, SourceError>> {
+        Box::pin(async move {
+            self.check()?;
+            let mut segments = url.rsplit('/');
+            let file = match (segments.next(), segments.next()) {
+                (Some("all.json"), _) => "all".to_string(),
+                (_, Some(sha1)) => sha1.to_string(),
+                _ => String::new(),
+            };
+            read_fixture(&format!("java/{}.json", file))
+        })
+    }
+
+    fn asset_index<'a>(&'a self, url: &'a str) -> SourceFuture<'a, Result<Value, SourceError>> {
+        Box::pin(async move {
+            self.asset_index_calls.fetch_add(1, Ordering::SeqCst);
+            self.check()?;
+            let file = url.rsplit('/').next().unwrap_or_default();
+            read_fixture(&format!("assets/{}", file))
+        })
+    }
+}
+
+fn read_fixture(path: &str) -> Result<Value, SourceError> {
+    let contents = std::fs::read_to_string(fixtures().join(path))
+        .map_err(|e| SourceError::Fetch(format!("{}: {}", path, e)))?;
+    serde_json::from_str(&contents).map_err(|e| SourceError::Decode(e.to_string()))
+}
+
+// Mismo mapeo que `manifestor_core::fetch_version_manifest`.
+fn manifest_from_mojang(raw: &Value) -> VersionManifest {
+    let text = |v: &Value, key: &str| v.get(key).and_then(Value::as_str).unwrap_or_default().to_string();
+    VersionManifest {
+        latest_release: text(&raw["latest"], "release"),
+        latest_snapshot: text(&raw["latest"], "snapshot"),
+        versions: raw["versions"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|v| manifestor::types::MinecraftVersion {
+                id: text(v, "id"),
+                hash: text(v, "sha1"),
+                release_time: text(v, "releaseTime"),
+                url: text(v, "url"),
+                version_type: text(v, "type"),
+            })
+            .collect(),
+    }
+}
+
+/// La caché es global al proceso: se configura una vez por binario de test,
+/// con un directorio propio.
+pub fn init_cache(configure: impl FnOnce(&mut CacheSettings)) {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        let mut settings = CacheSettings {
+            dir: std::env::temp_dir().join(format!("manifestor-test-{}", std::process::id())),
+            ..CacheSettings::default()
+        };
+        configure(&mut settings);
+        let _ = std::fs::remove_dir_all(&settings.dir);
+        cache::init(settings);
+    });
+}
+
+pub fn settings() -> Settings {
+    let mut settings = Settings::default();
+    settings.upstream.manifest_url = MANIFEST_URL.to_string();
+    settings.upstream.max_retries = 0;
+    settings.admin.token = Some(ADMIN_TOKEN.to_string());
+    settings
+}
+
+/// Estado sobre una `FixtureSource` nueva.
+pub fn state() -> (AppState, Arc<FixtureSource>) {
+    state_with(settings())
+}
+
+pub fn state_with(settings: Settings) -> (AppState, Arc<FixtureSource>) {
+    init_cache(|_| {});
+    let source = Arc::new(FixtureSource::default());
+    let state = AppState::with_source(settings, source.clone()).expect("estado de test");
+    (state, source)
+}
+
+/// Router completo sobre una `FixtureSource` nueva.
+pub fn app() -> (Router, Arc<FixtureSource>) {
+    app_with(settings())
+}
+
+pub fn app_with(settings: Settings) -> (Router, Arc<FixtureSource>) {
+    let (state, source) = state_with(settings);
+    (api::create_router(state), source)
+}
+
+pub async fn send(app: &Router, request: Request<Body>) -> Response {
+    app.clone().oneshot(request).await.expect("el router no falla")
+}
+
+pub async fn get(app: &Router, uri: &str) -> Response {
+    send(app, Request::get(uri).body(Body::empty()).unwrap()).await
+}
+
+pub async fn post_json(app: &Router, uri: &str, body: Value) -> Response {
+    let request = Request::post(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    send(app, request).await
+}
+
+pub async fn body_text(response: Response) -> String {
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.expect("cuerpo legible");
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Comprueba el estado y devuelve el cuerpo como JSON.
+pub async fn json(response: Response, status: StatusCode) -> Value {
+    assert_eq!(response.status(), status);
+    let text = body_text(response).await;
+    serde_json::from_str(&text).unwrap_or_else(|e| panic!("JSON inválido ({}): {}", e, text))
+}
@@ -0,0 +1,205 @@
+mod common;
+
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    http::{header, HeaderValue, Request, StatusCode},
+    response::Response,
+    Router,
+};
+use common::{app, send};
+use manifestor::compression::deflate::gzip;
+use serde_json::{json, Value};
+
+/// Descompresor mínimo para lo que produce `deflate`: bloques con códigos
+/// Huffman fijos y bloques sin comprimir. Comprueba también el CRC y el
+/// tamaño del pie gzip.
+fn gunzip(data: &[u8]) -> Vec<u8> {
+    assert_eq!(&data[..3], &[0x1f, 0x8b, 8], "cabecera gzip");
+    assert_eq!(data[3], 0, "sin flags");
+    let mut bits = Bits { data: &data[10..], pos: 0 };
+    let mut out = vec![];
+
+    loop {
+        let last = bits.read(1) == 1;
+        match bits.read(2) {
+            0 => {
+                bits.pos = bits.pos.div_ceil(8) * 8;
+                let len = bits.read(16) as usize;
+                assert_eq!(bits.read(16) as usize, !len & 0xffff);
+                for _ in 0..len {
+                    out.push(bits.read(8) as u8);
+                }
+            }
+            1 => inflate_fixed(&mut bits, &mut out),
+            other => panic!("tipo de bloque inesperado: {}", other),
+        }
+        if last {
+            break;
+        }
+    }
+
+    let trailer = &data[10 + bits.pos.div_ceil(8)..];
+    assert_eq!(trailer.len(), 8, "pie gzip");
+    assert_eq!(u32::from_le_bytes(trailer[..4].try_into().unwrap()), crc32(&out));
+    assert_eq!(u32::from_le_bytes(trailer[4..].try_into().unwrap()), out.len() as u32);
+    out
+}
+
+const LENGTH_BASE: [usize; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+const DIST_BASE: [usize; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+    8193, 12289, 16385, 24577,
+];
+
+fn inflate_fixed(bits: &mut Bits, out: &mut Vec<u8>) {
+    loop {
+        let symbol = bits.fixed_literal();
+        match symbol {
+            0..=255 => out.push(symbol as u8),
+            256 => return,
+            _ => {
+                let i = symbol - 257;
+                let extra = if i < 8 || i == 28 { 0 } else { (i - 4) / 4 };
+                let len = LENGTH_BASE[i] + bits.read(extra as u32) as usize;
+                let d = bits.huffman(5) as usize;
+                let extra = if d < 4 { 0 } else { (d - 2) / 2 };
+                let dist = DIST_BASE[d] + bits.read(extra as u32) as usize;
+                assert!(dist <= out.len() && dist <= 32 * 1024, "distancia fuera de la ventana: {}", dist);
+                for _ in 0..len {
+                    out.push(out[out.len() - dist]);
+                }
+            }
+        }
+    }
+}
+
+struct Bits<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Bits<'_> {
+    // Valores desde el bit menos significativo.
+    fn read(&mut self, count: u32) -> u32 {
+        (0..count).fold(0, |value, i| {
+            let bit = (self.data[self.pos / 8] >> (self.pos % 8)) & 1;
+            self.pos += 1;
+            value | u32::from(bit) << i
+        })
+    }
+
+    // Códigos Huffman, desde el más significativo.
+    fn huffman(&mut self, len: u32) -> u32 {
+        (0..len).fold(0, |code, _| code << 1 | self.read(1))
+    }
+
+    fn fixed_literal(&mut self) -> usize {
+        let code = self.huffman(7) as usize;
+        if code <= 0x17 {
+            return 256 + code;
+        }
+        let code = code << 1 | self.read(1) as usize;
+        match code {
+            0x30..=0xbf => code - 0x30,
+            0xc0..=0xc7 => 280 + code - 0xc0,
+            _ => 144 + (code << 1 | self.read(1) as usize) - 0x190,
+        }
+    }
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |mut crc, &b| {
+        crc ^= u32::from(b);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { 0xedb88320 ^ (crc >> 1) } else { crc >> 1 };
+        }
+        crc
+    })
+}
+
+// Bytes pseudoaleatorios (xorshift): no se pueden comprimir.
+fn noise(len: usize, mut seed: u32) -> Vec<u8> {
+    (0..len)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed as u8
+        })
+        .collect()
+}
+
+#[test]
+fn gzip_roundtrips_empty_input() {
+    assert!(gunzip(&gzip(b"")).is_empty());
+    assert_eq!(gunzip(&gzip(b"a")), b"a");
+}
+
+#[test]
+fn gzip_roundtrips_incompressible_input() {
+    let data = noise(100_000, 0x9e37_79b9);
+    assert_eq!(gunzip(&gzip(&data)), data);
+}
+
+#[test]
+fn gzip_roundtrips_input_larger_than_the_window() {
+    // Un bloque repetido más allá de los 32 KiB de ventana, más JSON repetitivo
+    // con coincidencias largas (258 bytes) y cercanas.
+    let block = noise(40 * 1024, 7);
+    let mut data = [block.as_slice(), block.as_slice(), block.as_slice()].concat();
+    for i in 0..5_000 {
+        data.extend(format!("{{\"name\":\"org.lwjgl:lwjgl:3.3.{}\",\"sha1\":\"{:040}\"}},", i % 7, i).bytes());
+    }
+    data.extend(std::iter::repeat_n(b'x', 2_000));
+
+    let compressed = gzip(&data);
+    assert!(compressed.len() < data.len());
+    assert_eq!(gunzip(&compressed), data);
+}
+
+async fn plain_and_gzip(app: &Router, request: impl Fn() -> Request<Body>) -> (Bytes, Response) {
+    let plain = to_bytes(send(app, request()).await.into_body(), usize::MAX).await.unwrap();
+    let mut gzip_request = request();
+    gzip_request.headers_mut().insert(header::ACCEPT_ENCODING, HeaderValue::from_static("gzip"));
+    (plain, send(app, gzip_request).await)
+}
+
+#[tokio::test]
+async fn gzip_responses_decode_to_the_plain_body() {
+    let (app, _) = app();
+
+    let (plain, response) = plain_and_gzip(&app, || Request::get("/version/1.20.1").body(Body::empty()).unwrap()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+    assert!(response.headers()[header::ETAG].to_str().unwrap().ends_with("-gzip\""));
+
+    let compressed = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert!(compressed.len() < plain.len());
+    assert_eq!(gunzip(&compressed), plain);
+}
+
+#[tokio::test]
+async fn large_responses_are_compressed_off_the_async_worker() {
+    let (app, _) = app();
+
+    // Un perfil con muchas librerías da una respuesta de varios cientos de KiB.
+    let mut raw: Value = serde_json::from_str(include_str!("fixtures/versions/1.20.1.json")).unwrap();
+    let libraries: Vec<Value> = (0..2_000)
+        .map(|i| json!({ "name": format!("com.example:lib{}:1.0", i), "url": "https://maven.example.com/" }))
+        .collect();
+    raw["libraries"] = Value::Array(libraries);
+    let request = || {
+        Request::post("/normalize")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(raw.to_string()))
+            .unwrap()
+    };
+
+    let (plain, response) = plain_and_gzip(&app, request).await;
+    assert!(plain.len() > 64 * 1024, "{}", plain.len());
+    assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+    let compressed = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(gunzip(&compressed), plain);
+}
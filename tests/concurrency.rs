@@ -0,0 +1,107 @@
+mod common;
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use axum::http::{header, StatusCode};
+use common::{app_with, get, json, settings};
+use manifestor::cache::singleflight::SingleFlight;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+#[tokio::test]
+async fn full_group_sheds_with_retry_after() {
+    let mut settings = settings();
+    settings.concurrency.normalize = 1;
+    settings.concurrency.retry_after_secs = 3;
+    let (app, source) = app_with(settings);
+    source.version_delay_ms.store(300, Ordering::SeqCst);
+
+    let slow = tokio::spawn({
+        let app = app.clone();
+        async move { get(&app, "/version/1.7.10/bundle/linux-x64").await.status() }
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let shed = get(&app, "/version/1.12.2/bundle/linux-x64").await;
+    assert_eq!(shed.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(shed.headers()[header::RETRY_AFTER], "3");
+
+    // El resto de grupos sigue respondiendo.
+    json(get(&app, "/manifest").await, StatusCode::OK).await;
+
+    assert_eq!(slow.await.unwrap(), StatusCode::OK);
+    source.version_delay_ms.store(0, Ordering::SeqCst);
+    json(get(&app, "/version/1.12.2/bundle/linux-x64").await, StatusCode::OK).await;
+}
+
+#[tokio::test]
+async fn proxy_downloads_hold_their_slot_until_the_body_ends() {
+    // Upstream que anuncia 1000 bytes, envía 10 y se queda esperando.
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let _ = stream.read(&mut [0; 1024]).await;
+                let _ = stream.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 1000\r\n\r\n").await;
+                let _ = stream.write_all(&[0; 10]).await;
+                std::future::pending::<()>().await;
+            });
+        }
+    });
+
+    let mut settings = settings();
+    settings.concurrency.proxy = 2;
+    settings.proxy.allowed_hosts = vec!["127.0.0.1".to_string()];
+    let (app, _) = app_with(settings);
+    let uri = |i: usize| format!("/proxy/{:040x}?url=http://{}/{}.jar", i + 1, addr, i);
+
+    // Las dos primeras ya tienen cabeceras, pero siguen descargando.
+    let first = get(&app, &uri(0)).await;
+    let second = get(&app, &uri(1)).await;
+    assert_eq!((first.status(), second.status()), (StatusCode::OK, StatusCode::OK));
+    assert_eq!(get(&app, &uri(2)).await.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    // Al soltar un cuerpo queda un hueco libre.
+    drop(first);
+    assert_eq!(get(&app, &uri(3)).await.status(), StatusCode::OK);
+    drop(second);
+}
+
+#[tokio::test]
+async fn single_flight_shares_one_call_and_then_forgets_it() {
+    let flight = Arc::new(SingleFlight::<Result<usize, String>>::new());
+    let calls = Arc::new(AtomicUsize::new(0));
+    let failing = |flight: Arc<SingleFlight<Result<usize, String>>>, calls: Arc<AtomicUsize>| async move {
+        flight
+            .run("manifest", || async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Err::<usize, _>(format!("fallo {}", calls.fetch_add(1, Ordering::SeqCst)))
+            })
+            .await
+    };
+
+    let (a, b) = tokio::join!(failing(flight.clone(), calls.clone()), failing(flight.clone(), calls.clone()));
+    assert_eq!(a, b);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    // Un error resuelto no queda fijado para las siguientes llamadas.
+    assert_eq!(failing(flight.clone(), calls.clone()).await, Err("fallo 1".to_string()));
+
+    // Ni el de una llamada cancelada a medias.
+    let cancelled = tokio::spawn(failing(flight.clone(), calls.clone()));
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    cancelled.abort();
+    let _ = cancelled.await;
+    let value = flight.run("manifest", || async { Ok(7) }).await;
+    assert_eq!(value, Ok(7));
+    assert_eq!(flight.run("manifest", || async { Ok(8) }).await, Ok(8));
+}
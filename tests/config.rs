@@ -0,0 +1,42 @@
+use manifestor::config::Settings;
+
+fn load(vars: &[(&str, &str)]) -> Result<Settings, String> {
+    Settings::load_from(|var| vars.iter().find(|(name, _)| *name == var).map(|(_, value)| value.to_string()))
+}
+
+#[test]
+fn string_settings_keep_numeric_looking_values() {
+    let settings = load(&[
+        ("ADMIN_TOKEN", "123456"),
+        ("PROFILES_TOKEN", "true"),
+        ("UPSTREAM_MIN_TLS_VERSION", "1.2"),
+    ])
+    .unwrap();
+    assert_eq!(settings.admin.token.as_deref(), Some("123456"));
+    assert_eq!(settings.profiles.token.as_deref(), Some("true"));
+    assert_eq!(settings.upstream.tls.min_version.as_deref(), Some("1.2"));
+}
+
+#[test]
+fn numbers_flags_and_lists_are_parsed_by_field_type() {
+    let settings = load(&[
+        ("CACHE_MAX_BYTES", "1024"),
+        ("COMPRESSION_ENABLED", "false"),
+        ("UPSTREAM_NO_PROXY", "localhost, .example.com"),
+    ])
+    .unwrap();
+    assert_eq!(settings.cache.max_bytes, 1024);
+    assert!(!settings.compression.enabled);
+    assert_eq!(settings.upstream.proxy.no_proxy, ["localhost", ".example.com"]);
+
+    assert!(load(&[("VERSION_TTL_SECS", "media hora")]).unwrap_err().contains("VERSION_TTL_SECS"));
+    assert!(load(&[("HISTORY_ENABLED", "1.2")]).unwrap_err().contains("HISTORY_ENABLED"));
+}
+
+#[test]
+fn example_config_loads() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/manifestor.example.toml");
+    let settings = load(&[("MANIFESTOR_CONFIG", path), ("ADMIN_TOKEN", "000123")]).unwrap();
+    assert!(settings.upstream.tls.system_roots);
+    assert_eq!(settings.admin.token.as_deref(), Some("000123"));
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use axum::http::{header, StatusCode};
+use common::{app, body_text, get, init_cache, json, post_json};
+use manifestor::{cache::disk, types::NormalizedVersion};
+use serde_json::json;
+
+// Sin TTL todo se vuelve a pedir a upstream: así se ve el fallback a disco.
+fn init() {
+    init_cache(|settings| {
+        settings.version_ttl_secs = 0;
+        settings.stale_grace_secs = 0;
+    });
+}
+
+#[tokio::test]
+async fn unknown_version_is_not_found() {
+    init();
+    let (app, source) = app();
+
+    let response = get(&app, "/version/no-existe").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(body_text(response).await.contains("no-existe"));
+    assert_eq!(source.version_calls(), 0);
+}
+
+#[tokio::test]
+async fn failing_version_download_is_bad_gateway() {
+    init();
+    let (app, _) = app();
+
+    // `1.0` está en el manifest pero no hay fixture para su JSON.
+    let response = get(&app, "/version/1.0").await;
+    assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+}
+
+#[tokio::test]
+async fn upstream_failure_falls_back_to_disk() {
+    init();
+    let (app, source) = app();
+
+    json(get(&app, "/version/1.20.1").await, StatusCode::OK).await;
+    source.fail(true);
+    let response = get(&app, "/version/1.20.1").await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let warning = response.headers()[header::WARNING].to_str().unwrap().to_string();
+    assert!(warning.starts_with("111"), "{}", warning);
+    let version = json(response, StatusCode::OK).await;
+    assert_eq!(version["id"], "1.20.1");
+}
+
+#[tokio::test]
+async fn disk_fallback_ignores_ids_sharing_a_file_name() {
+    init();
+    let (app, _) = app();
+
+    // `1.12 2` y `1.12_2` se guardan en el mismo `1.12_2.json`.
+    let version: NormalizedVersion =
+        serde_json::from_value(json(get(&app, "/version/1.12.2").await, StatusCode::OK).await).unwrap();
+    disk::store_version("1.12 2", &version, "\"etag\"").await;
+
+    assert_eq!(disk::load_version("1.12 2").await.unwrap().key, "1.12 2");
+    assert!(disk::load_version("1.12_2").await.is_none());
+}
+
+#[tokio::test]
+async fn invalid_platform_is_bad_request() {
+    init();
+    let (app, _) = app();
+    assert_eq!(get(&app, "/version/1.20.1/bundle/beos-x64").await.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(get(&app, "/version/1.20.1/bundle/linux").await.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn secrets_are_rejected_in_argument_expansion() {
+    init();
+    let (app, _) = app();
+    let uri = "/version/1.16.5/arguments/template";
+
+    let rejected = post_json(&app, uri, json!({ "values": { "auth_access_token": "abc" } })).await;
+    assert_eq!(rejected.status(), StatusCode::BAD_REQUEST);
+
+    let expanded = json(
+        post_json(&app, uri, json!({ "values": { "auth_player_name": "Steve" } })).await,
+        StatusCode::OK,
+    )
+    .await;
+    assert!(expanded["command_line"].as_array().unwrap().iter().any(|a| a == "Steve"));
+    assert!(expanded["unresolved"].as_array().unwrap().iter().any(|a| a == "auth_access_token"));
+}
+
+#[tokio::test]
+async fn malformed_requests_are_rejected() {
+    init();
+    let (app, _) = app();
+
+    assert_eq!(post_json(&app, "/normalize", json!([1, 2])).await.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(post_json(&app, "/versions/batch", json!({ "ids": [] })).await.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn malformed_version_json_is_unprocessable() {
+    init();
+    let (app, _) = app();
+
+    let body = json!({ "id": "roto", "libraries": [{ "name": 42 }] });
+    let response = post_json(&app, "/normalize", body).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(body_text(response).await.contains("JSON de versión inválido"));
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{app, app_with, get, json, settings};
+
+fn experimental_app() -> (axum::Router, std::sync::Arc<common::FixtureSource>) {
+    let mut settings = settings();
+    settings.experimental.catalogs = vec!["https://archive.example.org/catalogs/experimental.json".to_string()];
+    app_with(settings)
+}
+
+#[tokio::test]
+async fn include_experimental_merges_the_catalog_by_date() {
+    let (app, _) = experimental_app();
+    let plain = json(get(&app, "/manifest").await, StatusCode::OK).await;
+    assert_eq!(plain["total"], 6);
+
+    let merged = json(get(&app, "/manifest?include=experimental").await, StatusCode::OK).await;
+    let ids: Vec<&str> = merged["versions"].as_array().unwrap().iter().map(|v| v["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, [
+        "23w31a",
+        "1.20.1",
+        "1.19_deep_dark_experimental_snapshot-1",
+        "1.16.5",
+        "1.14_combat-212796",
+        "1.12.2",
+        "1.7.10",
+        "1.0",
+    ]);
+    // Mojang gana para los ids que ya lista.
+    assert_eq!(merged["versions"][3]["sha1"], "b3756bc66c5aad32d104fd909a323859600681a8");
+
+    let only = json(get(&app, "/manifest?include=experimental&type=experiment").await, StatusCode::OK).await;
+    assert_eq!(only["total"], 2);
+}
+
+#[tokio::test]
+async fn experimental_versions_are_normalized_like_the_rest() {
+    let (catalog_app, _) = experimental_app();
+    let version = json(get(&catalog_app, "/version/1.19_deep_dark_experimental_snapshot-1").await, StatusCode::OK).await;
+    assert_eq!(version["id"], "1.19_deep_dark_experimental_snapshot-1");
+    assert_eq!(version["type"], "experiment");
+    assert_eq!(version["java_version"], 17);
+
+    let (without_catalog, _) = app();
+    let missing = get(&without_catalog, "/version/1.14_combat-212796").await;
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,199 @@
+mod common;
+
+use std::sync::{atomic::Ordering, Arc};
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    Router,
+};
+use common::{app_with, json, post_json, send, settings, FixtureSource, ADMIN_TOKEN};
+use serde_json::{json, Value};
+
+const MIRROR: &str = "https://mirror.example.com";
+
+// La caché del manifest es común a todo el binario: todos los tests usan el
+// mismo relleno (más versiones de las que admite una consulta con detalles).
+fn app() -> (Router, Arc<FixtureSource>) {
+    let mut settings = settings();
+    settings.mirror.base_url = Some(MIRROR.to_string());
+    settings.profiles.enabled = true;
+    let (app, source) = app_with(settings);
+    source.padding.store(70, Ordering::SeqCst);
+    (app, source)
+}
+
+async fn query(app: &Router, query: &str, variables: Value) -> Value {
+    let body = json!({ "query": query, "variables": variables });
+    json(post_json(app, "/graphql", body).await, StatusCode::OK).await
+}
+
+#[tokio::test]
+async fn manifest_fields_do_not_load_versions() {
+    let (app, source) = app();
+    let body = json!({ "query": "{ latest { release } versions(type: \"release\", limit: 2) { id release_time } }" });
+    let response = json(post_json(&app, "/graphql", body).await, StatusCode::OK).await;
+
+    assert_eq!(response["data"]["latest"]["release"], "1.20.1");
+    assert_eq!(response["data"]["versions"][0], json!({ "id": "1.20.1", "release_time": "2023-06-12T13:25:51+00:00" }));
+    assert!(response.get("errors").is_none());
+    assert_eq!(source.version_calls(), 0);
+}
+
+#[tokio::test]
+async fn selects_nested_version_fields() {
+    let (app, _) = app();
+    let body = json!({
+        "query": "query V($id: String!) { version(id: $id) { ...Java libraries { name } } } fragment Java on Version { java_version }",
+        "variables": { "id": "1.16.5" },
+    });
+    let response = json(post_json(&app, "/graphql", body).await, StatusCode::OK).await;
+
+    let version = &response["data"]["version"];
+    assert_eq!(version["java_version"], 8);
+    let library = version["libraries"][0].as_object().unwrap();
+    assert_eq!(library.keys().collect::<Vec<_>>(), ["name"]);
+}
+
+#[tokio::test]
+async fn field_errors_keep_the_rest_of_the_data() {
+    let (app, _) = app();
+    let body = json!({ "query": "{ latest { snapshot } version(id: \"1.12.2\") { nope } missing: version(id: \"nada\") { id } }" });
+    let response = json(post_json(&app, "/graphql", body).await, StatusCode::OK).await;
+
+    assert_eq!(response["data"]["latest"]["snapshot"], "23w31a");
+    assert_eq!(response["data"]["missing"], serde_json::Value::Null);
+    assert_eq!(response["errors"][0]["path"], json!(["version", "nope"]));
+
+    let invalid = post_json(&app, "/graphql", json!({ "query": "{ latest {" })).await;
+    assert_eq!(invalid.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn deeply_nested_queries_are_rejected_before_running() {
+    let (app, source) = app();
+    let selections = format!("{{{}{}}}", "a{".repeat(5000), "}".repeat(5000));
+    let lists = format!("{{ version(id: {}{}) {{ id }} }}", "[".repeat(8000), "]".repeat(8000));
+    let types = format!("query Q($id: {}String{}) {{ latest {{ release }} }}", "[".repeat(8000), "]".repeat(8000));
+
+    for query in [selections, lists, types] {
+        let response = post_json(&app, "/graphql", json!({ "query": query })).await;
+        let body = json(response, StatusCode::BAD_REQUEST).await;
+        assert!(body["errors"][0]["message"].as_str().unwrap().contains("32 niveles"));
+    }
+    assert_eq!(source.attempts(), 0);
+}
+
+#[tokio::test]
+async fn detailed_fields_are_capped_but_manifest_fields_are_not() {
+    let (app, source) = app();
+    let response = query(&app, "{ versions { id java_version } latest { release } }", json!({})).await;
+    assert_eq!(response["data"]["versions"], Value::Null);
+    assert_eq!(response["data"]["latest"]["release"], "1.20.1");
+    assert_eq!(response["errors"][0]["path"], json!(["versions"]));
+    assert!(response["errors"][0]["message"].as_str().unwrap().contains("64"));
+    assert_eq!(source.version_calls(), 0);
+
+    let response = query(&app, "{ versions { id ... on Version { __typename } } }", json!({})).await;
+    assert_eq!(response["data"]["versions"].as_array().unwrap().len(), 76);
+    assert!(response.get("errors").is_none());
+}
+
+#[tokio::test]
+async fn fragments_are_merged_by_type() {
+    let (app, _) = app();
+    let response = query(
+        &app,
+        "{ version(id: \"1.20.1\") { ...Ids ... on Version { java_version } ... on Latest { release } } }
+         fragment Ids on Version { id ...Main }
+         fragment Main on Version { main_class id }",
+        json!({}),
+    )
+    .await;
+    assert!(response.get("errors").is_none());
+    assert_eq!(
+        response["data"]["version"],
+        json!({ "id": "1.20.1", "main_class": "net.minecraft.client.main.Main", "java_version": 17 })
+    );
+
+    let response = query(&app, "{ latest { ...Nope } }", json!({})).await;
+    assert!(response["errors"][0]["message"].as_str().unwrap().contains("Nope"));
+}
+
+#[tokio::test]
+async fn skip_and_include_follow_their_variables() {
+    let (app, _) = app();
+    let document = "query Q($full: Boolean!, $quiet: Boolean = true) {
+        latest { release snapshot @include(if: $full) }
+        version(id: \"1.12.2\") @skip(if: $quiet) { id }
+        ... @include(if: $full) { extra: latest { release } }
+    }";
+
+    let response = query(&app, document, json!({ "full": true })).await;
+    assert_eq!(response["data"], json!({ "latest": { "release": "1.20.1", "snapshot": "23w31a" }, "extra": { "release": "1.20.1" } }));
+
+    let response = query(&app, document, json!({ "full": false, "quiet": false })).await;
+    assert_eq!(response["data"], json!({ "latest": { "release": "1.20.1" }, "version": { "id": "1.12.2" } }));
+}
+
+#[tokio::test]
+async fn asset_objects_are_listed_through_the_mirror() {
+    let (app, _) = app();
+    let response = query(
+        &app,
+        "{ version(id: \"1.20.1\") { asset_index { id url objects(prefix: \"minecraft/\") { name url } } } }",
+        json!({}),
+    )
+    .await;
+    assert!(response.get("errors").is_none());
+    let index = &response["data"]["version"]["asset_index"];
+    assert_eq!(index["id"], "5");
+    assert!(index["url"].as_str().unwrap().starts_with(MIRROR));
+
+    let objects = index["objects"].as_array().unwrap();
+    let names: Vec<&str> = objects.iter().map(|o| o["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["minecraft/lang/en_us.json", "minecraft/sounds/ambient/cave/cave1.ogg", "minecraft/sounds/records/old.ogg"]);
+    assert!(objects.iter().all(|o| o["url"].as_str().unwrap().starts_with(MIRROR)));
+
+    let many = (0..5).map(|i| format!("a{}: version(id: \"1.20.1\") {{ asset_index {{ objects {{ size }} }} }}", i));
+    let response = query(&app, &format!("{{ {} }}", many.collect::<Vec<_>>().join(" ")), json!({})).await;
+    let errors = response["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0]["message"].as_str().unwrap().contains("4 listas"));
+}
+
+#[tokio::test]
+async fn uploaded_profiles_are_part_of_the_graph() {
+    let (app, _) = app();
+    let profile = json!({
+        "id": "1.16.5-graphql",
+        "inheritsFrom": "1.16.5",
+        "mainClass": "net.minecraft.launchwrapper.Launch",
+        "libraries": [{ "name": "optifine:OptiFine:1.16.5_HD_U_G8" }],
+    });
+    let upload = Request::post("/profiles")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, format!("Bearer {}", ADMIN_TOKEN))
+        .body(Body::from(profile.to_string()))
+        .unwrap();
+    assert_eq!(send(&app, upload).await.status(), StatusCode::CREATED);
+
+    let response = query(
+        &app,
+        "query P($name: String!) {
+            profile(name: $name) { name inherits_from main_class java_version libraries { name } }
+            missing: profile(name: \"nada\") { name }
+            profiles { name }
+        }",
+        json!({ "name": "1.16.5-graphql" }),
+    )
+    .await;
+    assert!(response.get("errors").is_none());
+    let found = &response["data"]["profile"];
+    assert_eq!(found["inherits_from"], "1.16.5");
+    assert_eq!(found["main_class"], "net.minecraft.launchwrapper.Launch");
+    assert_eq!(found["java_version"], 8);
+    assert!(found["libraries"].as_array().unwrap().iter().any(|l| l["name"] == "optifine:OptiFine:1.16.5_HD_U_G8"));
+    assert_eq!(response["data"]["missing"], Value::Null);
+    assert!(response["data"]["profiles"].as_array().unwrap().iter().any(|p| p["name"] == "1.16.5-graphql"));
+}
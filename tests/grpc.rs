@@ -0,0 +1,95 @@
+#![cfg(feature = "grpc")]
+
+mod common;
+
+use axum::{
+    body::{Body, Bytes},
+    http::{HeaderMap, Request},
+    Router,
+};
+use common::{send, state};
+use http_body_util::BodyExt;
+use manifestor::grpc::{self, wire};
+
+// Cuerpo de la respuesta sin la cabecera de gRPC, y las cabeceras con los
+// trailers incluidos (en "trailers-only" el estado viene en las cabeceras).
+async fn call(app: &Router, method: &str, message: wire::Encoder) -> (HeaderMap, Bytes) {
+    let request = Request::post(format!("/manifestor.v1.Manifestor/{}", method))
+        .header("content-type", "application/grpc")
+        .body(Body::from(wire::frame(&message.finish())))
+        .unwrap();
+    let response = send(app, request).await;
+    let mut headers = response.headers().clone();
+    let collected = response.into_body().collect().await.unwrap();
+    if let Some(trailers) = collected.trailers() {
+        headers.extend(trailers.clone());
+    }
+    let body = collected.to_bytes();
+    let message = if body.is_empty() { body } else { Bytes::from(wire::unframe(&body).unwrap().to_vec()) };
+    (headers, message)
+}
+
+fn status(headers: &HeaderMap) -> &str {
+    headers["grpc-status"].to_str().unwrap()
+}
+
+fn strings(message: &[u8], field: u32) -> Vec<String> {
+    wire::length_delimited_fields(message)
+        .unwrap()
+        .into_iter()
+        .filter(|(n, _)| *n == field)
+        .map(|(_, value)| String::from_utf8(value.to_vec()).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn get_version_returns_normalized_json() {
+    let app = grpc::router(state().0);
+    let mut request = wire::Encoder::new();
+    request.string(1, "1.16.5");
+
+    let (headers, message) = call(&app, "GetVersion", request).await;
+    assert_eq!(status(&headers), "0");
+    assert_eq!(strings(&message, 1), ["1.16.5"]);
+    let fields = wire::length_delimited_fields(&message).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(fields.iter().find(|(n, _)| *n == 2).unwrap().1).unwrap();
+    assert_eq!(json["id"], "1.16.5");
+}
+
+#[tokio::test]
+async fn download_plan_lists_platform_files() {
+    let app = grpc::router(state().0);
+    let mut request = wire::Encoder::new();
+    request.string(1, "1.20.1").string(2, "linux-x64");
+
+    let (headers, message) = call(&app, "GetDownloadPlan", request).await;
+    assert_eq!(status(&headers), "0");
+    let fields = wire::length_delimited_fields(&message).unwrap();
+    let paths: Vec<String> = fields
+        .iter()
+        .filter(|(n, _)| *n == 3)
+        .flat_map(|(_, item)| strings(item, 2))
+        .collect();
+    assert!(paths.contains(&"versions/1.20.1/1.20.1.jar".to_string()));
+    assert!(paths.contains(&"assets/indexes/5.json".to_string()));
+    assert!(!paths.iter().any(|p| p.contains("natives-windows")));
+}
+
+#[tokio::test]
+async fn errors_map_to_grpc_status() {
+    let app = grpc::router(state().0);
+
+    let mut request = wire::Encoder::new();
+    request.string(1, "no-existe");
+    let (headers, _) = call(&app, "GetVersion", request).await;
+    assert_eq!(status(&headers), "5");
+
+    let mut request = wire::Encoder::new();
+    request.string(1, "1.20.1").string(2, "beos-x64");
+    let (headers, _) = call(&app, "GetDownloadPlan", request).await;
+    assert_eq!(status(&headers), "3");
+    assert!(headers["grpc-message"].to_str().unwrap().contains("Sistema operativo desconocido"));
+
+    let (headers, _) = call(&app, "Nope", wire::Encoder::new()).await;
+    assert_eq!(status(&headers), "12");
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use axum::http::StatusCode;
+use common::{app_with, body_text, get, json, settings};
+use serde_json::Value;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+// Responde 200 a todo y cuenta las peticiones.
+async fn counting_upstream() -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut buf = [0; 1024];
+            let _ = stream.read(&mut buf).await;
+            counter.fetch_add(1, Ordering::SeqCst);
+            let response = "HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    });
+    (format!("http://{}/manifest.json", addr), requests)
+}
+
+#[tokio::test]
+async fn readiness_probe_is_reused_within_its_ttl() {
+    let (url, requests) = counting_upstream().await;
+    let mut settings = settings();
+    settings.upstream.manifest_url = url;
+    settings.upstream.ready_probe_ttl_secs = 60;
+    let (app, _) = app_with(settings);
+
+    for _ in 0..3 {
+        let readiness = json(get(&app, "/readyz").await, StatusCode::OK).await;
+        assert_eq!(readiness["upstream_reachable"], true);
+    }
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn open_breaker_skips_the_probe() {
+    let (url, requests) = counting_upstream().await;
+    let mut settings = settings();
+    settings.upstream.manifest_url = url;
+    settings.upstream.breaker_failure_threshold = 1;
+    let (app, _) = app_with(settings);
+
+    // `1.0` está en el manifest pero no tiene fixture: falla upstream.
+    assert_eq!(get(&app, "/version/1.0").await.status(), StatusCode::BAD_GATEWAY);
+
+    // El manifest de otros tests puede estar en caché; solo importa el sondeo.
+    let readiness: Value = serde_json::from_str(&body_text(get(&app, "/readyz").await).await).unwrap();
+    assert_eq!(readiness["upstream_reachable"], false);
+    assert_eq!(requests.load(Ordering::SeqCst), 0);
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use common::{app, app_with, get, json, send, settings, ADMIN_TOKEN};
+
+fn history_app() -> (axum::Router, std::sync::Arc<common::FixtureSource>) {
+    let mut settings = settings();
+    settings.history.enabled = true;
+    app_with(settings)
+}
+
+#[tokio::test]
+async fn history_is_disabled_by_default() {
+    let (app, _) = app();
+    assert_eq!(get(&app, "/version/1.7.10/history").await.status(), StatusCode::NOT_FOUND);
+    assert_eq!(get(&app, "/manifest?as_of=2030-01-01").await.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn refetching_the_same_version_keeps_one_record() {
+    let (app, source) = history_app();
+    assert_eq!(get(&app, "/version/1.16.5/history").await.status(), StatusCode::NOT_FOUND);
+
+    json(get(&app, "/version/1.16.5").await, StatusCode::OK).await;
+    let purge = Request::delete("/cache/version/1.16.5")
+        .header(header::AUTHORIZATION, format!("Bearer {}", ADMIN_TOKEN))
+        .body(Body::empty())
+        .unwrap();
+    assert_eq!(send(&app, purge).await.status(), StatusCode::NO_CONTENT);
+    json(get(&app, "/version/1.16.5").await, StatusCode::OK).await;
+    assert_eq!(source.version_calls(), 2);
+
+    let history = json(get(&app, "/version/1.16.5/history").await, StatusCode::OK).await;
+    let records = history["records"].as_array().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["sha1"], "b3756bc66c5aad32d104fd909a323859600681a8");
+    assert!(records[0]["fetched_at"].as_str().unwrap().ends_with('Z'));
+}
+
+#[tokio::test]
+async fn as_of_serves_the_recorded_copy() {
+    let (app, _) = history_app();
+    let current = json(get(&app, "/version/1.12.2").await, StatusCode::OK).await;
+
+    let snapshot = json(get(&app, "/version/1.12.2?as_of=2100-01-01T00:00:00Z").await, StatusCode::OK).await;
+    assert_eq!(snapshot, current);
+    assert_eq!(get(&app, "/version/1.12.2?as_of=1970-01-02").await.status(), StatusCode::NOT_FOUND);
+    assert_eq!(get(&app, "/version/1.12.2?as_of=ayer").await.status(), StatusCode::BAD_REQUEST);
+
+    let manifest = json(get(&app, "/manifest?as_of=4102444800&type=release&limit=1").await, StatusCode::OK).await;
+    assert_eq!(manifest["versions"][0]["id"], "1.20.1");
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{app, get, json};
+
+#[tokio::test]
+async fn recommends_the_runtime_for_the_platform() {
+    let (app, _) = app();
+    let java = json(get(&app, "/version/1.20.1/java?platform=windows-x64&files=true").await, StatusCode::OK).await;
+
+    assert_eq!(java["component"], "java-runtime-gamma");
+    assert_eq!(java["major_version"], 17);
+    assert_eq!(java["platform"], "windows-x64");
+    assert_eq!(java["runtime"]["name"], "17.0.8");
+    assert_eq!(java["manifest"]["sha1"], "e11e2c4ead8ba9e1e4d7ba8d9bd4a0f2e1b1c7a4");
+
+    let files = java["files"].as_array().unwrap();
+    let exe = files.iter().find(|f| f["path"] == "bin/java.exe").unwrap();
+    assert_eq!(exe["executable"], true);
+    assert_eq!(exe["download"]["sha1"], "2ec02fd6ab7e4e8d5c0b4cb76b0ee3ac29b1e8d2");
+    let link = files.iter().find(|f| f["type"] == "link").unwrap();
+    assert_eq!(link["target"], "../../LICENSE");
+}
+
+#[tokio::test]
+async fn legacy_versions_use_jre_legacy_without_files_by_default() {
+    let (app, _) = app();
+    let java = json(get(&app, "/version/1.12.2/java?platform=linux-x64").await, StatusCode::OK).await;
+    assert_eq!(java["component"], "jre-legacy");
+    assert_eq!(java["major_version"], 8);
+    assert_eq!(java["platform"], "linux");
+    assert!(java.get("files").is_none());
+
+    // Mojang no publica jre-legacy para Apple Silicon.
+    assert_eq!(get(&app, "/version/1.12.2/java?platform=osx-arm64").await.status(), StatusCode::NOT_FOUND);
+    assert_eq!(get(&app, "/version/1.12.2/java").await.status(), StatusCode::BAD_REQUEST);
+}
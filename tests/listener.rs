@@ -0,0 +1,48 @@
+mod common;
+
+use std::net::{SocketAddr, TcpListener as StdListener};
+
+use common::{settings, state};
+use manifestor::{api, listener};
+use tokio_util::sync::CancellationToken;
+
+// Un puerto libre en IPv4; lo que importa es poder repetirlo en IPv6.
+fn free_port() -> u16 {
+    StdListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
+
+#[tokio::test]
+async fn ipv4_and_ipv6_listeners_share_a_port() {
+    let port = free_port();
+    let v4 = listener::bind(SocketAddr::from(([127, 0, 0, 1], port))).unwrap();
+    let v6 = listener::bind(SocketAddr::from(([0, 0, 0, 0, 0, 0, 0, 1], port))).unwrap();
+    assert_eq!(v4.local_addr().unwrap().port(), v6.local_addr().unwrap().port());
+
+    assert!(listener::bind(v4.local_addr().unwrap()).is_err());
+}
+
+#[tokio::test]
+async fn every_listen_addr_serves_the_api_until_shutdown() {
+    let port = free_port();
+    let mut settings = settings();
+    settings.server.listen_addr = SocketAddr::from(([127, 0, 0, 1], port));
+    settings.server.listen_addrs = vec![
+        SocketAddr::from(([0, 0, 0, 0, 0, 0, 0, 1], port)),
+        settings.server.listen_addr,
+    ];
+    assert_eq!(listener::http_addrs(&settings.server).len(), 2);
+
+    let token = CancellationToken::new();
+    let app = api::create_router(state().0);
+    let server = tokio::spawn(listener::serve(&settings.server, app, token.clone()).unwrap());
+
+    let client = reqwest::Client::new();
+    for host in ["127.0.0.1", "[::1]"] {
+        let url = format!("http://{}:{}/version/1.20.1", host, port);
+        let version: serde_json::Value = client.get(&url).send().await.unwrap().json().await.unwrap();
+        assert_eq!(version["id"], "1.20.1");
+    }
+
+    token.cancel();
+    server.await.unwrap().unwrap();
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use axum::http::StatusCode;
+use common::{app_with, json, post_json, settings};
+use manifestor::maven::in_repositories;
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+#[test]
+fn checksums_are_only_fetched_from_configured_repositories() {
+    let repositories = ["https://libraries.minecraft.net/".to_string(), "https://maven.neoforged.net/releases".to_string()];
+    let allowed = |url: &str| in_repositories(url, &repositories);
+
+    assert!(allowed("https://libraries.minecraft.net/com/mojang/brigadier/1.0.18/brigadier-1.0.18.jar"));
+    assert!(allowed("https://LIBRARIES.minecraft.net:443/org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1.jar"));
+    assert!(allowed("https://maven.neoforged.net/releases/net/neoforged/fml/1.0/fml-1.0.jar"));
+
+    // Hosts, puertos, esquemas y rutas que solo se parecen.
+    assert!(!allowed("https://libraries.minecraft.net.attacker.example/com/evil/1.0/evil-1.0.jar"));
+    assert!(!allowed("https://libraries.minecraft.net@attacker.example/com/evil/1.0/evil-1.0.jar"));
+    assert!(!allowed("https://libraries.minecraft.net:8443/com/evil/1.0/evil-1.0.jar"));
+    assert!(!allowed("http://libraries.minecraft.net/com/evil/1.0/evil-1.0.jar"));
+    assert!(!allowed("https://maven.neoforged.net/releases-evil/net/evil/1.0/evil-1.0.jar"));
+    assert!(!allowed("no es una url"));
+}
+
+// Responde a todo con el mismo sha1 y cuenta las peticiones.
+async fn checksum_repository() -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut buf = [0; 1024];
+            let _ = stream.read(&mut buf).await;
+            counter.fetch_add(1, Ordering::SeqCst);
+            let response = format!("HTTP/1.1 200 OK\r\ncontent-length: 40\r\nconnection: close\r\n\r\n{}", SHA1);
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    });
+    (format!("http://{}/maven/", addr), requests)
+}
+
+const SHA1: &str = "0123456789abcdef0123456789abcdef01234567";
+
+#[tokio::test]
+async fn checksum_lookups_are_capped_per_version() {
+    let (repository, requests) = checksum_repository().await;
+    let mut settings = settings();
+    settings.maven.repositories = vec![repository.clone()];
+    settings.maven.fill_checksums = true;
+    settings.maven.max_checksum_lookups = 3;
+    let (app, _) = app_with(settings);
+
+    let libraries: Vec<Value> = (0..50)
+        .map(|i| json!({ "name": format!("com.example:capped{}:1.0", i), "url": repository }))
+        .collect();
+    let raw = json!({ "id": "muchas-librerias", "type": "release", "mainClass": "Main", "libraries": libraries });
+    let version = json(post_json(&app, "/normalize", raw).await, StatusCode::OK).await;
+
+    let filled = version["libraries"].as_array().unwrap().iter().filter(|l| l["sha1"] == SHA1).count();
+    assert_eq!(filled, 3);
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,157 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{settings, state_with};
+use manifestor::{
+    notify::{self, signed_payload},
+    types::{MinecraftVersion, VersionManifest},
+};
+use ring::hmac;
+use serde_json::Value;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+    sync::mpsc,
+};
+
+const SECRET: &str = "webhook-secret";
+
+struct Delivery {
+    // Cabecera en minúsculas.
+    head: String,
+    body: Vec<u8>,
+}
+
+impl Delivery {
+    fn header(&self, name: &str) -> Option<&str> {
+        let prefix = format!("{}: ", name);
+        self.head.lines().find_map(|line| line.strip_prefix(&prefix))
+    }
+}
+
+// Responde a cada POST con la siguiente respuesta de la lista (la última se
+// repite) y manda lo recibido por el canal.
+async fn webhook_server(responses: &'static [&'static str]) -> (String, mpsc::UnboundedReceiver<Delivery>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (sender, received) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        for n in 0.. {
+            let Ok((mut stream, _)) = listener.accept().await else {
+                return;
+            };
+            let mut data = vec![];
+            let mut buf = [0; 4096];
+            let (head, body_len) = loop {
+                let read = stream.read(&mut buf).await.unwrap();
+                data.extend_from_slice(&buf[..read]);
+                if let Some(end) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+                    let head = String::from_utf8_lossy(&data[..end]).to_lowercase();
+                    let len = head
+                        .lines()
+                        .find_map(|l| l.strip_prefix("content-length: "))
+                        .and_then(|v| v.parse::<usize>().ok())
+                        .unwrap_or(0);
+                    data.drain(..end + 4);
+                    break (head, len);
+                }
+            };
+            while data.len() < body_len {
+                let read = stream.read(&mut buf).await.unwrap();
+                data.extend_from_slice(&buf[..read]);
+            }
+            let response = responses[n.min(responses.len() - 1)];
+            stream.write_all(response.as_bytes()).await.unwrap();
+            let _ = sender.send(Delivery { head, body: data });
+        }
+    });
+    (format!("http://{}/hook", addr), received)
+}
+
+const OK: &str = "HTTP/1.1 204 No Content\r\nconnection: close\r\n\r\n";
+const RATE_LIMITED: &str = "HTTP/1.1 429 Too Many Requests\r\nretry-after: 0\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+const DOWN: &str = "HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+
+fn manifest(ids: &[&str]) -> VersionManifest {
+    VersionManifest {
+        latest_release: ids[0].to_string(),
+        latest_snapshot: ids[0].to_string(),
+        versions: ids
+            .iter()
+            .map(|id| MinecraftVersion {
+                id: id.to_string(),
+                hash: String::new(),
+                release_time: "2024-06-13T08:24:03+00:00".to_string(),
+                url: String::new(),
+                version_type: "release".to_string(),
+            })
+            .collect(),
+    }
+}
+
+async fn next(received: &mut mpsc::UnboundedReceiver<Delivery>) -> Delivery {
+    tokio::time::timeout(Duration::from_secs(5), received.recv()).await.expect("webhook recibido").unwrap()
+}
+
+#[tokio::test]
+async fn deliveries_are_signed_formatted_and_retried() {
+    let (json_url, mut json_received) = webhook_server(&[RATE_LIMITED, OK]).await;
+    let (discord_url, mut discord_received) = webhook_server(&[OK]).await;
+    let mut settings = settings();
+    settings.notify.webhooks = vec![json_url, format!("discord={}", discord_url)];
+    settings.notify.secret = Some(SECRET.to_string());
+    settings.notify.max_retries = 2;
+    let (state, _) = state_with(settings);
+    let notifier = notify::spawn(state.clone());
+
+    state.events.observe(&manifest(&["1.20.6"]));
+    state.events.observe(&manifest(&["1.21", "1.20.6"]));
+
+    // El 429 con `Retry-After: 0` se reintenta enseguida.
+    let limited = next(&mut json_received).await;
+    let delivery = next(&mut json_received).await;
+    assert_eq!(limited.body, delivery.body);
+    let event: Value = serde_json::from_slice(&delivery.body).unwrap();
+    assert_eq!(event["event"], "version_added");
+    assert_eq!(event["id"], "1.21");
+
+    // La firma cubre la hora y el cuerpo; con otra hora no vale.
+    let timestamp: u64 = delivery.header("x-manifestor-timestamp").unwrap().parse().unwrap();
+    let signature = delivery.header("x-manifestor-signature").unwrap().strip_prefix("sha256=").unwrap();
+    let signature: Vec<u8> = (0..signature.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&signature[i..i + 2], 16).unwrap())
+        .collect();
+    let key = hmac::Key::new(hmac::HMAC_SHA256, SECRET.as_bytes());
+    assert!(hmac::verify(&key, &signed_payload(timestamp, &delivery.body), &signature).is_ok());
+    assert!(hmac::verify(&key, &signed_payload(timestamp - 60, &delivery.body), &signature).is_err());
+    assert!(hmac::verify(&key, &delivery.body, &signature).is_err());
+
+    let discord = next(&mut discord_received).await;
+    let message: Value = serde_json::from_slice(&discord.body).unwrap();
+    assert_eq!(message["username"], "manifestor");
+    assert_eq!(message["content"], "Nueva release de Minecraft: **1.21**");
+
+    state.shutdown.cancel();
+    notifier.await.unwrap();
+}
+
+#[tokio::test]
+async fn shutdown_abandons_retries_and_waits_for_the_notifier() {
+    let (url, mut received) = webhook_server(&[DOWN]).await;
+    let mut settings = settings();
+    settings.notify.webhooks = vec![url];
+    settings.notify.max_retries = 5;
+    let (state, _) = state_with(settings);
+    let notifier = notify::spawn(state.clone());
+
+    state.events.observe(&manifest(&["24w14a"]));
+    state.events.observe(&manifest(&["1.21", "24w14a"]));
+    next(&mut received).await;
+
+    // El primer reintento espera 1 s; el apagado no lo espera.
+    state.shutdown.cancel();
+    tokio::time::timeout(Duration::from_millis(500), notifier).await.expect("notifier terminado").unwrap();
+    assert!(received.try_recv().is_err());
+}
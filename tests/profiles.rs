@@ -0,0 +1,129 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use common::{app, app_with, get, json, post_json, send, settings, ADMIN_TOKEN};
+use serde_json::{json as json_value, Value};
+
+fn registry() -> (axum::Router, std::sync::Arc<common::FixtureSource>) {
+    let mut settings = settings();
+    settings.profiles.enabled = true;
+    app_with(settings)
+}
+
+fn upload(uri: &str, profile: &Value, token: Option<&str>) -> Request<Body> {
+    let mut request = Request::post(uri).header(header::CONTENT_TYPE, "application/json");
+    if let Some(token) = token {
+        request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+    }
+    request.body(Body::from(profile.to_string())).unwrap()
+}
+
+fn optifine() -> Value {
+    json_value!({
+        "id": "1.16.5-OptiFine_HD_U_G8",
+        "inheritsFrom": "1.16.5",
+        "mainClass": "net.minecraft.launchwrapper.Launch",
+        "libraries": [
+            { "name": "optifine:OptiFine:1.16.5_HD_U_G8" },
+            { "name": "optifine:launchwrapper-of:2.2" },
+        ],
+    })
+}
+
+fn library_names(version: &Value) -> Vec<&str> {
+    version["libraries"].as_array().unwrap().iter().map(|l| l["name"].as_str().unwrap()).collect()
+}
+
+#[tokio::test]
+async fn registry_is_disabled_by_default() {
+    let (app, _) = app();
+    assert_eq!(get(&app, "/profiles").await.status(), StatusCode::NOT_FOUND);
+    assert_eq!(get(&app, "/profiles/anything").await.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn uploaded_profiles_are_resolved_and_served() {
+    let (app, _) = registry();
+    let unauthorized = send(&app, upload("/profiles", &optifine(), None)).await;
+    assert_eq!(unauthorized.status(), StatusCode::UNAUTHORIZED);
+
+    let created = send(&app, upload("/profiles", &optifine(), Some(ADMIN_TOKEN))).await;
+    assert_eq!(created.headers()[header::LOCATION], "/profiles/1.16.5-OptiFine_HD_U_G8");
+    let summary = json(created, StatusCode::CREATED).await;
+    assert_eq!(summary["inherits_from"], "1.16.5");
+
+    let version = json(get(&app, "/profiles/1.16.5-OptiFine_HD_U_G8").await, StatusCode::OK).await;
+    assert_eq!(version["main_class"], "net.minecraft.launchwrapper.Launch");
+    let names = library_names(&version);
+    assert_eq!(names[0], "optifine:OptiFine:1.16.5_HD_U_G8");
+    assert!(names.iter().any(|n| n.starts_with("com.mojang:")));
+
+    // Un modpack puede heredar de otro perfil del registro.
+    let modpack = json_value!({
+        "id": "skyblock-pack",
+        "inheritsFrom": "1.16.5-OptiFine_HD_U_G8",
+        "libraries": [{ "name": "net.example:skyblock-core:1.0" }],
+    });
+    json(send(&app, upload("/profiles", &modpack, Some(ADMIN_TOKEN))).await, StatusCode::CREATED).await;
+    let pack = json(get(&app, "/profiles/skyblock-pack").await, StatusCode::OK).await;
+    assert_eq!(pack["main_class"], "net.minecraft.launchwrapper.Launch");
+    assert!(library_names(&pack).contains(&"optifine:OptiFine:1.16.5_HD_U_G8"));
+
+    let list = json(get(&app, "/profiles").await, StatusCode::OK).await;
+    let listed: Vec<&str> = list["profiles"].as_array().unwrap().iter().map(|p| p["name"].as_str().unwrap()).collect();
+    assert!(listed.contains(&"skyblock-pack") && listed.contains(&"1.16.5-OptiFine_HD_U_G8"));
+}
+
+#[tokio::test]
+async fn profiles_can_be_replaced_and_deleted() {
+    let (app, _) = registry();
+    let profile = json_value!({ "id": "1.12.2-custom", "inheritsFrom": "1.12.2" });
+    let uri = "/profiles?name=replaceable";
+    json(send(&app, upload(uri, &profile, Some(ADMIN_TOKEN))).await, StatusCode::CREATED).await;
+    json(send(&app, upload(uri, &profile, Some(ADMIN_TOKEN))).await, StatusCode::OK).await;
+
+    let bad_name = send(&app, upload("/profiles?name=../escape", &profile, Some(ADMIN_TOKEN))).await;
+    assert_eq!(bad_name.status(), StatusCode::BAD_REQUEST);
+    let missing_parent = json_value!({ "id": "orphan", "inheritsFrom": "9.9.9" });
+    let orphan = send(&app, upload("/profiles", &missing_parent, Some(ADMIN_TOKEN))).await;
+    assert_eq!(orphan.status(), StatusCode::NOT_FOUND);
+
+    let delete = Request::delete("/profiles/replaceable")
+        .header(header::AUTHORIZATION, format!("Bearer {}", ADMIN_TOKEN))
+        .body(Body::empty())
+        .unwrap();
+    assert_eq!(send(&app, delete).await.status(), StatusCode::NO_CONTENT);
+    assert_eq!(get(&app, "/profiles/replaceable").await.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn profiles_cannot_take_the_name_of_a_published_version() {
+    let mut settings = settings();
+    settings.profiles.enabled = true;
+    settings.experimental.catalogs = vec!["https://archive.example.org/catalogs/experimental.json".to_string()];
+    let (app, _) = app_with(settings);
+
+    let vanilla = json_value!({ "id": "1.20.1", "mainClass": "evil.Main" });
+    let taken = send(&app, upload("/profiles", &vanilla, Some(ADMIN_TOKEN))).await;
+    assert_eq!(taken.status(), StatusCode::CONFLICT);
+    let renamed = send(&app, upload("/profiles?name=1.16.5", &optifine(), Some(ADMIN_TOKEN))).await;
+    assert_eq!(renamed.status(), StatusCode::CONFLICT);
+    let uri = "/profiles?name=1.19_deep_dark_experimental_snapshot-1";
+    assert_eq!(send(&app, upload(uri, &optifine(), Some(ADMIN_TOKEN))).await.status(), StatusCode::CONFLICT);
+    assert_eq!(get(&app, "/profiles/1.20.1").await.status(), StatusCode::NOT_FOUND);
+
+    // Un perfil que ya estuviera guardado con el nombre de una versión de
+    // Mojang tampoco la sustituye como padre.
+    let shadow = json_value!({ "id": "shadow", "inheritsFrom": "1.12.2", "mainClass": "evil.Main" });
+    json(send(&app, upload("/profiles", &shadow, Some(ADMIN_TOKEN))).await, StatusCode::CREATED).await;
+    let dir = manifestor::cache::settings().dir.join("profiles");
+    std::fs::rename(dir.join("shadow.json"), dir.join("1.12.2.json")).unwrap();
+
+    let child = json_value!({ "id": "child", "inheritsFrom": "1.12.2" });
+    let resolved = json(post_json(&app, "/version/resolve", child).await, StatusCode::OK).await;
+    assert_eq!(resolved["main_class"], "net.minecraft.client.main.Main");
+    std::fs::remove_file(dir.join("1.12.2.json")).unwrap();
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    response::Response,
+    Router,
+};
+use common::{app_with, send, settings};
+use manifestor::config::RateLimit;
+
+// Los buckets son globales al proceso: cada test usa su propia IP.
+fn limited_app(limit: RateLimit) -> Router {
+    let mut settings = settings();
+    settings.rate_limit.trust_forwarded_for = true;
+    settings.rate_limit.api = limit;
+    app_with(settings).0
+}
+
+async fn get_from(app: &Router, uri: &str, forwarded_for: &str) -> Response {
+    let request = Request::get(uri).header("x-forwarded-for", forwarded_for).body(Body::empty()).unwrap();
+    send(app, request).await
+}
+
+#[tokio::test]
+async fn exhausted_bucket_is_too_many_requests_with_retry_after() {
+    let app = limited_app(RateLimit { per_sec: 0.25, burst: 2 });
+
+    for _ in 0..2 {
+        assert_eq!(get_from(&app, "/manifest", "10.0.0.1").await.status(), StatusCode::OK);
+    }
+    let limited = get_from(&app, "/manifest", "10.0.0.1").await;
+    assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(limited.headers()[header::RETRY_AFTER], "4");
+
+    // Otra IP tiene su propio bucket.
+    assert_eq!(get_from(&app, "/manifest", "10.0.0.2").await.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn health_endpoints_are_not_limited() {
+    let app = limited_app(RateLimit { per_sec: 0.1, burst: 1 });
+
+    assert_eq!(get_from(&app, "/manifest", "10.0.1.1").await.status(), StatusCode::OK);
+    assert_eq!(get_from(&app, "/manifest", "10.0.1.1").await.status(), StatusCode::TOO_MANY_REQUESTS);
+    for _ in 0..5 {
+        assert_eq!(get_from(&app, "/healthz", "10.0.1.1").await.status(), StatusCode::OK);
+    }
+}
+
+#[tokio::test]
+async fn bucket_refills_over_time() {
+    let app = limited_app(RateLimit { per_sec: 20.0, burst: 1 });
+
+    assert_eq!(get_from(&app, "/manifest", "10.0.2.1").await.status(), StatusCode::OK);
+    assert_eq!(get_from(&app, "/manifest", "10.0.2.1").await.status(), StatusCode::TOO_MANY_REQUESTS);
+    tokio::time::sleep(Duration::from_millis(80)).await;
+    assert_eq!(get_from(&app, "/manifest", "10.0.2.1").await.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn spoofed_forwarded_for_entries_do_not_get_a_new_bucket() {
+    let app = limited_app(RateLimit { per_sec: 0.1, burst: 1 });
+
+    // El proxy de confianza añade la IP real al final; lo de antes lo eligió el cliente.
+    assert_eq!(get_from(&app, "/manifest", "1.1.1.1, 10.0.3.1").await.status(), StatusCode::OK);
+    let spoofed = get_from(&app, "/manifest", "2.2.2.2, 10.0.3.1").await;
+    assert_eq!(spoofed.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn client_is_counted_from_the_right_past_trusted_hops() {
+    let mut settings = settings();
+    settings.rate_limit.trust_forwarded_for = true;
+    settings.rate_limit.trusted_proxy_hops = 2;
+    settings.rate_limit.api = RateLimit { per_sec: 0.1, burst: 1 };
+    let app = app_with(settings).0;
+
+    assert_eq!(get_from(&app, "/manifest", "9.9.9.9, 10.0.4.1, 172.16.0.1").await.status(), StatusCode::OK);
+    let same_client = get_from(&app, "/manifest", "8.8.8.8, 10.0.4.1, 172.16.0.2").await;
+    assert_eq!(same_client.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(get_from(&app, "/manifest", "10.0.4.2, 172.16.0.1").await.status(), StatusCode::OK);
+}
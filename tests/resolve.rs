@@ -0,0 +1,37 @@
+// Binario aparte: necesita un proceso que nunca haya tenido manifest, ni en
+// caché ni en disco.
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use common::{app_with, post_json, send, settings, ADMIN_TOKEN};
+use serde_json::json;
+
+#[tokio::test]
+async fn without_a_manifest_profiles_do_not_stand_in_for_parents() {
+    let mut settings = settings();
+    settings.profiles.enabled = true;
+    let (app, source) = app_with(settings);
+    source.fail(true);
+
+    // Un perfil guardado con el nombre de una versión de Mojang.
+    let raw = json!({ "id": "1.20.1", "mainClass": "evil.Main" });
+    let version = manifestor_core::parse_version_json(&raw).unwrap();
+    let stored = json!({ "name": "1.20.1", "uploaded_at": 0, "raw": raw, "etag": "\"x\"", "version": version });
+    let dir = manifestor::cache::settings().dir.join("profiles");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("1.20.1.json"), stored.to_string()).unwrap();
+
+    let child = json!({ "id": "child", "inheritsFrom": "1.20.1" });
+    let response = post_json(&app, "/version/resolve", child.clone()).await;
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let upload = Request::post("/profiles?name=child")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, format!("Bearer {}", ADMIN_TOKEN))
+        .body(Body::from(child.to_string()))
+        .unwrap();
+    assert_eq!(send(&app, upload).await.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use common::{app, get, json, send};
+
+fn accept(uri: &str, media: &str) -> Request<Body> {
+    Request::get(uri).header(header::ACCEPT, media).body(Body::empty()).unwrap()
+}
+
+#[tokio::test]
+async fn v1_is_served_without_vendor_type() {
+    let (app, _) = app();
+    let response = get(&app, "/version/1.12.2").await;
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+    let version = json(response, StatusCode::OK).await;
+    assert!(version["client_jar"].is_object());
+    assert!(version["requires_extraction"].is_array());
+}
+
+#[tokio::test]
+async fn v2_moves_jars_and_extraction_flags() {
+    let (app, _) = app();
+    let v1 = json(get(&app, "/version/1.7.10").await, StatusCode::OK).await;
+
+    let response = send(&app, accept("/version/1.7.10", "application/vnd.manifestor.v2+json")).await;
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/vnd.manifestor.v2+json");
+    let v2 = json(response, StatusCode::OK).await;
+
+    assert_eq!(v2["downloads"]["client"], v1["client_jar"]);
+    assert!(v2.get("client_jar").is_none());
+    assert!(v2.get("requires_extraction").is_none());
+    let natives = v2["natives"].as_array().unwrap();
+    assert!(!natives.is_empty());
+    assert!(natives.iter().all(|n| n["extract"].is_boolean()));
+}
+
+#[tokio::test]
+async fn unknown_schema_is_not_acceptable() {
+    let (app, _) = app();
+    let response = send(&app, accept("/version/1.20.1", "application/vnd.manifestor.v9+json")).await;
+    assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+
+    // Con varias opciones se elige la más alta conocida.
+    let media = "application/vnd.manifestor.v9+json, application/vnd.manifestor.v1+json;q=0.5";
+    let response = send(&app, accept("/version/1.20.1", media)).await;
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/vnd.manifestor.v1+json");
+}
+
+#[test]
+fn q_values_rank_and_exclude_versions() {
+    use manifestor::schema::{negotiate, SchemaVersion};
+    let version = |accept: &str| negotiate(std::iter::once(accept)).map(|schema| schema.version);
+
+    // q=0 es "no aceptable", aunque sea la versión más alta.
+    let both = "application/vnd.manifestor.v2+json;q=0, application/vnd.manifestor.v1+json";
+    assert_eq!(version(both), Ok(SchemaVersion::V1));
+    assert_eq!(version("application/vnd.manifestor.v1+json;q=0.4, application/vnd.manifestor.v2+json;q=0.2"), Ok(SchemaVersion::V1));
+    assert_eq!(version("application/vnd.manifestor.v1+json;q=0.5, application/vnd.manifestor.v2+json;q=0.5"), Ok(SchemaVersion::V2));
+    assert_eq!(version("application/vnd.manifestor.v2+json; Q=0.0"), Ok(SchemaVersion::V1));
+    assert_eq!(version("application/vnd.manifestor.v9+json, application/vnd.manifestor.v2+json;q=0").unwrap_err().0, StatusCode::NOT_ACCEPTABLE);
+}
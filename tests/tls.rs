@@ -0,0 +1,114 @@
+#![cfg(feature = "tls")]
+
+mod common;
+
+use std::{
+    net::{SocketAddr, TcpListener as StdListener},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use common::{settings, state};
+use manifestor::{api, listener, upstream::UpstreamClient};
+use tokio_util::sync::CancellationToken;
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/tls").join(name)
+}
+
+// Copia los archivos a un directorio propio para poder sustituirlos.
+fn serve_tls(test: &str) -> (SocketAddr, PathBuf, CancellationToken) {
+    let dir = std::env::temp_dir().join(format!("manifestor-tls-{}-{}", test, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy(fixture("localhost.crt"), dir.join("server.crt")).unwrap();
+    std::fs::copy(fixture("localhost.key"), dir.join("server.key")).unwrap();
+
+    let port = StdListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let mut settings = settings();
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    settings.server.listen_addr = SocketAddr::from(([127, 0, 0, 1], 0));
+    settings.server.tls.listen_addrs = vec![addr];
+    settings.server.tls.cert_file = Some(dir.join("server.crt"));
+    settings.server.tls.key_file = Some(dir.join("server.key"));
+
+    let token = CancellationToken::new();
+    let app = api::create_router(state().0);
+    tokio::spawn(listener::serve(&settings.server, app, token.clone()).unwrap());
+    (addr, dir, token)
+}
+
+// Cliente que solo confía en el certificado `name`.
+fn client(addr: SocketAddr, name: &str) -> reqwest::Client {
+    let cert = reqwest::Certificate::from_pem(&std::fs::read(fixture(name)).unwrap()).unwrap();
+    reqwest::Client::builder()
+        .tls_built_in_root_certs(false)
+        .add_root_certificate(cert)
+        .resolve("localhost", addr)
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn serves_the_api_over_https() {
+    let (addr, _, token) = serve_tls("serve");
+    let url = format!("https://localhost:{}/version/1.20.1", addr.port());
+
+    let response = client(addr, "localhost.crt").get(&url).send().await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let version: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(version["id"], "1.20.1");
+
+    assert!(client(addr, "renewed.crt").get(&url).send().await.is_err());
+    token.cancel();
+}
+
+#[tokio::test]
+async fn certificate_is_reloaded_on_sighup() {
+    let (addr, dir, token) = serve_tls("reload");
+    let url = format!("https://localhost:{}/healthz", addr.port());
+    assert!(client(addr, "localhost.crt").get(&url).send().await.is_ok());
+
+    // Un certificado roto no sustituye al que se está sirviendo.
+    std::fs::write(dir.join("server.crt"), "no es un certificado").unwrap();
+    hangup();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(client(addr, "localhost.crt").get(&url).send().await.is_ok());
+
+    std::fs::copy(fixture("renewed.crt"), dir.join("server.crt")).unwrap();
+    std::fs::copy(fixture("renewed.key"), dir.join("server.key")).unwrap();
+    hangup();
+    let mut renewed = false;
+    for _ in 0..50 {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        if client(addr, "renewed.crt").get(&url).send().await.is_ok() {
+            renewed = true;
+            break;
+        }
+    }
+    assert!(renewed);
+    token.cancel();
+}
+
+#[tokio::test]
+async fn upstream_client_trusts_the_configured_ca() {
+    let (addr, _, token) = serve_tls("upstream");
+    let url = format!("https://localhost:{}/healthz", addr.port());
+
+    let default = UpstreamClient::new(&settings().upstream).unwrap();
+    assert!(default.client().get(&url).send().await.is_err());
+
+    let mut upstream = settings().upstream;
+    upstream.tls.ca_file = Some(fixture("localhost.crt"));
+    upstream.tls.system_roots = false;
+    let custom = UpstreamClient::new(&upstream).unwrap();
+    assert!(custom.client().get(&url).send().await.unwrap().status().is_success());
+    token.cancel();
+}
+
+fn hangup() {
+    let status = std::process::Command::new("kill")
+        .args(["-HUP", &std::process::id().to_string()])
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
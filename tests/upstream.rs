@@ -0,0 +1,101 @@
+mod common;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use common::settings;
+use manifestor::{config::Settings, upstream::UpstreamClient};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+    sync::oneshot,
+};
+
+// Responde `{}` a una sola petición y devuelve su cabecera, en minúsculas.
+async fn recorder() -> (String, oneshot::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (sender, received) = oneshot::channel();
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut head = vec![];
+        let mut buf = [0; 1024];
+        while !head.ends_with(b"\r\n\r\n") {
+            let n = stream.read(&mut buf).await.unwrap();
+            head.extend_from_slice(&buf[..n]);
+        }
+        let response = "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 2\r\nconnection: close\r\n\r\n{}";
+        stream.write_all(response.as_bytes()).await.unwrap();
+        let _ = sender.send(String::from_utf8_lossy(&head).to_lowercase());
+    });
+    (format!("http://{}", addr), received)
+}
+
+#[tokio::test]
+async fn requests_go_through_the_configured_proxy_with_credentials() {
+    let (proxy_url, received) = recorder().await;
+    let mut settings = settings().upstream;
+    settings.proxy.url = Some(proxy_url);
+    settings.proxy.username = Some("colegio".to_string());
+    settings.proxy.password = Some("s3creto".to_string());
+    let upstream = UpstreamClient::new(&settings).unwrap();
+
+    let url = "http://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+    let response = upstream.client().get(url).send().await.unwrap();
+    assert!(response.status().is_success());
+
+    let head = received.await.unwrap();
+    assert!(head.starts_with(&format!("get {} http/1.1\r\n", url)));
+    let credentials = STANDARD.encode("colegio:s3creto").to_lowercase();
+    assert!(head.contains(&format!("proxy-authorization: basic {}\r\n", credentials)));
+}
+
+#[tokio::test]
+async fn no_proxy_hosts_are_reached_directly() {
+    let (server_url, received) = recorder().await;
+    let mut settings = settings().upstream;
+    // Nada escucha aquí: si se usara el proxy, la petición fallaría.
+    settings.proxy.url = Some("http://127.0.0.1:9".to_string());
+    settings.proxy.no_proxy = vec!["127.0.0.1".to_string()];
+    let upstream = UpstreamClient::new(&settings).unwrap();
+
+    upstream.client().get(format!("{}/directo", server_url)).send().await.unwrap();
+    assert!(received.await.unwrap().starts_with("get /directo http/1.1\r\n"));
+}
+
+#[tokio::test]
+async fn invalid_network_settings_fail_at_startup() {
+    let error = |configure: fn(&mut manifestor::config::UpstreamSettings)| {
+        let mut settings = settings().upstream;
+        configure(&mut settings);
+        UpstreamClient::new(&settings).err().unwrap()
+    };
+
+    assert!(error(|s| s.tls.ca_file = Some("/no/existe/ca.pem".into())).contains("/no/existe/ca.pem"));
+    assert!(error(|s| s.tls.system_roots = false).contains("ca_file"));
+    assert!(error(|s| s.tls.min_version = Some("1.4".to_string())).contains("1.4"));
+    assert!(error(|s| s.tls.min_version = Some("1.3".to_string())).contains("1.3"));
+    assert!(error(|s| s.proxy.url = Some("no es una url".to_string())).contains("upstream.proxy.url"));
+}
+
+fn from_env(vars: &[(&str, &str)]) -> Result<UpstreamClient, String> {
+    let settings = Settings::load_from(|var| vars.iter().find(|(name, _)| *name == var).map(|(_, v)| v.to_string()))?;
+    UpstreamClient::new(&settings.upstream)
+}
+
+#[tokio::test]
+async fn network_settings_can_come_from_the_environment() {
+    let (server_url, received) = recorder().await;
+    let upstream = from_env(&[
+        ("UPSTREAM_PROXY_URL", "http://127.0.0.1:9"),
+        ("UPSTREAM_NO_PROXY", "localhost, 127.0.0.1"),
+        ("UPSTREAM_MIN_TLS_VERSION", "1.2"),
+        ("UPSTREAM_SYSTEM_ROOTS", "true"),
+    ])
+    .unwrap();
+    upstream.client().get(format!("{}/env", server_url)).send().await.unwrap();
+    assert!(received.await.unwrap().starts_with("get /env http/1.1\r\n"));
+
+    let error = |vars: &[(&str, &str)]| from_env(vars).err().unwrap();
+    assert!(error(&[("UPSTREAM_MIN_TLS_VERSION", "1.3")]).contains("1.3"));
+    assert!(error(&[("UPSTREAM_SYSTEM_ROOTS", "false")]).contains("ca_file"));
+    assert!(error(&[("UPSTREAM_CA_FILE", "/no/existe/ca.pem")]).contains("/no/existe/ca.pem"));
+}
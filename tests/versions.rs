@@ -0,0 +1,195 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{app, get, json, post_json};
+use serde_json::{json as json_value, Value};
+
+fn names(values: &Value, key: &str) -> Vec<String> {
+    values
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v[key].as_str().unwrap().to_string())
+        .collect()
+}
+
+fn strings(value: &Value) -> Vec<&str> {
+    value.as_array().unwrap().iter().map(|v| v.as_str().unwrap()).collect()
+}
+
+#[tokio::test]
+async fn manifest_lists_fixture_versions() {
+    let (app, _) = app();
+    let manifest = json(get(&app, "/manifest").await, StatusCode::OK).await;
+
+    assert_eq!(manifest["latest_release"], "1.20.1");
+    assert_eq!(manifest["latest_snapshot"], "23w31a");
+    assert_eq!(names(&manifest["versions"], "id")[..3], ["23w31a", "1.20.1", "1.16.5"]);
+}
+
+#[tokio::test]
+async fn normalizes_legacy_arguments_and_natives() {
+    let (app, _) = app();
+    let version = json(get(&app, "/version/1.7.10").await, StatusCode::OK).await;
+
+    assert_eq!(version["java_version"], 8);
+    assert_eq!(version["asset_index"]["id"], "1.7.10");
+    assert_eq!(version["legacy"], false);
+    assert!(strings(&version["arguments"]["game"]).contains(&"${auth_access_token}"));
+    // Sin `arguments.jvm`, solo lo que trae `minecraftArguments`.
+    assert!(version["arguments"]["jvm"].as_array().unwrap().is_empty());
+    let classifiers = names(&version["natives"], "classifier");
+    for os in ["natives-linux", "natives-osx", "natives-windows"] {
+        assert!(classifiers.contains(&os.to_string()), "{:?}", classifiers);
+    }
+}
+
+#[tokio::test]
+async fn normalizes_logging_and_version_type_argument() {
+    let (app, _) = app();
+    let version = json(get(&app, "/version/1.12.2").await, StatusCode::OK).await;
+
+    assert_eq!(version["logging"]["file"]["id"], "client-1.12.xml");
+    assert_eq!(version["logging"]["argument"], "-Dlog4j.configurationFile=${path}");
+    assert!(strings(&version["arguments"]["game"]).contains(&"${version_type}"));
+}
+
+#[tokio::test]
+async fn normalizes_modern_and_snapshot_versions() {
+    let (app, _) = app();
+
+    let release = json(get(&app, "/version/1.20.1").await, StatusCode::OK).await;
+    assert_eq!(release["java_version"], 17);
+    assert_eq!(release["main_class"], "net.minecraft.client.main.Main");
+    assert!(release["natives"].as_array().unwrap().is_empty());
+    assert!(names(&release["libraries"], "name").contains(&"org.lwjgl:lwjgl:3.3.1:natives-macos-arm64".to_string()));
+
+    let snapshot = json(get(&app, "/version/23w31a").await, StatusCode::OK).await;
+    assert_eq!(snapshot["type"], "snapshot");
+    assert_eq!(snapshot["asset_index"]["id"], "7");
+}
+
+#[tokio::test]
+async fn bundle_applies_os_rules_to_arguments() {
+    let (app, _) = app();
+
+    let osx = json(get(&app, "/version/1.16.5/bundle/osx-x64").await, StatusCode::OK).await;
+    let jvm = strings(&osx["arguments"]["jvm"]);
+    assert!(jvm.contains(&"-XstartOnFirstThread"));
+    assert!(!jvm.iter().any(|arg| arg.starts_with("-XX:HeapDumpPath")));
+    assert!(!jvm.contains(&"-Xss1M"));
+
+    let windows = json(get(&app, "/version/1.16.5/bundle/windows-x86").await, StatusCode::OK).await;
+    let jvm = strings(&windows["arguments"]["jvm"]);
+    assert!(jvm.contains(&"-Xss1M"));
+    assert!(jvm.contains(&"-Dos.name=Windows 10"));
+    assert!(!jvm.contains(&"-XstartOnFirstThread"));
+    assert_eq!(windows["java_runtime"]["platform"], "windows-x86");
+    assert_eq!(windows["java_runtime"]["component"], "jre-legacy");
+}
+
+#[tokio::test]
+async fn bundle_drops_feature_gated_arguments() {
+    let (app, _) = app();
+    let bundle = json(get(&app, "/version/1.20.1/bundle/linux-x64").await, StatusCode::OK).await;
+    let game = strings(&bundle["arguments"]["game"]);
+
+    assert!(!game.contains(&"--demo"));
+    assert!(!game.contains(&"--width"));
+    assert!(!game.contains(&"--quickPlayPath"));
+    assert!(!bundle["placeholders"].as_array().unwrap().iter().any(|p| p == "resolution_width"));
+}
+
+#[tokio::test]
+async fn bundle_picks_libraries_and_natives_for_the_platform() {
+    let (app, _) = app();
+
+    // 1.7.10: LWJGL 2.9.1 en todo salvo macOS, que usa la nightly.
+    let osx = json(get(&app, "/version/1.7.10/bundle/osx-x64").await, StatusCode::OK).await;
+    let libraries = names(&osx["libraries"], "name");
+    assert!(libraries.contains(&"org.lwjgl.lwjgl:lwjgl:2.9.2-nightly-20140822".to_string()));
+    assert!(!libraries.contains(&"org.lwjgl.lwjgl:lwjgl:2.9.1".to_string()));
+    assert_eq!(names(&osx["natives"], "classifier"), ["natives-osx", "natives-osx"]);
+
+    // `${arch}` se resuelve según la arquitectura; twitch no existe en Linux.
+    let windows = json(get(&app, "/version/1.7.10/bundle/windows-x86").await, StatusCode::OK).await;
+    assert!(names(&windows["natives"], "classifier").contains(&"natives-windows-32".to_string()));
+    let linux = json(get(&app, "/version/1.7.10/bundle/linux-x64").await, StatusCode::OK).await;
+    assert!(!names(&linux["natives"], "name").contains(&"tv.twitch:twitch-platform:5.16".to_string()));
+
+    // LWJGL 3.3: un jar de natives por SO y arquitectura.
+    let arm = json(get(&app, "/version/1.20.1/bundle/windows-arm64").await, StatusCode::OK).await;
+    let natives: Vec<String> = names(&arm["libraries"], "name")
+        .into_iter()
+        .filter(|name| name.contains(":natives-"))
+        .collect();
+    assert_eq!(natives, ["org.lwjgl:lwjgl:3.3.1:natives-windows-arm64"]);
+    assert_eq!(arm["java_runtime"]["component"], "java-runtime-gamma");
+}
+
+#[tokio::test]
+async fn argument_template_documents_placeholders() {
+    let (app, _) = app();
+    let template = json(get(&app, "/version/1.20.1/arguments/template?platform=linux-x64").await, StatusCode::OK).await;
+
+    assert_eq!(template["placeholders"]["auth_access_token"]["secret"], true);
+    assert_eq!(template["placeholders"]["auth_player_name"]["secret"], false);
+    let library_path = template["jvm"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|arg| arg["raw"] == "-Djava.library.path=${natives_directory}")
+        .unwrap();
+    assert_eq!(library_path["tokens"][0]["kind"], "literal");
+    assert_eq!(library_path["tokens"][1]["name"], "natives_directory");
+}
+
+#[tokio::test]
+async fn keeps_sha256_and_sha512_checksums() {
+    let (app, _) = app();
+    let sha256 = "a".repeat(64);
+    let body = json_value!({
+        "id": "fabric-loader-0.14.21-1.20.1",
+        "mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+        "libraries": [
+            { "name": "net.fabricmc:intermediary:1.20.1", "url": "https://maven.fabricmc.net/", "sha256": sha256.to_uppercase() },
+            { "name": "org.ow2.asm:asm:9.5", "url": "https://maven.fabricmc.net/", "sha1": "b".repeat(40), "sha512": "short" },
+        ],
+    });
+    let version = json(post_json(&app, "/normalize", body).await, StatusCode::OK).await;
+
+    let libraries = version["libraries"].as_array().unwrap();
+    assert_eq!(libraries[0]["checksums"]["sha256"], sha256.as_str());
+    assert!(libraries[0]["sha1"].is_null());
+    // Un hash con la longitud equivocada se descarta.
+    assert!(libraries[1].get("checksums").is_none());
+}
+
+#[test]
+fn diff_compares_every_checksum() {
+    let version = |libraries: Value| {
+        manifestor_core::parse_version_json(&json_value!({ "id": "fabric", "libraries": libraries })).unwrap()
+    };
+    let library = |name: &str, hashes: Value| {
+        let mut library = json_value!({ "name": name, "url": "https://maven.fabricmc.net/" });
+        library.as_object_mut().unwrap().extend(hashes.as_object().unwrap().clone());
+        library
+    };
+    let (a, b, c) = ("a".repeat(64), "b".repeat(64), "c".repeat(40));
+
+    let from = version(json_value!([
+        library("net.fabricmc:intermediary:1.20.1", json_value!({ "sha256": a })),
+        library("net.fabricmc:sponge-mixin:0.12.5", json_value!({ "sha1": c, "sha256": a })),
+        library("org.ow2.asm:asm:9.5", json_value!({ "sha1": c })),
+    ]));
+    let to = version(json_value!([
+        library("net.fabricmc:intermediary:1.20.1", json_value!({ "sha256": b })),
+        library("net.fabricmc:sponge-mixin:0.12.5", json_value!({ "sha1": c, "sha256": b })),
+        library("org.ow2.asm:asm:9.5", json_value!({ "sha1": c, "sha256": b })),
+    ]));
+
+    let diff = manifestor::manifest::diff::diff(from, to);
+    let changed: Vec<&str> = diff.libraries.changed.iter().map(|change| change.to.name.as_str()).collect();
+    // asm solo gana un sha256: el sha1, que es el que comparten, no cambia.
+    assert_eq!(changed, ["net.fabricmc:intermediary:1.20.1", "net.fabricmc:sponge-mixin:0.12.5"]);
+}